@@ -9,37 +9,35 @@ use syncrim::{
 
 fn main() {
     fern_setup();
-    let cs = ComponentStore {
-        store: vec![
-            Add::rc_new(
-                "add",
-                (200.0, 120.0),
-                Input::new("c1", "out"),
-                Input::new("c2", "out"),
-            ),
-            Constant::rc_new("c1", (60.0, 100.0), 3),
-            Constant::rc_new("c2", (60.0, 140.0), 4),
-            Wire::rc_new(
-                "w1",
-                vec![(110.0, 100.0), (180.0, 100.0)],
-                Input::new("c1", "out"),
-            ),
-            Wire::rc_new(
-                "w2",
-                vec![(110.0, 140.0), (180.0, 140.0)],
-                Input::new("c2", "out"),
-            ),
-            Wire::rc_new(
-                "w3",
-                vec![(220.0, 120.0), (260.0, 120.0)],
-                Input::new("add", "out"),
-            ),
-            Probe::rc_new("p1", (270.0, 120.0), Input::new("add", "out")),
-        ],
-    };
+    let cs = ComponentStore::new(vec![
+        Add::arc_new(
+            "add",
+            (200.0, 120.0),
+            Input::new("c1", "out"),
+            Input::new("c2", "out"),
+        ),
+        Constant::arc_new("c1", (60.0, 100.0), 3),
+        Constant::arc_new("c2", (60.0, 140.0), 4),
+        Wire::arc_new(
+            "w1",
+            vec![(110.0, 100.0), (180.0, 100.0)],
+            Input::new("c1", "out"),
+        ),
+        Wire::arc_new(
+            "w2",
+            vec![(110.0, 140.0), (180.0, 140.0)],
+            Input::new("c2", "out"),
+        ),
+        Wire::arc_new(
+            "w3",
+            vec![(220.0, 120.0), (260.0, 120.0)],
+            Input::new("add", "out"),
+        ),
+        Probe::arc_new("p1", (270.0, 120.0), Input::new("add", "out")),
+    ]);
 
     let path = PathBuf::from("add.json");
-    cs.save_file(&path);
+    cs.save_file(&path).unwrap();
 
     #[cfg(feature = "gui-egui")]
     syncrim::gui_egui::gui(cs, &path, Library::default()).ok();
@@ -8,15 +8,13 @@ use syncrim::{
 };
 fn main() {
     fern_setup();
-    let cs = ComponentStore {
-        store: vec![
-            ProbeEdit::rc_new("probe_edit", (100.0, 100.0)),
-            Probe::rc_new("probe", (250.0, 100.0), Input::new("probe_edit", "out")),
-        ],
-    };
+    let cs = ComponentStore::new(vec![
+        ProbeEdit::arc_new("probe_edit", (100.0, 100.0)),
+        Probe::arc_new("probe", (250.0, 100.0), Input::new("probe_edit", "out")),
+    ]);
 
     let path = PathBuf::from("probe_edit.json");
-    cs.save_file(&path);
+    cs.save_file(&path).unwrap();
 
     #[cfg(feature = "gui-egui")]
     syncrim::gui_egui::gui(cs, &path, Library::default()).ok();
@@ -4,12 +4,14 @@ use syncrim::gui_egui::editor::Library;
 use syncrim::{common::ComponentStore, components::*, fern::fern_setup};
 fn main() {
     fern_setup();
-    let cs = ComponentStore {
-        store: vec![ProbeStim::rc_new("stim", (100.0, 100.0), vec![42, 1, 2])],
-    };
+    let cs = ComponentStore::new(vec![ProbeStim::arc_new(
+        "stim",
+        (100.0, 100.0),
+        vec![42, 1, 2],
+    )]);
 
     let path = PathBuf::from("probe_stim.json");
-    cs.save_file(&path);
+    cs.save_file(&path).unwrap();
 
     #[cfg(feature = "gui-egui")]
     syncrim::gui_egui::gui(cs, &path, Library::default()).ok();
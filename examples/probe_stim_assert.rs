@@ -8,20 +8,18 @@ use syncrim::{
 };
 fn main() {
     fern_setup();
-    let cs = ComponentStore {
-        store: vec![
-            ProbeStim::rc_new("stim", (100.0, 100.0), vec![0, 1, 2, 3, 1337]),
-            ProbeAssert::rc_new(
-                "assert",
-                (200.0, 100.0),
-                Input::new("stim", "out"),
-                vec![0, 1, 2, 3, 42],
-            ),
-        ],
-    };
+    let cs = ComponentStore::new(vec![
+        ProbeStim::arc_new("stim", (100.0, 100.0), vec![0, 1, 2, 3, 1337]),
+        ProbeAssert::arc_new(
+            "assert",
+            (200.0, 100.0),
+            Input::new("stim", "out"),
+            vec![0, 1, 2, 3, 42],
+        ),
+    ]);
 
     let path = PathBuf::from("probe_stim_assert.json");
-    cs.save_file(&path);
+    cs.save_file(&path).unwrap();
 
     #[cfg(feature = "gui-egui")]
     syncrim::gui_egui::gui(cs, &path, Library::default()).ok();
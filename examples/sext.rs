@@ -8,26 +8,24 @@ use syncrim::{
 };
 fn main() {
     fern_setup();
-    let cs = ComponentStore {
-        store: vec![
-            Constant::rc_new("c0", (50.0, 110.0), 32768),
-            Wire::rc_new(
-                "w0",
-                vec![(110.0, 110.0), (140.0, 110.0)],
-                Input::new("c0", "out"),
-            ),
-            Sext::rc_new("sxt0", (180.0, 100.0), Input::new("c0", "out"), 16, 24),
-            Wire::rc_new(
-                "w1",
-                vec![(220.0, 100.0), (250.0, 100.0)],
-                Input::new("sxt0", "out"),
-            ),
-            Cross::rc_new("p1", (260.0, 100.0), Input::new("sxt0", "out")),
-        ],
-    };
+    let cs = ComponentStore::new(vec![
+        Constant::arc_new("c0", (50.0, 110.0), 32768),
+        Wire::arc_new(
+            "w0",
+            vec![(110.0, 110.0), (140.0, 110.0)],
+            Input::new("c0", "out"),
+        ),
+        Sext::arc_new("sxt0", (180.0, 100.0), Input::new("c0", "out"), 16, 24),
+        Wire::arc_new(
+            "w1",
+            vec![(220.0, 100.0), (250.0, 100.0)],
+            Input::new("sxt0", "out"),
+        ),
+        Cross::arc_new("p1", (260.0, 100.0), Input::new("sxt0", "out")),
+    ]);
 
     let path = PathBuf::from("sext.json");
-    cs.save_file(&path);
+    cs.save_file(&path).unwrap();
 
     #[cfg(feature = "gui-egui")]
     syncrim::gui_egui::gui(cs, &path, Library::default()).ok();
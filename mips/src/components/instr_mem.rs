@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
-use std::rc::Rc;
+use std::sync::Arc;
 use syncrim::common::{
-    Component, Condition, Input, InputPort, OutputType, Ports, SignalUnsigned, SignalValue,
+    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalUnsigned, SignalValue,
     Simulator,
 };
 
@@ -28,17 +28,23 @@ impl Component for InstrMem {
     fn get_id_ports(&self) -> (String, Ports) {
         (
             self.id.clone(),
-            Ports {
-                inputs: vec![InputPort {
+            Ports::new(
+                vec![&InputPort {
                     port_id: INSTR_MEM_PC_ID.to_string(),
                     input: self.pc.clone(),
                 }],
-                out_type: OutputType::Combinatorial,
-                outputs: vec![INSTR_MEM_OUT_ID.to_string()],
-            },
+                OutputType::Combinatorial,
+                vec![INSTR_MEM_OUT_ID],
+            ),
         )
     }
 
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        if target_port_id == INSTR_MEM_PC_ID {
+            self.pc = new_input;
+        }
+    }
+
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
         let instr: SignalValue =
             match TryInto::<SignalUnsigned>::try_into(simulator.get_input_value(&self.pc)) {
@@ -74,7 +80,7 @@ impl InstrMem {
         }
     }
 
-    pub fn rc_new(id: &str, pos: (f32, f32), pc: Input, instr: Vec<u32>) -> Rc<Self> {
-        Rc::new(InstrMem::new(id, pos, pc, instr))
+    pub fn arc_new(id: &str, pos: (f32, f32), pc: Input, instr: Vec<u32>) -> Arc<Self> {
+        Arc::new(InstrMem::new(id, pos, pc, instr))
     }
 }
@@ -3,3 +3,35 @@ mod reg_file;
 
 pub use instr_mem::*;
 pub use reg_file::*;
+
+use syncrim::registry::{dummy_input, ComponentType};
+
+/// the MIPS component types, register by `syncrim::registry::register_components`
+pub const COMPONENTS: &[ComponentType] = &[
+    ComponentType {
+        name: "InstrMem",
+        category: "MIPS",
+        description: "Instruction memory, of words",
+        library: true,
+        new: |id, pos| InstrMem::arc_new(id, pos, dummy_input(), vec![]),
+    },
+    ComponentType {
+        name: "RegFile",
+        category: "MIPS",
+        description: "Register file, two read ports and a write port",
+        library: true,
+        new: |id, pos| {
+            RegFile::arc_new(
+                id,
+                pos,
+                200.0,
+                300.0,
+                dummy_input(),
+                dummy_input(),
+                dummy_input(),
+                dummy_input(),
+                dummy_input(),
+            )
+        },
+    },
+];
@@ -2,9 +2,9 @@ use log::*;
 use num_enum::TryFromPrimitive;
 use serde::{Deserialize, Serialize};
 use std::ops::{Deref, Range};
-use std::{cell::RefCell, rc::Rc};
+use std::sync::{Arc, RwLock};
 use syncrim::common::{
-    Component, Condition, Input, InputPort, OutputType, Ports, SignalUnsigned, Simulator,
+    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalUnsigned, Simulator,
 };
 
 #[allow(non_camel_case_types)]
@@ -83,12 +83,12 @@ pub struct RegOp {
 }
 // TODO: Perhaps we want registers to be of Signal type (containing potentially Signal::Unknown)
 
-#[derive(Serialize, Deserialize, Clone)]
-pub struct RegHistory(RefCell<Vec<RegOp>>);
+#[derive(Serialize, Deserialize)]
+pub struct RegHistory(RwLock<Vec<RegOp>>);
 
 impl RegHistory {
     pub fn new() -> Self {
-        RegHistory(RefCell::new(Vec::new()))
+        RegHistory(RwLock::new(Vec::new()))
     }
 }
 
@@ -99,11 +99,11 @@ impl Default for RegHistory {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
-pub struct RegStore(pub Rc<RefCell<[u32; 32]>>);
+pub struct RegStore(pub Arc<RwLock<[u32; 32]>>);
 
 impl RegStore {
     pub fn new() -> Self {
-        RegStore(Rc::new(RefCell::new([0; 32])))
+        RegStore(Arc::new(RwLock::new([0; 32])))
     }
 
     pub fn full_range() -> Range<u8> {
@@ -126,7 +126,7 @@ impl Default for RegStore {
 }
 
 impl Deref for RegStore {
-    type Target = RefCell<[u32; 32]>;
+    type Target = RwLock<[u32; 32]>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -166,7 +166,7 @@ impl RegFile {
     }
 
     #[allow(clippy::too_many_arguments)]
-    pub fn rc_new(
+    pub fn arc_new(
         id: &str,
         pos: (f32, f32),
         width: f32,
@@ -176,8 +176,8 @@ impl RegFile {
         write_data: Input,
         write_addr: Input,
         write_enable: Input,
-    ) -> Rc<Self> {
-        Rc::new(RegFile::new(
+    ) -> Arc<Self> {
+        Arc::new(RegFile::new(
             id,
             pos,
             width,
@@ -196,7 +196,7 @@ impl RegFile {
 
         // mips always reads 0;
         if read_addr > 0 {
-            self.registers.borrow()[read_addr as usize]
+            self.registers.read().unwrap()[read_addr as usize]
         } else {
             0
         }
@@ -212,35 +212,46 @@ impl Component for RegFile {
     fn get_id_ports(&self) -> (String, Ports) {
         (
             self.id.clone(),
-            Ports {
-                inputs: vec![
-                    InputPort {
+            Ports::new(
+                vec![
+                    &InputPort {
                         port_id: REG_FILE_READ_ADDR1_ID.to_string(),
                         input: self.read_addr1.clone(),
                     },
-                    InputPort {
+                    &InputPort {
                         port_id: REG_FILE_READ_ADDR2_ID.to_string(),
                         input: self.read_addr2.clone(),
                     },
-                    InputPort {
+                    &InputPort {
                         port_id: REG_FILE_WRITE_DATA_ID.to_string(),
                         input: self.write_data.clone(),
                     },
-                    InputPort {
+                    &InputPort {
                         port_id: REG_FILE_WRITE_ADDR_ID.to_string(),
                         input: self.write_addr.clone(),
                     },
-                    InputPort {
+                    &InputPort {
                         port_id: REG_FILE_WRITE_ENABLE_ID.to_string(),
                         input: self.write_enable.clone(),
                     },
                 ],
-                out_type: OutputType::Combinatorial,
-                outputs: vec!["reg_a".into(), "reg_b".into()],
-            },
+                OutputType::Combinatorial,
+                vec!["reg_a", "reg_b"],
+            ),
         )
     }
 
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        match target_port_id.as_str() {
+            REG_FILE_READ_ADDR1_ID => self.read_addr1 = new_input,
+            REG_FILE_READ_ADDR2_ID => self.read_addr2 = new_input,
+            REG_FILE_WRITE_DATA_ID => self.write_data = new_input,
+            REG_FILE_WRITE_ADDR_ID => self.write_addr = new_input,
+            REG_FILE_WRITE_ENABLE_ID => self.write_enable = new_input,
+            _ => {}
+        }
+    }
+
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
         if simulator.get_input_value(&self.write_enable) == (true as SignalUnsigned).into() {
             let data = simulator.get_input_value(&self.write_data);
@@ -250,7 +261,7 @@ impl Component for RegFile {
                 .try_into()
                 .unwrap();
             trace!("write_addr {}", write_addr);
-            self.registers.borrow_mut()[write_addr as usize] = data.try_into().unwrap();
+            self.registers.write().unwrap()[write_addr as usize] = data.try_into().unwrap();
         }
 
         // read after write
@@ -263,6 +274,18 @@ impl Component for RegFile {
         simulator.set_out_value(&self.id, REG_FILE_REG_B_OUT, reg_value_b);
         Ok(())
     }
+
+    // register contents
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(*self.registers.read().unwrap()).ok()
+    }
+
+    fn load_state(&self, state: serde_json::Value) -> Result<(), String> {
+        *self.registers.write().unwrap() =
+            serde_json::from_value(state).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -271,7 +294,7 @@ impl Component for RegFile {
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::rc::Rc;
+    use std::sync::Arc;
     use syncrim::{
         common::{ComponentStore, Input, Simulator},
         components::ProbeOut,
@@ -280,33 +303,31 @@ mod test {
     // an example of integration test for a mips specific component
     #[test]
     fn test_reg_file() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("read_reg_1")),
-                Rc::new(ProbeOut::new("read_reg_2")),
-                Rc::new(ProbeOut::new("write_data")),
-                Rc::new(ProbeOut::new("write_addr")),
-                Rc::new(ProbeOut::new("write_enable")),
-                // regfile
-                Rc::new(RegFile {
-                    id: "reg_file".to_string(),
-                    pos: (200.0, 150.0),
-                    width: 100.0,
-                    height: 150.0,
-
-                    // ports
-                    read_addr1: Input::new("read_reg_1", "out"),
-                    read_addr2: Input::new("read_reg_2", "out"),
-                    write_data: Input::new("write_data", "out"),
-                    write_addr: Input::new("write_addr", "out"),
-                    write_enable: Input::new("write_enable", "out"),
-
-                    // data
-                    registers: RegStore::new(),
-                    history: RegHistory::new(),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("read_reg_1")),
+            Arc::new(ProbeOut::new("read_reg_2")),
+            Arc::new(ProbeOut::new("write_data")),
+            Arc::new(ProbeOut::new("write_addr")),
+            Arc::new(ProbeOut::new("write_enable")),
+            // regfile
+            Arc::new(RegFile {
+                id: "reg_file".to_string(),
+                pos: (200.0, 150.0),
+                width: 100.0,
+                height: 150.0,
+
+                // ports
+                read_addr1: Input::new("read_reg_1", "out"),
+                read_addr2: Input::new("read_reg_2", "out"),
+                write_data: Input::new("write_data", "out"),
+                write_addr: Input::new("write_addr", "out"),
+                write_enable: Input::new("write_enable", "out"),
+
+                // data
+                registers: RegStore::new(),
+                history: RegHistory::new(),
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
 
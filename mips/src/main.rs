@@ -6,7 +6,8 @@ fn main() {
     fern_setup();
 
     let path = PathBuf::from("mips.json");
-    let cs = ComponentStore::load_file(&path);
+    syncrim::registry::register_components(COMPONENTS);
+    let cs = ComponentStore::load_file(&path).unwrap_or_else(|e| panic!("{}", e));
 
     #[cfg(feature = "gui-vizia")]
     syncrim::gui_vizia::gui(cs, &path);
@@ -2,6 +2,7 @@
 // Useful to assert that illegal models and/or states does not pass unnoticed
 #[test]
 #[should_panic(expected = "assertion failed")]
+#[allow(clippy::assertions_on_constants)]
 fn should_fail() {
     assert!(false)
 }
@@ -0,0 +1,151 @@
+// Benchmark of signal lookup and clocking, using the `riscv.json` model
+//
+// Run with:
+//
+// cargo bench --bench riscv_json
+//
+// Compares `Simulator::get_input_value` as it was before inputs were resolved to
+// indices (cloning the `(id, field)` key for each read) with the current one, and
+// the clock rate of the full model before and now. Components now read their
+// inputs through the indices resolved by `Simulator::new`, instead of looking up
+// each input by `get_input_value`, thus the clock rate before is measured by
+// adding these lookups to each cycle.
+use riscv::components::*;
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use syncrim::common::{ComponentStore, Input, SignalValue, Simulator};
+
+const READ_PASSES: usize = 10_000;
+const CYCLES: usize = 10_000;
+
+// `Simulator::get_input_value` before inputs were resolved to indices
+fn get_input_value_before(simulator: &Simulator, input: &Input) -> SignalValue {
+    let nr_out = *simulator
+        .id_nr_outputs
+        .get(&input.id)
+        .unwrap_or_else(|| panic!("\n{:?} not found in \n{:?}", input, simulator.id_nr_outputs));
+    let index = *simulator
+        .id_field_index
+        .get(&(input.id.clone(), input.field.clone()))
+        .unwrap_or_else(|| {
+            panic!(
+                "Component {:?}, field {:?} not found.",
+                input.id, input.field
+            )
+        });
+    if index < nr_out {
+        let start_index = *simulator.id_start_index.get(&input.id).unwrap();
+        simulator.sim_state[start_index + index].get_value()
+    } else {
+        panic!(
+            "ICE: Attempt to read {:?} at index {}, where {:?} has only {} outputs.",
+            input.id, index, input.id, nr_out
+        )
+    }
+}
+
+fn main() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("riscv.json");
+    syncrim::migration::register_migrations(riscv::components::MIGRATIONS);
+    syncrim::registry::register_components(riscv::components::COMPONENTS);
+    let mut cs = ComponentStore::load_file(&path).unwrap_or_else(|e| panic!("{}", e));
+
+    // load a small program, incrementing x1 forever:
+    // 0x0: addi x1, x1, 1
+    // 0x4: jal x0, -4
+    let mut bytes = BTreeMap::new();
+    for (i, instr) in [0x0010_8093u32, 0xffdf_f06f].iter().enumerate() {
+        for (j, byte) in instr.to_le_bytes().iter().enumerate() {
+            bytes.insert(i * 4 + j, *byte);
+        }
+    }
+    for c in cs.store.iter_mut() {
+        if let Some(instr_mem) = c.as_any().downcast_ref::<InstrMem>() {
+            let mut instr_mem = instr_mem.clone();
+            instr_mem.bytes = bytes.clone();
+            *c = Arc::new(instr_mem);
+        }
+    }
+
+    let inputs: Vec<Input> = cs
+        .store
+        .iter()
+        .flat_map(|c| c.get_id_ports().1.inputs)
+        .map(|port| port.input)
+        .collect();
+
+    let mut simulator = Simulator::new(cs).unwrap();
+    println!(
+        "riscv.json: {} components, {} inputs",
+        simulator.ordered_components.len(),
+        inputs.len()
+    );
+
+    // read as before
+    let now = Instant::now();
+    let mut checksum = 0usize;
+    for _ in 0..READ_PASSES {
+        for input in &inputs {
+            checksum += matches!(
+                get_input_value_before(&simulator, input),
+                SignalValue::Data(_)
+            ) as usize;
+        }
+    }
+    let before = now.elapsed();
+
+    // read as now
+    let now = Instant::now();
+    for _ in 0..READ_PASSES {
+        for input in &inputs {
+            checksum += matches!(simulator.get_input_value(input), SignalValue::Data(_)) as usize;
+        }
+    }
+    let now = now.elapsed();
+
+    let reads = (READ_PASSES * inputs.len()) as f64;
+    println!(
+        "get_input_value, before: {:>8.2} ns/read",
+        before.as_nanos() as f64 / reads
+    );
+    println!(
+        "get_input_value, now:    {:>8.2} ns/read ({:.1}x faster)",
+        now.as_nanos() as f64 / reads,
+        before.as_secs_f64() / now.as_secs_f64()
+    );
+
+    // full model clock rate, with or without looking up the component inputs
+    let mut clock = |lookup: bool| -> Duration {
+        let now = Instant::now();
+        for _ in 0..CYCLES {
+            if lookup {
+                for input in &inputs {
+                    checksum +=
+                        matches!(simulator.get_input_value(input), SignalValue::Data(_)) as usize;
+                }
+            }
+            simulator.clock();
+        }
+        now.elapsed()
+    };
+    let before = clock(true);
+    let now = clock(false);
+    println!(
+        "clock, before: {:>10.0} cycles/s ({} cycles in {:?})",
+        CYCLES as f64 / before.as_secs_f64(),
+        CYCLES,
+        before
+    );
+    println!(
+        "clock, now:    {:>10.0} cycles/s ({} cycles in {:?}, {:.1}x faster)",
+        CYCLES as f64 / now.as_secs_f64(),
+        CYCLES,
+        now,
+        before.as_secs_f64() / now.as_secs_f64()
+    );
+    println!("checksum {}", checksum);
+}
@@ -4,15 +4,14 @@ use fern;
 use riscv::components::*;
 use riscv_elf_parse;
 use std::{
-    cell::RefCell,
     collections::{BTreeMap, HashSet},
     fs,
     ops::Range,
     path::PathBuf,
     process::Command,
-    rc::Rc,
+    sync::Arc,
 };
-use syncrim::common::{ComponentStore, Input};
+use syncrim::common::ComponentStore;
 
 #[derive(Parser, Debug)]
 struct Args {
@@ -78,7 +77,9 @@ fn main() {
         }
     }
     let path = PathBuf::from("riscv.json");
-    let mut cs = ComponentStore::load_file(&path);
+    syncrim::migration::register_migrations(riscv::components::MIGRATIONS);
+    syncrim::registry::register_components(riscv::components::COMPONENTS);
+    let mut cs = ComponentStore::load_file(&path).unwrap_or_else(|e| panic!("{}", e));
     let mut i = 0;
     let mut store = cs.store.clone();
     for component in store.clone() {
@@ -97,7 +98,7 @@ fn main() {
             // also, set the initial state for reset
             data_mem_comp.init_state = data_mem;
             // repush the mutated RVMem to the Component vector
-            store.push(Rc::new(data_mem_comp));
+            store.push(Arc::new(data_mem_comp));
             //satisfy borrow checker
             break;
         }
@@ -120,7 +121,7 @@ fn main() {
             // replace the symbols with ELF symbols
             instr_mem_comp.symbols = memory.symbols;
             // repush the mutated InstrMem to the Component vector
-            store.push(Rc::new(instr_mem_comp));
+            store.push(Arc::new(instr_mem_comp));
             //satisfy borrow checker
             break;
         }
@@ -129,99 +130,9 @@ fn main() {
     cs.store = store;
     #[cfg(feature = "gui-egui")]
     {
-        use std::collections::HashMap;
-        let dummy = Input::new("id", "field");
-        let lib = ComponentStore {
-            store: vec![
-                Rc::new(InstrMem {
-                    width: INSTR_MEM_WIDTH,
-                    height: INSTR_MEM_HEIGHT,
-                    id: "dummy_instr_mem".to_string(),
-                    pos: (0.0, 0.0),
-                    pc: dummy.clone(),
-                    bytes: BTreeMap::new(),
-                    range: Range {
-                        start: 0,
-                        end: 0x1000,
-                    },
-                    breakpoints: Rc::new(RefCell::new(HashSet::new())),
-                    symbols: HashMap::new(),
-                    le: true,
-                }),
-                Rc::new(ALU {
-                    id: "dummy_alu".to_string(),
-                    pos: (0.0, 0.0),
-                    operator_i: dummy.clone(),
-                    operand_a_i: dummy.clone(),
-                    operand_b_i: dummy.clone(),
-                }),
-                Rc::new(BranchLogic {
-                    width: BRANCH_LOGIC_WIDTH,
-                    height: BRANCH_LOGIC_HEIGHT,
-                    id: "dummy_blu".to_string(),
-                    pos: (0.0, 0.0),
-                    rs1: dummy.clone(),
-                    rs2: dummy.clone(),
-                    ctrl: dummy.clone(),
-                    enable: dummy.clone(),
-                    // int: dummy.clone(),
-                    // mret: dummy.clone(),
-                }),
-                Rc::new(Decoder {
-                    width: DECODER_WIDTH,
-                    height: DECODER_HEIGHT,
-                    id: "dummy_decoder".to_string(),
-                    pos: (0.0, 0.0),
-                    instruction: dummy.clone(),
-                }),
-                Rc::new(LSBZero {
-                    height: LSB_ZERO_HEIGHT,
-                    width: LSB_ZERO_WIDTH,
-                    id: "dummy_lsbzero".to_string(),
-                    pos: (0.0, 0.0),
-                    data_i: dummy.clone(),
-                }),
-                Rc::new(RegFile::dummy()),
-                Rc::new(SZExt {
-                    height: SIGN_ZERO_EXT_HEIGHT,
-                    width: SIGN_ZERO_EXT_WIDTH,
-                    id: "dummy_szext".to_string(),
-                    pos: (0.0, 0.0),
-                    data_i: dummy.clone(),
-                    sel_i: dummy.clone(),
-                }),
-                Rc::new(WBCtl {
-                    height: WB_CTL_HEIGHT,
-                    width: WB_CTL_WIDTH,
-                    id: "dummy_wbctl".to_string(),
-                    pos: (0.0, 0.0),
-                    clic_i: dummy.clone(),
-                    dec_i: dummy.clone(),
-                }),
-                Rc::new(GPIO {
-                    height: GPIO_HEIGHT,
-                    width: GPIO_WIDTH,
-                    id: "dummy_gpio".to_string(),
-                    pos: (0.0, 0.0),
-                    data_i: dummy.clone(),
-                    addr_i: dummy.clone(),
-                    size_i: dummy.clone(),
-                    we_i: dummy.clone(),
-                    se_i: dummy.clone(),
-                    csr_d: dummy.clone(),
-                    csr_a: dummy.clone(),
-                    csr_ctl: dummy.clone(),
-                    csrstore: GPIOCsrStore::default(),
-                    pins: Pins::default(),
-                    memory: Memory::default(),
-                }),
-                ProbeLabel::rc_new("dummy_labeled_probe", (0.0, 0.0), dummy.clone()),
-            ],
-        };
-        let mut component_vec = lib.store.clone();
-        component_vec.append(&mut syncrim::gui_egui::editor::Library::default().0.clone());
-        let _ =
-            syncrim::gui_egui::gui(cs, &path, syncrim::gui_egui::editor::Library(component_vec));
+        // the library of the editor, with the registered RISC-V components
+        let library = syncrim::gui_egui::editor::Library::default();
+        let _ = syncrim::gui_egui::gui(cs, &path, library);
     }
 
     #[cfg(feature = "gui-vizia")]
@@ -1,7 +1,7 @@
 use log::trace;
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "gui-egui")]
-use std::rc::Rc;
+use std::sync::Arc;
 #[cfg(feature = "gui-egui")]
 use syncrim::common::EguiComponent;
 use syncrim::{
@@ -34,15 +34,15 @@ impl Component for ALU {
         println!("ALU");
     }
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
         let dummy = Input::new("dummy", "out");
-        Box::new(Rc::new(ALU {
+        Some(Box::new(Arc::new(ALU {
             id: id.to_string(),
             pos: (pos.0, pos.1),
             operator_i: dummy.clone(),
             operand_a_i: dummy.clone(),
             operand_b_i: dummy.clone(),
-        }))
+        })))
     }
 
     fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
@@ -86,22 +86,21 @@ impl Component for ALU {
 
     #[allow(non_snake_case)]
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
-        let operator_i = match simulator.get_input_value(&self.operator_i) {
+        let operator_i = match simulator.get_port_value(0) {
             SignalValue::Data(data) => data,
             _ => {
                 simulator.set_out_value(&self.id, ALU_RESULT_O_ID, SignalValue::Unknown);
                 return Ok(());
             }
         };
-        //if i is set, these two must be set or panic is reasonable.
-        let operand_a_i: u32 = simulator
-            .get_input_value(&self.operand_a_i)
-            .try_into()
-            .unwrap();
-        let operand_b_i: u32 = simulator
-            .get_input_value(&self.operand_b_i)
-            .try_into()
-            .unwrap();
+        // unknown operands yield an unknown result
+        let (Ok(operand_a_i), Ok(operand_b_i)) = (
+            u32::try_from(simulator.get_port_value(1)),
+            u32::try_from(simulator.get_port_value(2)),
+        ) else {
+            simulator.set_out_value(&self.id, ALU_RESULT_O_ID, SignalValue::Unknown);
+            return Ok(());
+        };
         trace!("ALU operand A: {}, operand B:{}", operand_a_i, operand_b_i);
         let mut result_o = 0;
         match operator_i {
@@ -176,6 +175,10 @@ impl Component for ALU {
         Ok(())
     }
 
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -185,7 +188,7 @@ impl Component for ALU {
 mod test {
     use super::*;
 
-    use std::rc::Rc;
+    use std::sync::Arc;
     use syncrim::{
         common::{ComponentStore, Input, Simulator},
         components::ProbeOut,
@@ -193,20 +196,18 @@ mod test {
     #[allow(arithmetic_overflow)]
     #[test]
     fn test_alu() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("operator_i")),
-                Rc::new(ProbeOut::new("operand_a_i")),
-                Rc::new(ProbeOut::new("operand_b_i")),
-                Rc::new(ALU {
-                    id: "alu".to_string(),
-                    pos: (0.0, 0.0),
-                    operator_i: Input::new("operator_i", "out"),
-                    operand_a_i: Input::new("operand_a_i", "out"),
-                    operand_b_i: Input::new("operand_b_i", "out"),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("operator_i")),
+            Arc::new(ProbeOut::new("operand_a_i")),
+            Arc::new(ProbeOut::new("operand_b_i")),
+            Arc::new(ALU {
+                id: "alu".to_string(),
+                pos: (0.0, 0.0),
+                operator_i: Input::new("operator_i", "out"),
+                operand_a_i: Input::new("operand_a_i", "out"),
+                operand_b_i: Input::new("operand_b_i", "out"),
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
         assert_eq!(simulator.cycle, 1);
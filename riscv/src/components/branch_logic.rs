@@ -1,7 +1,7 @@
 use log::trace;
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "gui-egui")]
-use std::rc::Rc;
+use std::sync::Arc;
 #[cfg(feature = "gui-egui")]
 use syncrim::common::EguiComponent;
 use syncrim::common::{
@@ -41,9 +41,9 @@ impl Component for BranchLogic {
     }
 
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
         let dummy = Input::new("dummy", "out");
-        Box::new(Rc::new(BranchLogic {
+        Some(Box::new(Arc::new(BranchLogic {
             width: 60.0,
             height: 60.0,
             id: id.to_string(),
@@ -54,7 +54,7 @@ impl Component for BranchLogic {
             enable: dummy.clone(),
             // mret: dummy.clone(),
             // int: dummy.clone(),
-        }))
+        })))
     }
 
     fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
@@ -102,15 +102,16 @@ impl Component for BranchLogic {
 
     #[allow(non_snake_case)]
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
-        let enable: u32 = simulator.get_input_value(&self.enable).try_into().unwrap();
+        let enable: u32 = simulator.get_port_value(3).try_into().unwrap();
         let out: SignalValue;
-        let rs1: SignalValue = simulator.get_input_value(&self.rs1);
-        let rs2: SignalValue = simulator.get_input_value(&self.rs2);
+        let rs1: SignalValue = simulator.get_port_value(0);
+        let rs2: SignalValue = simulator.get_port_value(1);
         if enable != 0 {
-            match simulator.get_input_value(&self.ctrl) {
-                SignalValue::Unknown | SignalValue::DontCare | SignalValue::Uninitialized => {
-                    out = SignalValue::Unknown
-                }
+            match simulator.get_port_value(2) {
+                SignalValue::Unknown
+                | SignalValue::DontCare
+                | SignalValue::Uninitialized
+                | SignalValue::Wide { .. } => out = SignalValue::Unknown,
                 SignalValue::Data(ctrl) => {
                     match ctrl {
                         0b000 => {
@@ -202,6 +203,10 @@ impl Component for BranchLogic {
         Ok(())
     }
 
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -210,7 +215,7 @@ impl Component for BranchLogic {
 mod test {
     use super::*;
 
-    use std::rc::Rc;
+    use std::sync::Arc;
     use syncrim::{
         common::{ComponentStore, Input, Simulator},
         components::ProbeOut,
@@ -218,28 +223,26 @@ mod test {
 
     #[test]
     fn test_beq() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("rs1")),
-                Rc::new(ProbeOut::new("rs2")),
-                Rc::new(ProbeOut::new("ctrl")),
-                Rc::new(ProbeOut::new("enable")),
-                Rc::new(ProbeOut::new("int")),
-                Rc::new(ProbeOut::new("mret")),
-                Rc::new(BranchLogic {
-                    width: 0.0,
-                    height: 0.0,
-                    id: "blu".to_string(),
-                    pos: (0.0, 0.0),
-                    rs1: Input::new("rs1", "out"),
-                    rs2: Input::new("rs2", "out"),
-                    ctrl: Input::new("ctrl", "out"),
-                    enable: Input::new("enable", "out"),
-                    // int: Input::new("int", "out"),
-                    // mret: Input::new("mret", "out"),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("rs1")),
+            Arc::new(ProbeOut::new("rs2")),
+            Arc::new(ProbeOut::new("ctrl")),
+            Arc::new(ProbeOut::new("enable")),
+            Arc::new(ProbeOut::new("int")),
+            Arc::new(ProbeOut::new("mret")),
+            Arc::new(BranchLogic {
+                width: 0.0,
+                height: 0.0,
+                id: "blu".to_string(),
+                pos: (0.0, 0.0),
+                rs1: Input::new("rs1", "out"),
+                rs2: Input::new("rs2", "out"),
+                ctrl: Input::new("ctrl", "out"),
+                enable: Input::new("enable", "out"),
+                // int: Input::new("int", "out"),
+                // mret: Input::new("mret", "out"),
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
         assert_eq!(simulator.cycle, 1);
@@ -279,28 +282,26 @@ mod test {
     }
     #[test]
     fn test_bne() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("rs1")),
-                Rc::new(ProbeOut::new("rs2")),
-                Rc::new(ProbeOut::new("ctrl")),
-                Rc::new(ProbeOut::new("enable")),
-                Rc::new(ProbeOut::new("int")),
-                Rc::new(ProbeOut::new("mret")),
-                Rc::new(BranchLogic {
-                    width: 0.0,
-                    height: 0.0,
-                    id: "blu".to_string(),
-                    pos: (0.0, 0.0),
-                    rs1: Input::new("rs1", "out"),
-                    rs2: Input::new("rs2", "out"),
-                    ctrl: Input::new("ctrl", "out"),
-                    enable: Input::new("enable", "out"),
-                    // int: Input::new("int", "out"),
-                    // mret: Input::new("mret", "out"),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("rs1")),
+            Arc::new(ProbeOut::new("rs2")),
+            Arc::new(ProbeOut::new("ctrl")),
+            Arc::new(ProbeOut::new("enable")),
+            Arc::new(ProbeOut::new("int")),
+            Arc::new(ProbeOut::new("mret")),
+            Arc::new(BranchLogic {
+                width: 0.0,
+                height: 0.0,
+                id: "blu".to_string(),
+                pos: (0.0, 0.0),
+                rs1: Input::new("rs1", "out"),
+                rs2: Input::new("rs2", "out"),
+                ctrl: Input::new("ctrl", "out"),
+                enable: Input::new("enable", "out"),
+                // int: Input::new("int", "out"),
+                // mret: Input::new("mret", "out"),
+            }),
+        ]);
         let mut simulator = Simulator::new(cs).unwrap();
         assert_eq!(simulator.cycle, 1);
 
@@ -339,28 +340,26 @@ mod test {
     }
     #[test]
     fn test_blt() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("rs1")),
-                Rc::new(ProbeOut::new("rs2")),
-                Rc::new(ProbeOut::new("ctrl")),
-                Rc::new(ProbeOut::new("enable")),
-                Rc::new(ProbeOut::new("int")),
-                Rc::new(ProbeOut::new("mret")),
-                Rc::new(BranchLogic {
-                    width: 0.0,
-                    height: 0.0,
-                    id: "blu".to_string(),
-                    pos: (0.0, 0.0),
-                    rs1: Input::new("rs1", "out"),
-                    rs2: Input::new("rs2", "out"),
-                    ctrl: Input::new("ctrl", "out"),
-                    enable: Input::new("enable", "out"),
-                    // int: Input::new("int", "out"),
-                    // mret: Input::new("mret", "out"),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("rs1")),
+            Arc::new(ProbeOut::new("rs2")),
+            Arc::new(ProbeOut::new("ctrl")),
+            Arc::new(ProbeOut::new("enable")),
+            Arc::new(ProbeOut::new("int")),
+            Arc::new(ProbeOut::new("mret")),
+            Arc::new(BranchLogic {
+                width: 0.0,
+                height: 0.0,
+                id: "blu".to_string(),
+                pos: (0.0, 0.0),
+                rs1: Input::new("rs1", "out"),
+                rs2: Input::new("rs2", "out"),
+                ctrl: Input::new("ctrl", "out"),
+                enable: Input::new("enable", "out"),
+                // int: Input::new("int", "out"),
+                // mret: Input::new("mret", "out"),
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
         assert_eq!(simulator.cycle, 1);
@@ -407,28 +406,26 @@ mod test {
     }
     #[test]
     fn test_bge() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("rs1")),
-                Rc::new(ProbeOut::new("rs2")),
-                Rc::new(ProbeOut::new("ctrl")),
-                Rc::new(ProbeOut::new("enable")),
-                Rc::new(ProbeOut::new("int")),
-                Rc::new(ProbeOut::new("mret")),
-                Rc::new(BranchLogic {
-                    width: 0.0,
-                    height: 0.0,
-                    id: "blu".to_string(),
-                    pos: (0.0, 0.0),
-                    rs1: Input::new("rs1", "out"),
-                    rs2: Input::new("rs2", "out"),
-                    ctrl: Input::new("ctrl", "out"),
-                    enable: Input::new("enable", "out"),
-                    // int: Input::new("int", "out"),
-                    // mret: Input::new("mret", "out"),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("rs1")),
+            Arc::new(ProbeOut::new("rs2")),
+            Arc::new(ProbeOut::new("ctrl")),
+            Arc::new(ProbeOut::new("enable")),
+            Arc::new(ProbeOut::new("int")),
+            Arc::new(ProbeOut::new("mret")),
+            Arc::new(BranchLogic {
+                width: 0.0,
+                height: 0.0,
+                id: "blu".to_string(),
+                pos: (0.0, 0.0),
+                rs1: Input::new("rs1", "out"),
+                rs2: Input::new("rs2", "out"),
+                ctrl: Input::new("ctrl", "out"),
+                enable: Input::new("enable", "out"),
+                // int: Input::new("int", "out"),
+                // mret: Input::new("mret", "out"),
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
         assert_eq!(simulator.cycle, 1);
@@ -475,28 +472,26 @@ mod test {
     }
     #[test]
     fn test_bltu() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("rs1")),
-                Rc::new(ProbeOut::new("rs2")),
-                Rc::new(ProbeOut::new("ctrl")),
-                Rc::new(ProbeOut::new("enable")),
-                Rc::new(ProbeOut::new("int")),
-                Rc::new(ProbeOut::new("mret")),
-                Rc::new(BranchLogic {
-                    width: 0.0,
-                    height: 0.0,
-                    id: "blu".to_string(),
-                    pos: (0.0, 0.0),
-                    rs1: Input::new("rs1", "out"),
-                    rs2: Input::new("rs2", "out"),
-                    ctrl: Input::new("ctrl", "out"),
-                    enable: Input::new("enable", "out"),
-                    // int: Input::new("int", "out"),
-                    // mret: Input::new("mret", "out"),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("rs1")),
+            Arc::new(ProbeOut::new("rs2")),
+            Arc::new(ProbeOut::new("ctrl")),
+            Arc::new(ProbeOut::new("enable")),
+            Arc::new(ProbeOut::new("int")),
+            Arc::new(ProbeOut::new("mret")),
+            Arc::new(BranchLogic {
+                width: 0.0,
+                height: 0.0,
+                id: "blu".to_string(),
+                pos: (0.0, 0.0),
+                rs1: Input::new("rs1", "out"),
+                rs2: Input::new("rs2", "out"),
+                ctrl: Input::new("ctrl", "out"),
+                enable: Input::new("enable", "out"),
+                // int: Input::new("int", "out"),
+                // mret: Input::new("mret", "out"),
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
         assert_eq!(simulator.cycle, 1);
@@ -543,28 +538,26 @@ mod test {
     }
     #[test]
     fn test_bgeu() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("rs1")),
-                Rc::new(ProbeOut::new("rs2")),
-                Rc::new(ProbeOut::new("ctrl")),
-                Rc::new(ProbeOut::new("enable")),
-                Rc::new(ProbeOut::new("int")),
-                Rc::new(ProbeOut::new("mret")),
-                Rc::new(BranchLogic {
-                    width: 0.0,
-                    height: 0.0,
-                    id: "blu".to_string(),
-                    pos: (0.0, 0.0),
-                    rs1: Input::new("rs1", "out"),
-                    rs2: Input::new("rs2", "out"),
-                    ctrl: Input::new("ctrl", "out"),
-                    enable: Input::new("enable", "out"),
-                    // int: Input::new("int", "out"),
-                    // mret: Input::new("mret", "out"),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("rs1")),
+            Arc::new(ProbeOut::new("rs2")),
+            Arc::new(ProbeOut::new("ctrl")),
+            Arc::new(ProbeOut::new("enable")),
+            Arc::new(ProbeOut::new("int")),
+            Arc::new(ProbeOut::new("mret")),
+            Arc::new(BranchLogic {
+                width: 0.0,
+                height: 0.0,
+                id: "blu".to_string(),
+                pos: (0.0, 0.0),
+                rs1: Input::new("rs1", "out"),
+                rs2: Input::new("rs2", "out"),
+                ctrl: Input::new("ctrl", "out"),
+                enable: Input::new("enable", "out"),
+                // int: Input::new("int", "out"),
+                // mret: Input::new("mret", "out"),
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
         assert_eq!(simulator.cycle, 1);
@@ -618,28 +611,26 @@ mod test {
     }
     #[test]
     fn test_jalr() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("rs1")),
-                Rc::new(ProbeOut::new("rs2")),
-                Rc::new(ProbeOut::new("ctrl")),
-                Rc::new(ProbeOut::new("enable")),
-                Rc::new(ProbeOut::new("int")),
-                Rc::new(ProbeOut::new("mret")),
-                Rc::new(BranchLogic {
-                    width: 0.0,
-                    height: 0.0,
-                    id: "blu".to_string(),
-                    pos: (0.0, 0.0),
-                    rs1: Input::new("rs1", "out"),
-                    rs2: Input::new("rs2", "out"),
-                    ctrl: Input::new("ctrl", "out"),
-                    enable: Input::new("enable", "out"),
-                    // int: Input::new("int", "out"),
-                    // mret: Input::new("mret", "out"),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("rs1")),
+            Arc::new(ProbeOut::new("rs2")),
+            Arc::new(ProbeOut::new("ctrl")),
+            Arc::new(ProbeOut::new("enable")),
+            Arc::new(ProbeOut::new("int")),
+            Arc::new(ProbeOut::new("mret")),
+            Arc::new(BranchLogic {
+                width: 0.0,
+                height: 0.0,
+                id: "blu".to_string(),
+                pos: (0.0, 0.0),
+                rs1: Input::new("rs1", "out"),
+                rs2: Input::new("rs2", "out"),
+                ctrl: Input::new("ctrl", "out"),
+                enable: Input::new("enable", "out"),
+                // int: Input::new("int", "out"),
+                // mret: Input::new("mret", "out"),
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
         assert_eq!(simulator.cycle, 1);
@@ -663,28 +654,26 @@ mod test {
     }
     #[test]
     fn test_jal() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("rs1")),
-                Rc::new(ProbeOut::new("rs2")),
-                Rc::new(ProbeOut::new("ctrl")),
-                Rc::new(ProbeOut::new("enable")),
-                Rc::new(ProbeOut::new("int")),
-                Rc::new(ProbeOut::new("mret")),
-                Rc::new(BranchLogic {
-                    width: 0.0,
-                    height: 0.0,
-                    id: "blu".to_string(),
-                    pos: (0.0, 0.0),
-                    rs1: Input::new("rs1", "out"),
-                    rs2: Input::new("rs2", "out"),
-                    ctrl: Input::new("ctrl", "out"),
-                    enable: Input::new("enable", "out"),
-                    // int: Input::new("int", "out"),
-                    // mret: Input::new("mret", "out"),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("rs1")),
+            Arc::new(ProbeOut::new("rs2")),
+            Arc::new(ProbeOut::new("ctrl")),
+            Arc::new(ProbeOut::new("enable")),
+            Arc::new(ProbeOut::new("int")),
+            Arc::new(ProbeOut::new("mret")),
+            Arc::new(BranchLogic {
+                width: 0.0,
+                height: 0.0,
+                id: "blu".to_string(),
+                pos: (0.0, 0.0),
+                rs1: Input::new("rs1", "out"),
+                rs2: Input::new("rs2", "out"),
+                ctrl: Input::new("ctrl", "out"),
+                enable: Input::new("enable", "out"),
+                // int: Input::new("int", "out"),
+                // mret: Input::new("mret", "out"),
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
         assert_eq!(simulator.cycle, 1);
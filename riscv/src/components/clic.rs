@@ -1,6 +1,7 @@
 use log::trace;
 use serde::{Deserialize, Serialize};
 use syncrim::common::InputPort;
+use syncrim::parameter::{input_parameters, set_input_parameter, Parameter, ParameterValue};
 use syncrim::{
     common::{Component, Condition, Id, Input, OutputType, Ports, Simulator},
     signal::{SignalSigned, SignalUnsigned, SignalValue},
@@ -8,7 +9,7 @@ use syncrim::{
 
 use priority_queue::PriorityQueue;
 
-use std::{cell::RefCell, collections::HashMap};
+use std::{collections::HashMap, sync::RwLock};
 const CLIC_TIMESTAMP_BASE: u32 = 0xB40;
 const CLIC_TIMESTAMP_PRESCALER: usize = 0x0;
 pub const CLIC_CSR_ADDR_ID: &str = "csr_addr";
@@ -37,7 +38,7 @@ pub const CLIC_STACK_DEPTH_OUT_ID: &str = "stack_depth_out";
 pub const TIMER_WIDTH: u32 = 16;
 pub const TIMER_PRES_WIDTH: u32 = 4;
 pub const TIMER_ADDR: u32 = 0x400;
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 struct CLICOp {
     pub mmio_op: Option<([u32; 2], u32)>,
     pub csr_op: Option<Vec<(usize, u32)>>,
@@ -76,22 +77,22 @@ pub struct CLIC {
 
     //internal state
     #[serde(skip)]
-    pub csrstore: RefCell<HashMap<usize, usize>>, //address, val
+    pub csrstore: RwLock<HashMap<usize, usize>>, //address, val
     #[serde(skip)]
-    pub mmio: RefCell<HashMap<usize, u8>>, //address, val
+    pub mmio: RwLock<HashMap<usize, u8>>, //address, val
     #[serde(skip)]
-    pub queue: RefCell<PriorityQueue<u32, u8>>, //prio, id's
+    pub queue: RwLock<PriorityQueue<u32, u8>>, //prio, id's
     #[serde(skip)]
-    pub clic_stack: RefCell<Vec<(u32, u32)>>,
+    pub clic_stack: RwLock<Vec<(u32, u32)>>,
     #[serde(skip)]
-    pub mtime: RefCell<u64>,
+    pub mtime: RwLock<u64>,
     #[serde(skip)]
-    pub monotonic: RefCell<u64>,
+    pub monotonic: RwLock<u64>,
     #[serde(skip)]
-    pub mtimecomp: RefCell<u64>,
+    pub mtimecomp: RwLock<u64>,
     // #[serde(skip)]
-    // pub stack_depth: RefCell<u32>, //current register stack depth
-    history: RefCell<Vec<CLICOp>>,
+    // pub stack_depth: RwLock<u32>, //current register stack depth
+    history: RwLock<Vec<CLICOp>>,
 }
 #[derive(Serialize, Deserialize, Copy, Clone, Debug)]
 pub struct MMIOEntry {
@@ -204,9 +205,9 @@ impl CLIC {
                 for i in 0xB00..0xBC0 {
                     csrstore.insert(i, 0); //set up individual interrupt config CSRs
                 }
-                RefCell::new(csrstore)
+                RwLock::new(csrstore)
             },
-            monotonic: RefCell::new(0),
+            monotonic: RwLock::new(0),
             mmio: {
                 let mut mmio = HashMap::new();
                 for i in 0x1000..0x10C0 {
@@ -214,13 +215,13 @@ impl CLIC {
                     //continuous CSR range
                     mmio.insert(i, 0);
                 }
-                RefCell::new(mmio)
+                RwLock::new(mmio)
             },
-            queue: RefCell::new(PriorityQueue::new()),
+            queue: RwLock::new(PriorityQueue::new()),
             // lines: lines,
             csr_ctl,
-            clic_stack: RefCell::new(Vec::new()),
-            history: RefCell::new(vec![]),
+            clic_stack: RwLock::new(Vec::new()),
+            history: RwLock::new(vec![]),
             mtime: 0.into(),
             mtimecomp: 0.into(),
             // stack_depth: 0.into(),
@@ -228,10 +229,26 @@ impl CLIC {
     }
 }
 
+// internal state, as saved by `save_state`, checkpoints keep the history length only
+#[derive(Serialize, Deserialize)]
+struct CLICState {
+    csrstore: HashMap<usize, usize>,
+    mmio: HashMap<usize, u8>,
+    queue: PriorityQueue<u32, u8>,
+    clic_stack: Vec<(u32, u32)>,
+    mtime: u64,
+    monotonic: u64,
+    mtimecomp: u64,
+    #[serde(default)]
+    history_len: usize,
+    #[serde(default)]
+    history: Option<Vec<CLICOp>>,
+}
+
 #[typetag::serde()]
 impl Component for CLIC {
     fn reset(&self) {
-        self.csrstore.swap({
+        *self.csrstore.write().unwrap() = {
             let mut csrstore = HashMap::new();
             csrstore.insert(0x300, 0); //mstatus
             csrstore.insert(0x305, 0b11); //mtvec, we only support vectored
@@ -255,19 +272,19 @@ impl Component for CLIC {
             for i in 0xD00..=0xDBF {
                 csrstore.insert(i, 0); //set up timestamping CSRs
             }
-            &RefCell::new(csrstore)
-        });
-        self.mmio.swap({
+            csrstore
+        };
+        *self.mmio.write().unwrap() = {
             let mut mmio = HashMap::new();
             for i in 0x1000..0x5010 {
                 mmio.insert(i, 0);
             }
-            &RefCell::new(mmio)
-        });
-        self.queue.swap(&RefCell::new(PriorityQueue::new()));
-        self.history.swap(&RefCell::new(vec![]));
-        self.clic_stack.swap(&RefCell::new(Vec::new()));
-        self.monotonic.swap(&RefCell::new(0));
+            mmio
+        };
+        *self.queue.write().unwrap() = PriorityQueue::new();
+        *self.history.write().unwrap() = vec![];
+        *self.clic_stack.write().unwrap() = Vec::new();
+        *self.monotonic.write().unwrap() = 0;
     }
 
     fn to_(&self) {
@@ -284,6 +301,7 @@ impl Component for CLIC {
             CLIC_DATA_WE_ID => self.data_we = new_input,
             CLIC_MRET_ID => self.mret = new_input,
             CLIC_PC_ID => self.pc = new_input,
+            CLIC_PC_NEXT_ID => self.pc_next = new_input,
             CLIC_DATA_SIZE_ID => self.data_size = new_input,
             _ => (),
         }
@@ -355,37 +373,16 @@ impl Component for CLIC {
 
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
         //get inputs
-        let csr_ctl: u32 = simulator
-            .get_input_value(&self.csr_ctl)
-            .try_into()
-            .unwrap_or(0);
-        let csr_addr: u32 = simulator
-            .get_input_value(&self.csr_addr)
-            .try_into()
-            .unwrap_or(0);
-        let csr_data: u32 = simulator
-            .get_input_value(&self.csr_data)
-            .try_into()
-            .unwrap_or(0);
-        let mmio_addr: u32 = simulator
-            .get_input_value(&self.addr)
-            .try_into()
-            .unwrap_or(0);
-        let mmio_data: u32 = simulator
-            .get_input_value(&self.data)
-            .try_into()
-            .unwrap_or(0);
-        let mret: u32 = simulator
-            .get_input_value(&self.mret)
-            .try_into()
-            .unwrap_or(0);
-        let pc: u32 = simulator.get_input_value(&self.pc).try_into().unwrap();
-        let pc_next: u32 = simulator.get_input_value(&self.pc_next).try_into().unwrap();
-        let data_size: u32 = simulator
-            .get_input_value(&self.data_size)
-            .try_into()
-            .unwrap_or(0);
-        let mmio_we: u32 = simulator.get_input_value(&self.data_we).try_into().unwrap();
+        let csr_ctl: u32 = simulator.get_port_value(1).try_into().unwrap_or(0);
+        let csr_addr: u32 = simulator.get_port_value(0).try_into().unwrap_or(0);
+        let csr_data: u32 = simulator.get_port_value(2).try_into().unwrap_or(0);
+        let mmio_addr: u32 = simulator.get_port_value(4).try_into().unwrap_or(0);
+        let mmio_data: u32 = simulator.get_port_value(3).try_into().unwrap_or(0);
+        let mret: u32 = simulator.get_port_value(6).try_into().unwrap_or(0);
+        let pc: u32 = simulator.get_port_value(7).try_into().unwrap();
+        let pc_next: u32 = simulator.get_port_value(8).try_into().unwrap();
+        let data_size: u32 = simulator.get_port_value(9).try_into().unwrap_or(0);
+        let mmio_we: u32 = simulator.get_port_value(5).try_into().unwrap();
 
         // define outputs
         let csr_out;
@@ -398,13 +395,13 @@ impl Component for CLIC {
 
         // get state
         //csr store
-        let mut csrstore = self.csrstore.borrow_mut();
+        let mut csrstore = self.csrstore.write().unwrap();
         // operation history for reversing
-        let mut history = self.history.borrow_mut();
+        let mut history = self.history.write().unwrap();
         // interrupt priority queue
-        let mut queue = self.queue.borrow_mut();
+        let mut queue = self.queue.write().unwrap();
         // super-clic threshold/return address stack
-        let mut clic_stack = self.clic_stack.borrow_mut();
+        let mut clic_stack = self.clic_stack.write().unwrap();
 
         // init a history entry for this cycle
         let mut history_entry = CLICOp {
@@ -414,7 +411,7 @@ impl Component for CLIC {
         };
         //dispatched interrupt id, used to unpend in csr store
         let mut dispatched_interrupt_id = None;
-        let mut monotonic = self.monotonic.borrow_mut();
+        let mut monotonic = self.monotonic.write().unwrap();
         *monotonic += 1;
         // handle CSR op if there was any
         csr_out = self.csr_op(
@@ -492,7 +489,7 @@ impl Component for CLIC {
             &mut csrstore,
         );
 
-        let mut mtime = self.mtime.borrow_mut();
+        let mut mtime = self.mtime.write().unwrap();
         let timer_t: TimerCSR = (*csrstore.get(&(TIMER_ADDR as usize)).unwrap_or(&0) as u32).into();
         let mtimecomp = timer_t.counter_top;
         if *mtime << timer_t.prescaler >= mtimecomp as u64 {
@@ -686,10 +683,10 @@ impl Component for CLIC {
 
     fn un_clock(&self) {
         // TODO: Add super-clic stack ops
-        let mut entry = self.history.borrow_mut().pop().unwrap();
+        let mut entry = self.history.write().unwrap().pop().unwrap();
         if let Some(mut ops) = entry.csr_op {
             while let Some(op) = ops.pop() {
-                self.csrstore.borrow_mut().insert(op.0, op.1 as usize);
+                self.csrstore.write().unwrap().insert(op.0, op.1 as usize);
             }
         }
         if let Some(op) = entry.mmio_op {
@@ -710,21 +707,81 @@ impl Component for CLIC {
         while let Some(e) = entry.queue_op.pop() {
             //readd
             if e.2 {
-                self.queue.borrow_mut().push(e.0, e.1);
+                self.queue.write().unwrap().push(e.0, e.1);
             }
             //remove
             else {
-                self.queue.borrow_mut().remove(&e.0);
+                self.queue.write().unwrap().remove(&e.0);
             }
         }
     }
 
+    fn save_state(&self) -> Option<serde_json::Value> {
+        self.state(true)
+    }
+
+    fn save_checkpoint(&self) -> Option<serde_json::Value> {
+        self.state(false)
+    }
+
+    fn load_state(&self, state: serde_json::Value) -> Result<(), String> {
+        let state: CLICState = serde_json::from_value(state).map_err(|e| e.to_string())?;
+        *self.csrstore.write().unwrap() = state.csrstore;
+        *self.mmio.write().unwrap() = state.mmio;
+        *self.queue.write().unwrap() = state.queue;
+        *self.clic_stack.write().unwrap() = state.clic_stack;
+        *self.mtime.write().unwrap() = state.mtime;
+        *self.monotonic.write().unwrap() = state.monotonic;
+        *self.mtimecomp.write().unwrap() = state.mtimecomp;
+        let mut history = self.history.write().unwrap();
+        match state.history {
+            Some(saved) => *history = saved,
+            None => history.truncate(state.history_len),
+        }
+        Ok(())
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        let mut parameters = vec![
+            Parameter::int("width", self.width as i64, 0, 1000),
+            Parameter::int("height", self.height as i64, 0, 1000),
+        ];
+        parameters.extend(input_parameters(self));
+        parameters
+    }
+
+    fn set_parameter(&mut self, name: &str, value: ParameterValue) -> Result<(), String> {
+        match name {
+            "width" => self.width = value.as_int()? as f32,
+            "height" => self.height = value.as_int()? as f32,
+            _ => return set_input_parameter(self, name, value),
+        }
+        Ok(())
+    }
 }
 
 impl CLIC {
+    // internal state, with the history or only its length (for checkpoints)
+    fn state(&self, with_history: bool) -> Option<serde_json::Value> {
+        let history = self.history.read().unwrap();
+        serde_json::to_value(CLICState {
+            csrstore: self.csrstore.read().unwrap().clone(),
+            mmio: self.mmio.read().unwrap().clone(),
+            queue: self.queue.read().unwrap().clone(),
+            clic_stack: self.clic_stack.read().unwrap().clone(),
+            mtime: *self.mtime.read().unwrap(),
+            monotonic: *self.monotonic.read().unwrap(),
+            mtimecomp: *self.mtimecomp.read().unwrap(),
+            history_len: history.len(),
+            history: with_history.then(|| history.clone()),
+        })
+        .ok()
+    }
+
     fn mmio_op(
         &self,
         addr: SignalUnsigned,
@@ -968,7 +1025,7 @@ impl CLIC {
 
     fn read(&self, addr: usize, size: usize, sign: bool, big_endian: bool) -> SignalValue {
         let data: Vec<u8> = (0..size)
-            .map(|i| *self.mmio.borrow().get(&(addr + i)).unwrap_or(&0))
+            .map(|i| *self.mmio.read().unwrap().get(&(addr + i)).unwrap_or(&0))
             .collect();
         let data = data.as_slice();
 
@@ -1036,7 +1093,7 @@ impl CLIC {
         match size {
             1 => {
                 trace!("write byte");
-                self.mmio.borrow_mut().insert(addr, data as u8);
+                self.mmio.write().unwrap().insert(addr, data as u8);
             }
             2 => {
                 if big_endian {
@@ -1046,7 +1103,7 @@ impl CLIC {
                         .iter()
                         .enumerate()
                         .for_each(|(i, bytes)| {
-                            self.mmio.borrow_mut().insert(addr + i, *bytes);
+                            self.mmio.write().unwrap().insert(addr + i, *bytes);
                         })
                 } else {
                     trace!("write half word le");
@@ -1055,7 +1112,7 @@ impl CLIC {
                         .iter()
                         .enumerate()
                         .for_each(|(i, bytes)| {
-                            self.mmio.borrow_mut().insert(addr + i, *bytes);
+                            self.mmio.write().unwrap().insert(addr + i, *bytes);
                         })
                 }
             }
@@ -1067,7 +1124,7 @@ impl CLIC {
                         .iter()
                         .enumerate()
                         .for_each(|(i, bytes)| {
-                            self.mmio.borrow_mut().insert(addr + i, *bytes);
+                            self.mmio.write().unwrap().insert(addr + i, *bytes);
                         })
                 } else {
                     trace!("write word le");
@@ -1075,7 +1132,7 @@ impl CLIC {
                         .iter()
                         .enumerate()
                         .for_each(|(i, bytes)| {
-                            self.mmio.borrow_mut().insert(addr + i, *bytes);
+                            self.mmio.write().unwrap().insert(addr + i, *bytes);
                         })
                 }
             }
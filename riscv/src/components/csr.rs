@@ -1,7 +1,7 @@
-use std::{cell::RefCell, collections::HashMap};
+use std::{collections::HashMap, sync::RwLock};
 
 use serde::{Deserialize, Serialize};
-use syncrim::common::{Component, Condition, Input, InputPort, OutputType, Ports, Simulator};
+use syncrim::common::{Component, Condition, Id, Input, InputPort, OutputType, Ports, Simulator};
 
 pub const CSR_ADDRESS_ID: &str = "address";
 pub const CSR_DATA_ID: &str = "data";
@@ -21,7 +21,7 @@ pub struct CSR {
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Registers {
-    registers: RefCell<HashMap<usize, (u32, CSRPriv)>>,
+    registers: RwLock<HashMap<usize, (u32, CSRPriv)>>,
 }
 #[derive(Serialize, Deserialize, Debug)]
 pub enum CSRPriv {
@@ -57,7 +57,7 @@ impl Default for Registers {
         //Debug registers unimplemented
 
         Registers {
-            registers: RefCell::new(map),
+            registers: RwLock::new(map),
         }
     }
 }
@@ -91,9 +91,18 @@ impl Component for CSR {
         )
     }
 
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        match target_port_id.as_str() {
+            CSR_ADDRESS_ID => self.address = new_input,
+            CSR_DATA_ID => self.data = new_input,
+            CSR_WE_ID => self.we = new_input,
+            _ => {}
+        }
+    }
+
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
         // get instr at pc/4
-        let _we = simulator.get_input_value(&self.we);
+        let _we = simulator.get_port_value(2);
         Ok(())
 
         //simulator.set_out_val(&self.id, "instruction", we);
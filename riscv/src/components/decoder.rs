@@ -1,13 +1,14 @@
 use log::trace;
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "gui-egui")]
-use std::rc::Rc;
+use std::sync::Arc;
 #[cfg(feature = "gui-egui")]
 use syncrim::common::EguiComponent;
 use syncrim::common::{
     Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalValue, Simulator,
 };
 use syncrim::components::MemCtrl;
+use syncrim::parameter::{input_parameters, set_input_parameter, Parameter, ParameterValue};
 
 pub const DECODER_INSTRUCTION_ID: &str = "instruction";
 
@@ -98,23 +99,45 @@ fn sign_zero_extend(sign: bool, width: u8, val: u32) -> u32 {
 
 #[typetag::serde()]
 impl Component for Decoder {
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
 
+    fn parameters(&self) -> Vec<Parameter> {
+        let mut parameters = vec![
+            Parameter::int("width", self.width as i64, 0, 1000),
+            Parameter::int("height", self.height as i64, 0, 1000),
+        ];
+        parameters.extend(input_parameters(self));
+        parameters
+    }
+
+    fn set_parameter(&mut self, name: &str, value: ParameterValue) -> Result<(), String> {
+        match name {
+            "width" => self.width = value.as_int()? as f32,
+            "height" => self.height = value.as_int()? as f32,
+            _ => return set_input_parameter(self, name, value),
+        }
+        Ok(())
+    }
+
     fn to_(&self) {
         println!("Decoder");
     }
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
         let dummy_input = Input::new("dummy", "out");
-        Box::new(Rc::new(Decoder {
+        Some(Box::new(Arc::new(Decoder {
             width: DECODER_WIDTH,
             height: DECODER_HEIGHT,
             id: id.to_string(),
             pos: (pos.0, pos.1),
             instruction: dummy_input,
-        }))
+        })))
     }
     fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
         if target_port_id.as_str() == DECODER_INSTRUCTION_ID {
@@ -166,10 +189,7 @@ impl Component for Decoder {
     }
     #[allow(non_snake_case)]
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
-        let instruction: u32 = simulator
-            .get_input_value(&self.instruction)
-            .try_into()
-            .unwrap();
+        let instruction: u32 = simulator.get_port_value(0).try_into().unwrap();
 
         //constant instruction field values
         let opcode = instruction & 0b1111111;
@@ -654,7 +674,7 @@ impl Component for Decoder {
 mod test {
     #![allow(unused_imports)]
     use super::*;
-    use std::rc::Rc;
+    use std::sync::Arc;
     use syncrim::{
         common::{ComponentStore, Input, Simulator},
         components::ProbeOut,
@@ -662,18 +682,16 @@ mod test {
 
     #[test]
     fn test_op() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("instruction")),
-                Rc::new(Decoder {
-                    width: 0.0,
-                    height: 0.0,
-                    id: "decoder".to_string(),
-                    pos: (0.0, 0.0),
-                    instruction: Input::new("instruction", "out"),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("instruction")),
+            Arc::new(Decoder {
+                width: 0.0,
+                height: 0.0,
+                id: "decoder".to_string(),
+                pos: (0.0, 0.0),
+                instruction: Input::new("instruction", "out"),
+            }),
+        ]);
         let mut simulator = Simulator::new(cs).unwrap();
 
         // outputs
@@ -850,18 +868,16 @@ mod test {
     }
     #[test]
     fn test_op_imm() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("instruction")),
-                Rc::new(Decoder {
-                    width: 0.0,
-                    height: 0.0,
-                    id: "decoder".to_string(),
-                    pos: (0.0, 0.0),
-                    instruction: Input::new("instruction", "out"),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("instruction")),
+            Arc::new(Decoder {
+                width: 0.0,
+                height: 0.0,
+                id: "decoder".to_string(),
+                pos: (0.0, 0.0),
+                instruction: Input::new("instruction", "out"),
+            }),
+        ]);
         let mut simulator = Simulator::new(cs).unwrap();
 
         // outputs
@@ -1029,18 +1045,16 @@ mod test {
     }
     #[test]
     fn test_lui_auipc_store_load() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("instruction")),
-                Rc::new(Decoder {
-                    width: 0.0,
-                    height: 0.0,
-                    id: "decoder".to_string(),
-                    pos: (0.0, 0.0),
-                    instruction: Input::new("instruction", "out"),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("instruction")),
+            Arc::new(Decoder {
+                width: 0.0,
+                height: 0.0,
+                id: "decoder".to_string(),
+                pos: (0.0, 0.0),
+                instruction: Input::new("instruction", "out"),
+            }),
+        ]);
         let mut simulator = Simulator::new(cs).unwrap();
 
         // outputs
@@ -1234,18 +1248,16 @@ mod test {
 
     #[test]
     fn test_jal_jalr_branch() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("instruction")),
-                Rc::new(Decoder {
-                    width: 0.0,
-                    height: 0.0,
-                    id: "decoder".to_string(),
-                    pos: (0.0, 0.0),
-                    instruction: Input::new("instruction", "out"),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("instruction")),
+            Arc::new(Decoder {
+                width: 0.0,
+                height: 0.0,
+                id: "decoder".to_string(),
+                pos: (0.0, 0.0),
+                instruction: Input::new("instruction", "out"),
+            }),
+        ]);
         let mut simulator = Simulator::new(cs).unwrap();
 
         // outputs
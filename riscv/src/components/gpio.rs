@@ -1,10 +1,11 @@
 use crate::components::mem::{MemCtrl, Memory};
 use log::trace;
 use serde::{Deserialize, Serialize};
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::RwLock;
 #[cfg(feature = "gui-egui")]
 use syncrim::common::EguiComponent;
+use syncrim::parameter::{input_parameters, set_input_parameter, Parameter, ParameterValue};
 use syncrim::{
     common::{Component, Condition, Id, Input, InputPort, OutputType, Ports, Simulator},
     signal::{SignalUnsigned, SignalValue},
@@ -64,7 +65,7 @@ pub struct Pin {
     pub id: u8,
 }
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct Pins(pub Rc<RefCell<Vec<Pin>>>);
+pub struct Pins(pub Arc<RwLock<Vec<Pin>>>);
 
 impl Default for Pins {
     fn default() -> Pins {
@@ -83,11 +84,11 @@ impl Default for Pins {
 }
 impl Pins {
     pub fn new(v: Vec<Pin>) -> Self {
-        Pins(Rc::new(RefCell::new(v)))
+        Pins(Arc::new(RwLock::new(v)))
     }
 }
 
-pub struct GPIOCsrStore(Rc<RefCell<HashMap<usize, usize>>>);
+pub struct GPIOCsrStore(Arc<RwLock<HashMap<usize, usize>>>);
 
 impl Default for GPIOCsrStore {
     fn default() -> GPIOCsrStore {
@@ -95,7 +96,7 @@ impl Default for GPIOCsrStore {
         for i in ((0 + GPIO_CSR_BASE) as usize)..=((6 + GPIO_CSR_BASE) as usize) {
             h.insert(i, 0);
         }
-        GPIOCsrStore(Rc::new(RefCell::new(h)))
+        GPIOCsrStore(Arc::new(RwLock::new(h)))
     }
 }
 
@@ -105,13 +106,31 @@ impl Component for GPIO {
         self
     }
 
+    fn parameters(&self) -> Vec<Parameter> {
+        let mut parameters = vec![
+            Parameter::int("width", self.width as i64, 0, 1000),
+            Parameter::int("height", self.height as i64, 0, 1000),
+        ];
+        parameters.extend(input_parameters(self));
+        parameters
+    }
+
+    fn set_parameter(&mut self, name: &str, value: ParameterValue) -> Result<(), String> {
+        match name {
+            "width" => self.width = value.as_int()? as f32,
+            "height" => self.height = value.as_int()? as f32,
+            _ => return set_input_parameter(self, name, value),
+        }
+        Ok(())
+    }
+
     fn to_(&self) {
         println!("GPIO");
     }
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
         let dummy = Input::new("dummy", "out");
-        Box::new(Rc::new(GPIO {
+        Some(Box::new(Arc::new(GPIO {
             height: GPIO_HEIGHT,
             width: GPIO_WIDTH,
             id: id.to_string(),
@@ -127,7 +146,7 @@ impl Component for GPIO {
             csrstore: GPIOCsrStore::default(),
             pins: Pins::default(),
             memory: Memory::default(),
-        }))
+        })))
     }
     fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
         if target_port_id.as_str() == GPIO_DATA_I_ID {
@@ -191,10 +210,11 @@ impl Component for GPIO {
     }
     #[allow(non_snake_case)]
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
-        let data = simulator.get_input_value(&self.data_i);
-        let addr = simulator.get_input_value(&self.addr_i);
-        let size = simulator.get_input_value(&self.size_i);
-        let sign = simulator.get_input_value(&self.se_i);
+        let data = simulator.get_port_value(0);
+        let addr = simulator.get_port_value(1);
+        let size = simulator.get_port_value(2);
+        let sign = simulator.get_port_value(4);
+        // the csr inputs are not ports, thus looked up
         let csr_data = simulator.get_input_value(&self.csr_d);
         let csr_addr = simulator.get_input_value(&self.csr_a);
         let csr_ctl = simulator.get_input_value(&self.csr_ctl);
@@ -202,7 +222,7 @@ impl Component for GPIO {
             SignalValue::Data(ctl) => {
                 let csr_addr: u32 = csr_addr.try_into().unwrap();
                 let csr_data: u32 = csr_data.try_into().unwrap_or(0); // could be a read still
-                let mut csrstore = self.csrstore.0.borrow_mut();
+                let mut csrstore = self.csrstore.0.write().unwrap();
                 let _ = self.csr_op(&mut csrstore, ctl, csr_data, csr_addr);
                 trace!(
                     "CSR TOUCH addr: {:x}",
@@ -213,7 +233,7 @@ impl Component for GPIO {
             }
             _ => {}
         }
-        match simulator.get_input_value(&self.we_i) {
+        match simulator.get_port_value(3) {
             SignalValue::Data(ctrl) => {
                 let addr: u32 = addr.try_into().unwrap_or(0);
                 if (0x6000_0000..=0x6000_0018).contains(&addr) {
@@ -244,7 +264,7 @@ impl Component for GPIO {
                 trace!("ctrl uninit");
             }
         };
-        for pin in &*self.pins.0.borrow() {
+        for pin in &*self.pins.0.read().unwrap() {
             let mut name = GPIO_PIN_O_ID.to_string();
             name.push_str(&format!("{}", pin.id));
             if pin.state {
@@ -255,11 +275,36 @@ impl Component for GPIO {
         }
         Ok(())
     }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(GPIOState {
+            memory: self.memory.0.read().unwrap().clone(),
+            pins: self.pins.0.read().unwrap().clone(),
+            csrstore: self.csrstore.0.read().unwrap().clone(),
+        })
+        .ok()
+    }
+
+    fn load_state(&self, state: serde_json::Value) -> Result<(), String> {
+        let state: GPIOState = serde_json::from_value(state).map_err(|e| e.to_string())?;
+        *self.memory.0.write().unwrap() = state.memory;
+        *self.pins.0.write().unwrap() = state.pins;
+        *self.csrstore.0.write().unwrap() = state.csrstore;
+        Ok(())
+    }
+}
+
+// internal state, as saved by `save_state`
+#[derive(Serialize, Deserialize)]
+struct GPIOState {
+    memory: std::collections::BTreeMap<usize, u8>,
+    pins: Vec<Pin>,
+    csrstore: HashMap<usize, usize>,
 }
 
 impl GPIO {
     fn handle_gpio_write(&self, _addr: u32) {
-        let mut pins = self.pins.0.borrow_mut();
+        let mut pins = self.pins.0.write().unwrap();
         /*let rel_addr = addr - 0x6000_0000;
         let touched_indices = [
             (rel_addr - rel_addr % 4) / 4,
@@ -1,9 +1,8 @@
 use asm_riscv::{self};
 use std::{
-    cell::RefCell,
     collections::{BTreeMap, HashMap, HashSet},
     ops::Range,
-    rc::Rc,
+    sync::{Arc, RwLock},
 };
 
 use log::trace;
@@ -30,7 +29,7 @@ pub struct InstrMem {
     pub pc: Input,
     pub range: Range<usize>,
     #[serde(skip)]
-    pub breakpoints: Rc<RefCell<HashSet<usize>>>,
+    pub breakpoints: Arc<RwLock<HashSet<usize>>>,
     #[serde(skip)]
     pub symbols: HashMap<usize, String>,
     pub le: bool,
@@ -46,9 +45,9 @@ impl Component for InstrMem {
         //println!("InstrMem");
     }
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
         let dummy_input = Input::new("dummy", "out");
-        Box::new(Rc::new(InstrMem {
+        Some(Box::new(Arc::new(InstrMem {
             width: INSTR_MEM_WIDTH,
             height: INSTR_MEM_HEIGHT,
             id: id.to_string(),
@@ -59,10 +58,10 @@ impl Component for InstrMem {
                 start: 0,
                 end: 0x1000,
             },
-            breakpoints: Rc::new(RefCell::new(HashSet::new())),
+            breakpoints: Arc::new(RwLock::new(HashSet::new())),
             symbols: HashMap::new(),
             le: true,
-        }))
+        })))
     }
     fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
         if target_port_id.as_str() == INSTR_MEM_PC_ID {
@@ -85,7 +84,7 @@ impl Component for InstrMem {
 
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
         // get instr at pc/4
-        let pc: u32 = simulator.get_input_value(&self.pc).try_into().unwrap();
+        let pc: u32 = simulator.get_port_value(0).try_into().unwrap();
         let instr = if !self.le {
             (*self.bytes.get(&((pc) as usize)).unwrap() as u32) << 24
                 | (*self.bytes.get(&((pc + 1) as usize)).unwrap() as u32) << 16
@@ -112,7 +111,7 @@ impl Component for InstrMem {
         trace!("pc:0x{:08x}", pc);
         // set output
         simulator.set_out_value(&self.id, "instruction", instr);
-        if !self.breakpoints.borrow().contains(&(pc as usize)) {
+        if !self.breakpoints.read().unwrap().contains(&(pc as usize)) {
             Ok(())
         } else {
             Err(Condition::Halt(format!("Breakpoint at {}", pc)))
@@ -123,7 +122,7 @@ mod test {
     #![allow(unused_imports)]
     use super::*;
 
-    use std::rc::Rc;
+    use std::sync::Arc;
     use syncrim::{
         common::{ComponentStore, Input, Simulator},
         components::ProbeOut,
@@ -138,26 +137,24 @@ mod test {
             instr_mem.insert((i * 4 + 2) as usize, bytes[2]);
             instr_mem.insert((i * 4 + 3) as usize, bytes[3]);
         }
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("pc")),
-                Rc::new(InstrMem {
-                    width: 0.0,
-                    height: 0.0,
-                    id: "imem".to_string(),
-                    pos: (0.0, 0.0),
-                    pc: Input::new("pc", "out"),
-                    bytes: instr_mem,
-                    range: Range {
-                        start: 0,
-                        end: 0x1000,
-                    },
-                    breakpoints: Rc::new(RefCell::new(HashSet::new())),
-                    symbols: HashMap::new(),
-                    le: false,
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("pc")),
+            Arc::new(InstrMem {
+                width: 0.0,
+                height: 0.0,
+                id: "imem".to_string(),
+                pos: (0.0, 0.0),
+                pc: Input::new("pc", "out"),
+                bytes: instr_mem,
+                range: Range {
+                    start: 0,
+                    end: 0x1000,
+                },
+                breakpoints: Arc::new(RwLock::new(HashSet::new())),
+                symbols: HashMap::new(),
+                le: false,
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
         assert_eq!(simulator.cycle, 1);
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "gui-egui")]
-use std::rc::Rc;
+use std::sync::Arc;
 #[cfg(feature = "gui-egui")]
 use syncrim::common::EguiComponent;
 use syncrim::common::{Component, Condition, Id, Input, InputPort, OutputType, Ports, Simulator};
@@ -29,15 +29,15 @@ impl Component for LED {
         println!("LED");
     }
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
         let dummy = Input::new("dummy", "out");
-        Box::new(Rc::new(LED {
+        Some(Box::new(Arc::new(LED {
             height: LED_HEIGHT,
             width: LED_WIDTH,
             id: id.to_string(),
             pos: (pos.0, pos.1),
             input: dummy.clone(),
-        }))
+        })))
     }
     fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
         if target_port_id.as_str() == LED_I_ID {
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "gui-egui")]
-use std::rc::Rc;
+use std::sync::Arc;
 #[cfg(feature = "gui-egui")]
 use syncrim::common::EguiComponent;
 use syncrim::{
@@ -26,6 +26,10 @@ pub struct LSBZero {
 
 #[typetag::serde()]
 impl Component for LSBZero {
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -34,15 +38,15 @@ impl Component for LSBZero {
         println!("LSBZero");
     }
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
         let dummy = Input::new("dummy", "out");
-        Box::new(Rc::new(LSBZero {
+        Some(Box::new(Arc::new(LSBZero {
             height: LSB_ZERO_HEIGHT,
             width: LSB_ZERO_WIDTH,
             id: id.to_string(),
             pos: (pos.0, pos.1),
             data_i: dummy.clone(),
-        }))
+        })))
     }
     fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
         if target_port_id.as_str() == LSB_ZERO_DATA_I_ID {
@@ -64,7 +68,7 @@ impl Component for LSBZero {
     }
     #[allow(non_snake_case)]
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
-        match simulator.get_input_value(&self.data_i) {
+        match simulator.get_port_value(0) {
             SignalValue::Data(mut data) => {
                 let mask: u32 = !0b1;
                 data &= mask;
@@ -80,25 +84,23 @@ mod test {
     #![allow(unused_imports)]
     use super::*;
 
-    use std::rc::Rc;
+    use std::sync::Arc;
     use syncrim::{
         common::{ComponentStore, Input, Simulator},
         components::ProbeOut,
     };
     #[test]
     fn lsb_zero_test() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("input")),
-                Rc::new(LSBZero {
-                    height: 0.0,
-                    width: 0.0,
-                    id: "lzero".to_string(),
-                    pos: (0.0, 0.0),
-                    data_i: Input::new("input", "out"),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("input")),
+            Arc::new(LSBZero {
+                height: 0.0,
+                width: 0.0,
+                id: "lzero".to_string(),
+                pos: (0.0, 0.0),
+                data_i: Input::new("input", "out"),
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
         assert_eq!(simulator.cycle, 1);
@@ -4,11 +4,16 @@ use num_enum::TryFromPrimitive;
 use serde::{Deserialize, Serialize};
 use std::ops::Deref;
 use std::ops::Range;
-use std::{cell::RefCell, collections::BTreeMap, convert::TryFrom, rc::Rc};
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    sync::{Arc, RwLock},
+};
 use syncrim::common::{
     Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalSigned, SignalUnsigned,
     SignalValue, Simulator,
 };
+use syncrim::profile::Access;
 //#[feature("gui-egui")]
 //use egui_extras::TableBuilder;
 pub const RV_MEM_DATA_I_ID: &str = "data_i";
@@ -18,7 +23,7 @@ pub const RV_MEM_SEXT_ID: &str = "sext";
 pub const RV_MEM_SIZE_ID: &str = "size";
 pub const RV_INTERRUPT_ID: &str = "interrupt";
 pub const RV_MEM_DATA_O_ID: &str = "data_o";
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize)]
 pub struct RVMem {
     pub(crate) id: Id,
     pub(crate) pos: (f32, f32),
@@ -43,7 +48,7 @@ pub struct RVMem {
     pub(crate) range: Range<u32>,
     // later history... tbd
     //
-    history: RefCell<Vec<MemOp>>,
+    history: RwLock<Vec<MemOp>>,
     #[serde(skip)]
     pub init_state: BTreeMap<usize, u8>,
 }
@@ -85,13 +90,13 @@ impl RVMem {
             interrupt,
             memory: Memory::new(memory.clone()),
             range,
-            history: RefCell::new(vec![]),
+            history: RwLock::new(vec![]),
             init_state: memory.clone(),
         }
     }
 
     #[allow(clippy::too_many_arguments)]
-    pub fn rc_new(
+    pub fn arc_new(
         id: &str,
         pos: (f32, f32),
         width: f32,
@@ -104,19 +109,19 @@ impl RVMem {
         size: Input,
         interrupt: Input,
         range: Range<u32>,
-    ) -> Rc<Self> {
+    ) -> Arc<Self> {
         let mut mem = BTreeMap::new();
         //fill the defined memory range with zeroes
         for i in range.clone() {
             mem.insert(i as usize, 0u8);
         }
-        Rc::new(RVMem::new(
+        Arc::new(RVMem::new(
             id, pos, width, height, big_endian, data, addr, ctrl, sext, size, interrupt, mem, range,
         ))
     }
 
     #[allow(clippy::too_many_arguments)]
-    pub fn rc_new_from_bytes(
+    pub fn arc_new_from_bytes(
         id: &str,
         pos: (f32, f32),
         width: f32,
@@ -130,8 +135,8 @@ impl RVMem {
         interrupt: Input,
         memory: BTreeMap<usize, u8>,
         range: Range<u32>,
-    ) -> Rc<Self> {
-        Rc::new(RVMem::new(
+    ) -> Arc<Self> {
+        Arc::new(RVMem::new(
             id, pos, width, height, big_endian, data, addr, ctrl, sext, size, interrupt, memory,
             range,
         ))
@@ -139,7 +144,7 @@ impl RVMem {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct Memory(pub Rc<RefCell<BTreeMap<usize, u8>>>);
+pub struct Memory(pub Arc<RwLock<BTreeMap<usize, u8>>>);
 
 impl Default for Memory {
     fn default() -> Self {
@@ -149,7 +154,7 @@ impl Default for Memory {
 
 impl Memory {
     pub fn new(data: BTreeMap<usize, u8>) -> Self {
-        Memory(Rc::new(RefCell::new(data)))
+        Memory(Arc::new(RwLock::new(data)))
     }
 
     fn align(&self, addr: usize, size: usize) -> SignalValue {
@@ -158,7 +163,7 @@ impl Memory {
 
     pub fn read(&self, addr: usize, size: usize, sign: bool, big_endian: bool) -> SignalValue {
         let data: Vec<u8> = (0..size)
-            .map(|i| *self.0.borrow().get(&(addr + i)).unwrap_or(&0))
+            .map(|i| *self.0.read().unwrap().get(&(addr + i)).unwrap_or(&0))
             .collect();
 
         let data = data.as_slice();
@@ -229,7 +234,7 @@ impl Memory {
         match size {
             1 => {
                 trace!("write byte");
-                self.0.borrow_mut().insert(addr, data as u8);
+                self.0.write().unwrap().insert(addr, data as u8);
             }
             2 => {
                 if big_endian {
@@ -239,7 +244,7 @@ impl Memory {
                         .iter()
                         .enumerate()
                         .for_each(|(i, bytes)| {
-                            self.0.borrow_mut().insert(addr + i, *bytes);
+                            self.0.write().unwrap().insert(addr + i, *bytes);
                         })
                 } else {
                     trace!("write half word le");
@@ -248,7 +253,7 @@ impl Memory {
                         .iter()
                         .enumerate()
                         .for_each(|(i, bytes)| {
-                            self.0.borrow_mut().insert(addr + i, *bytes);
+                            self.0.write().unwrap().insert(addr + i, *bytes);
                         })
                 }
             }
@@ -260,7 +265,7 @@ impl Memory {
                         .iter()
                         .enumerate()
                         .for_each(|(i, bytes)| {
-                            self.0.borrow_mut().insert(addr + i, *bytes);
+                            self.0.write().unwrap().insert(addr + i, *bytes);
                         })
                 } else {
                     trace!("write word le");
@@ -268,7 +273,7 @@ impl Memory {
                         .iter()
                         .enumerate()
                         .for_each(|(i, bytes)| {
-                            self.0.borrow_mut().insert(addr + i, *bytes);
+                            self.0.write().unwrap().insert(addr + i, *bytes);
                         })
                 }
             }
@@ -340,17 +345,29 @@ impl Component for RVMem {
         )
     }
 
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        match target_port_id.as_str() {
+            RV_MEM_DATA_I_ID => self.data = new_input,
+            RV_MEM_ADDR_ID => self.addr = new_input,
+            RV_MEM_CTRL_ID => self.ctrl = new_input,
+            RV_MEM_SEXT_ID => self.sext = new_input,
+            RV_MEM_SIZE_ID => self.size = new_input,
+            RV_INTERRUPT_ID => self.interrupt = new_input,
+            _ => {}
+        }
+    }
+
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
         let mut history_entry = MemOp {
             data: None,
             addr: 0,
             size: 0,
         };
-        let data = simulator.get_input_value(&self.data);
-        let addr = simulator.get_input_value(&self.addr);
-        let size = simulator.get_input_value(&self.size);
-        let sign = simulator.get_input_value(&self.sext);
-        let interrupt = simulator.get_input_value(&self.interrupt);
+        let data = simulator.get_port_value(0);
+        let addr = simulator.get_port_value(1);
+        let size = simulator.get_port_value(4);
+        let sign = simulator.get_port_value(3);
+        let interrupt = simulator.get_port_value(5);
         // let mem_int_addr = simulator.get_input_value(&self.mem_int_addr);
 
         // match mem_int_addr {
@@ -361,7 +378,7 @@ impl Component for RVMem {
         //     _ => simulator.set_out_value(&self.id, "isr_addr", SignalValue::Unknown),
         // }
 
-        match simulator.get_input_value(&self.ctrl) {
+        match simulator.get_port_value(2) {
             SignalValue::Data(ctrl) => {
                 let interrupt_occurred = SignalValue::Data(true as u32) == interrupt;
 
@@ -373,6 +390,7 @@ impl Component for RVMem {
 
                 match ctrl {
                     MemCtrl::ReadIsr => {
+                        simulator.record_access(Access::Read);
                         let addr: u32 = addr.try_into().unwrap();
 
                         //if not in mmio range
@@ -389,6 +407,7 @@ impl Component for RVMem {
                         let addr: u32 = addr.try_into().unwrap();
                         if !(0x1000..=0x500F).contains(&addr) {
                             //if not in mmio range
+                            simulator.record_access(Access::Read);
                             let size: u32 = size.try_into().unwrap();
                             let sign: u32 = sign.try_into().unwrap();
                             trace!("read addr {:?} size {:?}", addr, size);
@@ -411,6 +430,7 @@ impl Component for RVMem {
                         let addr: u32 = addr.try_into().unwrap();
                         if !(0x1000..=0x500F).contains(&addr) {
                             //if not in mmio range
+                            simulator.record_access(Access::Write);
                             let size: u32 = size.try_into().unwrap();
                             history_entry = MemOp {
                                 data: match self.memory.read(
@@ -444,25 +464,25 @@ impl Component for RVMem {
             }
         }
 
-        // for (idx, i) in self.memory.0.borrow().iter().enumerate() {
+        // for (idx, i) in self.memory.0.read().unwrap().iter().enumerate() {
         //     if i.0 % 4 == 0 && idx < 40 {
         //         //only print 40 bytes so the trace isn't busy
         //         trace!(
         //             "0x{:08x} : 0x{:02x}{:02x}{:02x}{:02x}",
         //             i.0,
-        //             self.memory.0.borrow().get(i.0).unwrap_or(&0u8),
-        //             self.memory.0.borrow().get(&(i.0 + 1)).unwrap_or(&0u8),
-        //             self.memory.0.borrow().get(&(i.0 + 2)).unwrap_or(&0u8),
-        //             self.memory.0.borrow().get(&(i.0 + 3)).unwrap_or(&0u8),
+        //             self.memory.0.read().unwrap().get(i.0).unwrap_or(&0u8),
+        //             self.memory.0.read().unwrap().get(&(i.0 + 1)).unwrap_or(&0u8),
+        //             self.memory.0.read().unwrap().get(&(i.0 + 2)).unwrap_or(&0u8),
+        //             self.memory.0.read().unwrap().get(&(i.0 + 3)).unwrap_or(&0u8),
         //         )
         //     }
         // }
-        self.history.borrow_mut().push(history_entry);
+        self.history.write().unwrap().push(history_entry);
         Ok(())
     }
 
     fn un_clock(&self) {
-        let entry = self.history.borrow_mut().pop().unwrap();
+        let entry = self.history.write().unwrap().pop().unwrap();
         if let Some(d) = entry.data {
             self.memory.write(
                 entry.addr,
@@ -474,17 +494,59 @@ impl Component for RVMem {
     }
 
     fn reset(&self) {
-        //let mut mem = self.memory.0.borrow_mut();
+        //let mut mem = self.memory.0.write().unwrap();
         //let b = self.init_state.0.clone();
-        self.memory.0.replace(self.init_state.clone());
-        //mem = self.init_state.0.borrow_mut();
+        *self.memory.0.write().unwrap() = self.init_state.clone();
+        //mem = self.init_state.0.write().unwrap();
         //self.memory.0.swap(&*self.init_state.0.clone());
-        self.history.swap(&RefCell::new(vec![]));
+        *self.history.write().unwrap() = vec![];
+    }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        self.state(true)
+    }
+
+    fn save_checkpoint(&self) -> Option<serde_json::Value> {
+        self.state(false)
+    }
+
+    fn load_state(&self, state: serde_json::Value) -> Result<(), String> {
+        let state: RVMemState = serde_json::from_value(state).map_err(|e| e.to_string())?;
+        *self.memory.0.write().unwrap() = state.memory;
+        let mut history = self.history.write().unwrap();
+        match state.history {
+            Some(saved) => *history = saved,
+            None => history.truncate(state.history_len),
+        }
+        Ok(())
+    }
+}
+
+impl RVMem {
+    // internal state, with the history or only its length (for checkpoints)
+    fn state(&self, with_history: bool) -> Option<serde_json::Value> {
+        let history = self.history.read().unwrap();
+        serde_json::to_value(RVMemState {
+            memory: self.memory.0.read().unwrap().clone(),
+            history_len: history.len(),
+            history: with_history.then(|| history.clone()),
+        })
+        .ok()
     }
 }
 
+// internal state, as saved by `save_state`, checkpoints keep the history length only
+#[derive(Serialize, Deserialize)]
+struct RVMemState {
+    memory: BTreeMap<usize, u8>,
+    #[serde(default)]
+    history_len: usize,
+    #[serde(default)]
+    history: Option<Vec<MemOp>>,
+}
+
 impl Deref for Memory {
-    type Target = RefCell<BTreeMap<usize, u8>>;
+    type Target = RwLock<BTreeMap<usize, u8>>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -494,49 +556,47 @@ impl Deref for Memory {
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::rc::Rc;
+    use std::sync::Arc;
     use syncrim::common::ComponentStore;
     use syncrim::components::ProbeOut;
 
     #[test]
     fn test_mem_be() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("data")),
-                Rc::new(ProbeOut::new("addr")),
-                Rc::new(ProbeOut::new("ctrl")),
-                Rc::new(ProbeOut::new("size")),
-                Rc::new(ProbeOut::new("sign")),
-                Rc::new(ProbeOut::new("interrupt")),
-                Rc::new(RVMem {
-                    id: "mem".into(),
-                    pos: (0.0, 0.0),
-                    width: 0.0,
-                    height: 0.0,
-
-                    // configuration
-                    big_endian: true, // i.e., big endian
-
-                    // ports
-                    data: Input::new("data", "out"),
-                    addr: Input::new("addr", "out"),
-                    ctrl: Input::new("ctrl", "out"),
-                    size: Input::new("size", "out"),
-                    sext: Input::new("sign", "out"),
-                    //interrupt: Input::new("sign", "out"),
-                    interrupt: Input::new("interrupt", "out"),
-
-                    // memory
-                    memory: Memory(Rc::new(RefCell::new(BTreeMap::new()))),
-                    range: Range {
-                        start: 0u32,
-                        end: 1u32,
-                    },
-                    history: RefCell::new(vec![]),
-                    init_state: BTreeMap::new(),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("data")),
+            Arc::new(ProbeOut::new("addr")),
+            Arc::new(ProbeOut::new("ctrl")),
+            Arc::new(ProbeOut::new("size")),
+            Arc::new(ProbeOut::new("sign")),
+            Arc::new(ProbeOut::new("interrupt")),
+            Arc::new(RVMem {
+                id: "mem".into(),
+                pos: (0.0, 0.0),
+                width: 0.0,
+                height: 0.0,
+
+                // configuration
+                big_endian: true, // i.e., big endian
+
+                // ports
+                data: Input::new("data", "out"),
+                addr: Input::new("addr", "out"),
+                ctrl: Input::new("ctrl", "out"),
+                size: Input::new("size", "out"),
+                sext: Input::new("sign", "out"),
+                //interrupt: Input::new("sign", "out"),
+                interrupt: Input::new("interrupt", "out"),
+
+                // memory
+                memory: Memory(Arc::new(RwLock::new(BTreeMap::new()))),
+                range: Range {
+                    start: 0u32,
+                    end: 1u32,
+                },
+                history: RwLock::new(vec![]),
+                init_state: BTreeMap::new(),
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
 
@@ -688,43 +748,41 @@ mod test {
 
     #[test]
     fn test_mem_le() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("data")),
-                Rc::new(ProbeOut::new("addr")),
-                Rc::new(ProbeOut::new("ctrl")),
-                Rc::new(ProbeOut::new("size")),
-                Rc::new(ProbeOut::new("sign")),
-                Rc::new(ProbeOut::new("interrupt")),
-                Rc::new(RVMem {
-                    id: "mem".into(),
-                    pos: (0.0, 0.0),
-                    width: 0.0,
-                    height: 0.0,
-
-                    // configuration
-                    big_endian: false, // i.e., little endian
-
-                    // ports
-                    data: Input::new("data", "out"),
-                    addr: Input::new("addr", "out"),
-                    ctrl: Input::new("ctrl", "out"),
-                    size: Input::new("size", "out"),
-                    sext: Input::new("sign", "out"),
-                    interrupt: Input::new("interrupt", "out"),
-
-                    // memory
-                    memory: Memory(Rc::new(RefCell::new(BTreeMap::new()))),
-                    // later history... tbd
-                    range: Range {
-                        start: 0u32,
-                        end: 1u32,
-                    },
-                    history: RefCell::new(vec![]),
-                    init_state: BTreeMap::new(),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("data")),
+            Arc::new(ProbeOut::new("addr")),
+            Arc::new(ProbeOut::new("ctrl")),
+            Arc::new(ProbeOut::new("size")),
+            Arc::new(ProbeOut::new("sign")),
+            Arc::new(ProbeOut::new("interrupt")),
+            Arc::new(RVMem {
+                id: "mem".into(),
+                pos: (0.0, 0.0),
+                width: 0.0,
+                height: 0.0,
+
+                // configuration
+                big_endian: false, // i.e., little endian
+
+                // ports
+                data: Input::new("data", "out"),
+                addr: Input::new("addr", "out"),
+                ctrl: Input::new("ctrl", "out"),
+                size: Input::new("size", "out"),
+                sext: Input::new("sign", "out"),
+                interrupt: Input::new("interrupt", "out"),
+
+                // memory
+                memory: Memory(Arc::new(RwLock::new(BTreeMap::new()))),
+                // later history... tbd
+                range: Range {
+                    start: 0u32,
+                    end: 1u32,
+                },
+                history: RwLock::new(vec![]),
+                init_state: BTreeMap::new(),
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
 
@@ -27,3 +27,288 @@ pub use probe_label::*;
 pub use reg_file::*;
 pub use sign_zero_ext::*;
 pub use wb_ctl::*;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use syncrim::migration::Migration;
+use syncrim::registry::{dummy_input, ComponentType};
+
+/// migrations of the RISC-V components, register by `syncrim::migration::register_migrations`
+/// before loading models
+pub const MIGRATIONS: &[Migration] = &[];
+
+/// the RISC-V component types, register by `syncrim::registry::register_components`
+pub const COMPONENTS: &[ComponentType] = &[
+    ComponentType {
+        name: "ALU",
+        category: "RISC-V",
+        description: "Arithmetic logic unit",
+        library: true,
+        new: |id, pos| {
+            Arc::new(ALU {
+                id: id.to_string(),
+                pos,
+                operator_i: dummy_input(),
+                operand_a_i: dummy_input(),
+                operand_b_i: dummy_input(),
+            })
+        },
+    },
+    ComponentType {
+        name: "BranchLogic",
+        category: "RISC-V",
+        description: "Branch condition, selecting the next program counter",
+        library: true,
+        new: |id, pos| {
+            Arc::new(BranchLogic {
+                width: BRANCH_LOGIC_WIDTH,
+                height: BRANCH_LOGIC_HEIGHT,
+                id: id.to_string(),
+                pos,
+                rs1: dummy_input(),
+                rs2: dummy_input(),
+                ctrl: dummy_input(),
+                enable: dummy_input(),
+            })
+        },
+    },
+    ComponentType {
+        name: "CLIC",
+        category: "RISC-V",
+        description: "Core local interrupt controller, with its CSRs and timer",
+        library: true,
+        new: |id, pos| {
+            Arc::new(CLIC::new(
+                id.to_string(),
+                pos,
+                100.0,
+                100.0,
+                dummy_input(),
+                dummy_input(),
+                dummy_input(),
+                dummy_input(),
+                dummy_input(),
+                dummy_input(),
+                dummy_input(),
+                dummy_input(),
+                dummy_input(),
+                dummy_input(),
+            ))
+        },
+    },
+    ComponentType {
+        name: "Decoder",
+        category: "RISC-V",
+        description: "Instruction decoder, the control signals and immediates",
+        library: true,
+        new: |id, pos| {
+            Arc::new(Decoder {
+                width: DECODER_WIDTH,
+                height: DECODER_HEIGHT,
+                id: id.to_string(),
+                pos,
+                instruction: dummy_input(),
+            })
+        },
+    },
+    #[cfg(not(feature = "gui-vizia"))]
+    ComponentType {
+        name: "GPIO",
+        category: "RISC-V",
+        description: "General purpose I/O pins, memory mapped",
+        library: true,
+        new: |id, pos| {
+            Arc::new(GPIO {
+                height: GPIO_HEIGHT,
+                width: GPIO_WIDTH,
+                id: id.to_string(),
+                pos,
+                data_i: dummy_input(),
+                addr_i: dummy_input(),
+                size_i: dummy_input(),
+                we_i: dummy_input(),
+                se_i: dummy_input(),
+                csr_d: dummy_input(),
+                csr_a: dummy_input(),
+                csr_ctl: dummy_input(),
+                csrstore: GPIOCsrStore::default(),
+                pins: Pins::default(),
+                memory: Memory::default(),
+            })
+        },
+    },
+    ComponentType {
+        name: "InstrMem",
+        category: "RISC-V",
+        description: "Instruction memory, with breakpoints",
+        library: true,
+        new: |id, pos| {
+            Arc::new(InstrMem {
+                width: INSTR_MEM_WIDTH,
+                height: INSTR_MEM_HEIGHT,
+                id: id.to_string(),
+                pos,
+                bytes: BTreeMap::new(),
+                pc: dummy_input(),
+                range: 0..0x1000,
+                breakpoints: Arc::new(RwLock::new(HashSet::new())),
+                symbols: HashMap::new(),
+                le: true,
+            })
+        },
+    },
+    #[cfg(not(feature = "gui-vizia"))]
+    ComponentType {
+        name: "LED",
+        category: "RISC-V",
+        description: "Shows its input as a light",
+        library: true,
+        new: |id, pos| {
+            Arc::new(LED {
+                height: LED_HEIGHT,
+                width: LED_WIDTH,
+                id: id.to_string(),
+                pos,
+                input: dummy_input(),
+            })
+        },
+    },
+    ComponentType {
+        name: "LSBZero",
+        category: "RISC-V",
+        description: "Clears the least significant bit",
+        library: true,
+        new: |id, pos| {
+            Arc::new(LSBZero {
+                height: LSB_ZERO_HEIGHT,
+                width: LSB_ZERO_WIDTH,
+                id: id.to_string(),
+                pos,
+                data_i: dummy_input(),
+            })
+        },
+    },
+    #[cfg(not(feature = "gui-vizia"))]
+    ComponentType {
+        name: "ProbeLabel",
+        category: "RISC-V",
+        description: "Shows the value of its input, labeled",
+        library: true,
+        new: |id, pos| ProbeLabel::arc_new(id, pos, dummy_input()),
+    },
+    ComponentType {
+        name: "RegFile",
+        category: "RISC-V",
+        description: "Register file, with a stack of register banks for interrupts",
+        library: true,
+        new: |id, pos| {
+            Arc::new(RegFile {
+                id: id.to_string(),
+                pos,
+                ..RegFile::dummy()
+            })
+        },
+    },
+    ComponentType {
+        name: "RVMem",
+        category: "RISC-V",
+        description: "Data memory, with memory mapped I/O",
+        library: true,
+        new: |id, pos| {
+            RVMem::arc_new(
+                id,
+                pos,
+                100.0,
+                100.0,
+                false,
+                dummy_input(),
+                dummy_input(),
+                dummy_input(),
+                dummy_input(),
+                dummy_input(),
+                dummy_input(),
+                0..0x20,
+            )
+        },
+    },
+    ComponentType {
+        name: "SZExt",
+        category: "RISC-V",
+        description: "Sign or zero extension, as selected",
+        library: true,
+        new: |id, pos| {
+            Arc::new(SZExt {
+                height: SIGN_ZERO_EXT_HEIGHT,
+                width: SIGN_ZERO_EXT_WIDTH,
+                id: id.to_string(),
+                pos,
+                data_i: dummy_input(),
+                sel_i: dummy_input(),
+            })
+        },
+    },
+    #[cfg(not(feature = "gui-vizia"))]
+    ComponentType {
+        name: "WBCtl",
+        category: "RISC-V",
+        description: "Write back control, on interrupts",
+        library: true,
+        new: |id, pos| {
+            Arc::new(WBCtl {
+                height: WB_CTL_HEIGHT,
+                width: WB_CTL_WIDTH,
+                id: id.to_string(),
+                pos,
+                clic_i: dummy_input(),
+                dec_i: dummy_input(),
+            })
+        },
+    },
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+    use syncrim::common::{Component, ComponentStore, Input};
+    use syncrim::migration::{register_migrations, FORMAT_VERSION};
+    use syncrim::parameter::{set_parameter, Parameter};
+
+    #[test]
+    fn test_migrate_riscv_json() {
+        register_migrations(MIGRATIONS);
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("riscv.json");
+        let mut model: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        // as saved before models had a header
+        model.as_object_mut().unwrap().remove("header");
+
+        let cs = ComponentStore::from_json(&model.to_string()).unwrap();
+        let header = cs.header();
+        assert_eq!(header.version, FORMAT_VERSION);
+        assert_eq!(header.libraries, vec!["riscv", "syncrim"]);
+    }
+
+    #[test]
+    fn test_components() {
+        syncrim::registry::register_components(COMPONENTS);
+        for component_type in COMPONENTS {
+            let component = (component_type.new)("c", (0.0, 0.0));
+            assert_eq!(
+                Component::typetag_name(component.as_ref()),
+                component_type.name
+            );
+            assert!(component.parameters().iter().all(|p| p.check().is_ok()));
+        }
+        assert!(syncrim::registry::component_type("Decoder").is_some());
+
+        // input ports are editable
+        let mut clic = (syncrim::registry::component_type("CLIC").unwrap().new)("clic", (0.0, 0.0));
+        let clic = std::sync::Arc::get_mut(&mut clic).unwrap();
+        let input = Parameter::input(CLIC_PC_ID, &Input::new("pc", "out"));
+        set_parameter(clic, CLIC_PC_ID, input.value).unwrap();
+        let (_, ports) = clic.get_id_ports();
+        let port = ports.inputs.iter().find(|p| p.port_id == CLIC_PC_ID);
+        assert_eq!(port.unwrap().input, Input::new("pc", "out"));
+    }
+}
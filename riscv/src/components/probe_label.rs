@@ -1,7 +1,7 @@
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
-use std::rc::Rc;
+use std::sync::Arc;
 #[cfg(feature = "gui-egui")]
 use syncrim::common::EguiComponent;
 use syncrim::common::{Component, Id, Input, InputPort, OutputType, Ports};
@@ -22,13 +22,13 @@ impl Component for ProbeLabel {
     }
 
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
         let dummy_input = Input::new("dummy", "out");
-        Box::new(Rc::new(ProbeLabel {
+        Some(Box::new(Arc::new(ProbeLabel {
             id: id.to_string(),
             pos: (pos.0, pos.1),
             input: dummy_input.clone(),
-        }))
+        })))
     }
 
     fn get_id_ports(&self) -> (Id, Ports) {
@@ -67,7 +67,7 @@ impl ProbeLabel {
         }
     }
 
-    pub fn rc_new(id: &str, pos: (f32, f32), input: Input) -> Rc<Self> {
-        Rc::new(ProbeLabel::new(id, pos, input))
+    pub fn arc_new(id: &str, pos: (f32, f32), input: Input) -> Arc<Self> {
+        Arc::new(ProbeLabel::new(id, pos, input))
     }
 }
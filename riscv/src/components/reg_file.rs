@@ -2,7 +2,7 @@ use log::*;
 use num_enum::TryFromPrimitive;
 use serde::{Deserialize, Serialize};
 use std::ops::{Deref, Range};
-use std::{cell::RefCell, rc::Rc};
+use std::sync::{Arc, RwLock};
 #[cfg(feature = "gui-egui")]
 use syncrim::common::EguiComponent;
 use syncrim::common::{
@@ -113,7 +113,7 @@ pub const REG_FILE_RA_OUT: &str = "ra";
 pub const REG_FILE_WIDTH: f32 = 250.0;
 pub const REG_FILE_HEIGHT: f32 = 500.0;
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize)]
 pub struct RegFile {
     pub id: String,
     pub pos: (f32, f32),
@@ -140,7 +140,7 @@ pub struct RegFile {
     // implementing tabs or something over the different
     // register sets
     #[serde(skip)]
-    pub stack_depth_state: RefCell<u32>,
+    pub stack_depth_state: RwLock<u32>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -154,12 +154,12 @@ pub struct RegOp {
 }
 // TODO: Perhaps we want registers to be of Signal type (containing potentially Signal::Unknown)
 
-#[derive(Serialize, Deserialize, Clone)]
-pub struct RegHistory(RefCell<Vec<RegOp>>);
+#[derive(Serialize, Deserialize)]
+pub struct RegHistory(RwLock<Vec<RegOp>>);
 
 impl RegHistory {
     pub fn new() -> Self {
-        RegHistory(RefCell::new(Vec::new()))
+        RegHistory(RwLock::new(Vec::new()))
     }
 }
 
@@ -172,10 +172,10 @@ impl Default for RegHistory {
 type RegStack = [[u32; 32]; REG_FILE_MAX_DEPTH];
 
 #[derive(Serialize, Deserialize, Clone)]
-pub struct RegStore(pub Rc<RefCell<RegStack>>);
+pub struct RegStore(pub Arc<RwLock<RegStack>>);
 
 impl RegStore {
-    pub fn new(regs: Rc<RefCell<RegStack>>) -> Self {
+    pub fn new(regs: Arc<RwLock<RegStack>>) -> Self {
         RegStore(regs)
     }
 
@@ -194,12 +194,12 @@ impl RegStore {
 
 impl Default for RegStore {
     fn default() -> Self {
-        Self::new(Rc::new(RefCell::new([[0; 32]; REG_FILE_MAX_DEPTH])))
+        Self::new(Arc::new(RwLock::new([[0; 32]; REG_FILE_MAX_DEPTH])))
     }
 }
 
 impl Deref for RegStore {
-    type Target = RefCell<[[u32; 32]; REG_FILE_MAX_DEPTH]>;
+    type Target = RwLock<[[u32; 32]; REG_FILE_MAX_DEPTH]>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -208,11 +208,20 @@ impl Deref for RegStore {
 
 impl RegFile {
     pub fn read_reg(&self, simulator: &Simulator, input: impl Into<SignalValue>) -> SignalValue {
-        let input = input.into();
         let stack_depth: SignalUnsigned = simulator
             .get_input_value(&self.stack_depth)
             .try_into()
             .unwrap();
+        self.read_reg_at(stack_depth, input)
+    }
+
+    // read register at `stack_depth`, as sampled by `clock`
+    fn read_reg_at(
+        &self,
+        stack_depth: SignalUnsigned,
+        input: impl Into<SignalValue>,
+    ) -> SignalValue {
+        let input = input.into();
         let stack_depth = if stack_depth as i32 >= 0 {
             stack_depth
         } else {
@@ -230,11 +239,13 @@ impl RegFile {
                     }
                     Reg::sp => {
                         // reg sp shared among all stacks, we use stack_depth 0 for that
-                        SignalValue::from(self.registers.borrow()[0][read_addr])
+                        SignalValue::from(self.registers.read().unwrap()[0][read_addr])
                     }
                     _ => {
                         // all other registers
-                        SignalValue::from(self.registers.borrow()[stack_depth as usize][read_addr])
+                        SignalValue::from(
+                            self.registers.read().unwrap()[stack_depth as usize][read_addr],
+                        )
                     }
                 }
             }
@@ -242,12 +253,13 @@ impl RegFile {
         }
     }
 
-    fn write_reg(&self, simulator: &Simulator, input: impl Into<SignalValue>, data: SignalValue) {
+    fn write_reg(
+        &self,
+        stack_depth: SignalUnsigned,
+        input: impl Into<SignalValue>,
+        data: SignalValue,
+    ) {
         let input: SignalValue = input.into();
-        let stack_depth: SignalUnsigned = simulator
-            .get_input_value(&self.stack_depth)
-            .try_into()
-            .unwrap();
         let stack_depth = if stack_depth as i32 >= 0 {
             stack_depth
         } else {
@@ -265,13 +277,13 @@ impl RegFile {
                     }
                     Reg::sp => {
                         // reg sp shared among all stacks, we use stack_depth 0 for that
-                        self.registers.borrow_mut()[0][write_addr as usize] =
+                        self.registers.write().unwrap()[0][write_addr as usize] =
                             data.try_into().unwrap();
                     }
                     _ => {
                         // all other registers
-                        self.registers.borrow_mut()[stack_depth as usize][write_addr as usize] =
-                            data.try_into().unwrap();
+                        self.registers.write().unwrap()[stack_depth as usize]
+                            [write_addr as usize] = data.try_into().unwrap();
                     }
                 }
             }
@@ -296,7 +308,7 @@ impl RegFile {
             write_data: dummy.clone(),
             write_addr: dummy.clone(),
             write_enable: dummy.clone(),
-            registers: RegStore::new(Rc::new(RefCell::new([[0; 32]; REG_FILE_MAX_DEPTH]))),
+            registers: RegStore::new(Arc::new(RwLock::new([[0; 32]; REG_FILE_MAX_DEPTH]))),
             history: RegHistory::new(),
             stack_depth_state: 0.into(),
         }
@@ -315,17 +327,18 @@ impl Component for RegFile {
 
     fn reset(&self) {
         self.registers
-            .borrow_mut()
+            .write()
+            .unwrap()
             .swap_with_slice(&mut [[0; 32]; REG_FILE_MAX_DEPTH]);
-        self.history.0.swap(&RefCell::new(vec![]));
+        *self.history.0.write().unwrap() = vec![];
     }
 
     fn get_id_ports(&self) -> (String, Ports) {
         (
             self.id.clone(),
-            Ports {
-                inputs: vec![
-                    InputPort {
+            Ports::new(
+                vec![
+                    &InputPort {
                         port_id: REG_FILE_STACK_DEPTH_ID.to_string(),
                         input: self.stack_depth.clone(),
                     },
@@ -334,49 +347,45 @@ impl Component for RegFile {
                     //     input: self.stack_depth.clone(),
                     //
                     //},
-                    InputPort {
+                    &InputPort {
                         port_id: REG_FILE_CLIC_RA_WE_ID.to_string(),
                         input: self.stack_depth.clone(),
                     },
-                    InputPort {
+                    &InputPort {
                         port_id: REG_FILE_READ_ADDR1_ID.to_string(),
                         input: self.read_addr1.clone(),
                     },
-                    InputPort {
+                    &InputPort {
                         port_id: REG_FILE_READ_ADDR2_ID.to_string(),
                         input: self.read_addr2.clone(),
                     },
-                    InputPort {
+                    &InputPort {
                         port_id: REG_FILE_WRITE_DATA_ID.to_string(),
                         input: self.write_data.clone(),
                     },
-                    InputPort {
+                    &InputPort {
                         port_id: REG_FILE_WRITE_ADDR_ID.to_string(),
                         input: self.write_addr.clone(),
                     },
-                    InputPort {
+                    &InputPort {
                         port_id: REG_FILE_WRITE_ENABLE_ID.to_string(),
                         input: self.write_enable.clone(),
                     },
                 ],
-                out_type: OutputType::Combinatorial,
-                outputs: vec![
-                    REG_FILE_REG_A_OUT.into(),
-                    REG_FILE_REG_B_OUT.into(),
-                    REG_FILE_RA_OUT.into(),
-                ],
-            },
+                OutputType::Combinatorial,
+                vec![REG_FILE_REG_A_OUT, REG_FILE_REG_B_OUT, REG_FILE_RA_OUT],
+            ),
         )
     }
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
         let dummy_input = Input::new("dummy", "out");
-        Box::new(Rc::new(RegFile {
+        Some(Box::new(Arc::new(RegFile {
             width: REG_FILE_WIDTH,
             height: REG_FILE_HEIGHT,
             id: id.to_string(),
             pos: (pos.0, pos.1),
-            registers: RegStore::new(Rc::new(RefCell::new([[0; 32]; REG_FILE_MAX_DEPTH]))),
+            registers: RegStore::new(Arc::new(RwLock::new([[0; 32]; REG_FILE_MAX_DEPTH]))),
             history: RegHistory::new(),
             stack_depth: dummy_input.clone(),
             //clic_mepc: dummy_input.clone(),
@@ -387,7 +396,7 @@ impl Component for RegFile {
             write_addr: dummy_input.clone(),
             write_enable: dummy_input.clone(),
             stack_depth_state: 0.into(),
-        }))
+        })))
     }
     fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
         match target_port_id.as_str() {
@@ -409,13 +418,12 @@ impl Component for RegFile {
             old_data: None,
             old_ra: None,
         };
-        let stack_depth: SignalUnsigned = simulator
-            .get_input_value(&self.stack_depth)
-            .try_into()
-            .unwrap();
+        // ports as in `get_id_ports`
+        let stack_depth: SignalUnsigned = simulator.get_port_value(0).try_into().unwrap();
 
         // special handling if clic_ra_we
         // THIS IS 2 WHEN
+        // (port 1 is connected to stack_depth, thus clic_ra_we is looked up)
         let clic_ra_we =
             simulator.get_input_value(&self.clic_ra_we) == (true as SignalUnsigned).into();
 
@@ -424,7 +432,7 @@ impl Component for RegFile {
             //     "update ra register {:?}",
             //     simulator.get_input_value(&self.clic_mepc)
             // );
-            let old_ra = self.read_reg(simulator, REG_RA);
+            let old_ra = self.read_reg_at(stack_depth, REG_RA);
             regop.old_ra = Some(old_ra.try_into().unwrap());
             // self.write_reg(
             //     simulator,
@@ -432,39 +440,40 @@ impl Component for RegFile {
             //     simulator.get_input_value(&self.clic_mepc),
             // );
             // write magic number to RA
-            self.write_reg(simulator, REG_RA, SignalValue::Data(0xFFFF_FFFF));
+            self.write_reg(stack_depth, REG_RA, SignalValue::Data(0xFFFF_FFFF));
         }
 
-        let stack_depth = stack_depth as usize;
-        *self.stack_depth_state.borrow_mut() = stack_depth as u32;
-        let read_addr1 = simulator.get_input_value(&self.read_addr1);
-        let read_addr2 = simulator.get_input_value(&self.read_addr2);
+        *self.stack_depth_state.write().unwrap() = stack_depth;
+        let read_addr1 = simulator.get_port_value(2);
+        let read_addr2 = simulator.get_port_value(3);
         //*depth_state = stack_depth;
 
-        if simulator.get_input_value(&self.write_enable) == (true as SignalUnsigned).into() {
-            let data = simulator.get_input_value(&self.write_data);
+        if simulator.get_port_value(6) == (true as SignalUnsigned).into() {
+            let data = simulator.get_port_value(4);
             trace!("write data {:?}", data);
-            let write_addr = simulator.get_input_value(&self.write_addr);
+            let write_addr = simulator.get_port_value(5);
 
             regop.write_addr2 = Some((
                 TryInto::<SignalUnsigned>::try_into(write_addr).unwrap() as u8,
-                self.read_reg(simulator, write_addr).try_into().unwrap(), // read old value
+                self.read_reg_at(stack_depth, write_addr)
+                    .try_into()
+                    .unwrap(), // read old value
             ));
 
-            self.write_reg(&simulator, write_addr, data);
+            self.write_reg(stack_depth, write_addr, data);
         }
-        self.history.0.borrow_mut().push(regop);
+        self.history.0.write().unwrap().push(regop);
 
         // read after write
-        let reg_value_a = self.read_reg(simulator, read_addr1);
+        let reg_value_a = self.read_reg_at(stack_depth, read_addr1);
         trace!("reg_value_a {:?}", reg_value_a);
         simulator.set_out_value(&self.id, REG_FILE_REG_A_OUT, reg_value_a);
 
-        let reg_value_b = self.read_reg(simulator, read_addr2);
+        let reg_value_b = self.read_reg_at(stack_depth, read_addr2);
         trace!("reg_value_b {:?}", reg_value_b);
         simulator.set_out_value(&self.id, REG_FILE_REG_B_OUT, reg_value_b);
 
-        let reg_value_ra = self.read_reg(simulator, REG_RA);
+        let reg_value_ra = self.read_reg_at(stack_depth, REG_RA);
         trace!("reg_value ra {:?}", reg_value_ra);
         simulator.set_out_value(&self.id, REG_FILE_RA_OUT, reg_value_ra);
 
@@ -473,18 +482,63 @@ impl Component for RegFile {
 
     fn un_clock(&self) {
         //println!("unclock");
-        let regop = self.history.0.borrow_mut().pop().unwrap();
-        let mut regstore = self.registers.borrow_mut();
+        let regop = self.history.0.write().unwrap().pop().unwrap();
+        let mut regstore = self.registers.write().unwrap();
         if let Some(w) = regop.write_addr2 {
             regstore[regop.stack_depth as usize][w.0 as usize] = w.1
         }
     }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        self.state(true)
+    }
+
+    fn save_checkpoint(&self) -> Option<serde_json::Value> {
+        self.state(false)
+    }
+
+    fn load_state(&self, state: serde_json::Value) -> Result<(), String> {
+        let state: RegFileState = serde_json::from_value(state).map_err(|e| e.to_string())?;
+        *self.registers.write().unwrap() = state.registers;
+        let mut history = self.history.0.write().unwrap();
+        match state.history {
+            Some(saved) => *history = saved,
+            None => history.truncate(state.history_len),
+        }
+        *self.stack_depth_state.write().unwrap() = state.stack_depth_state;
+        Ok(())
+    }
+}
+
+impl RegFile {
+    // internal state, with the history or only its length (for checkpoints)
+    fn state(&self, with_history: bool) -> Option<serde_json::Value> {
+        let history = self.history.0.read().unwrap();
+        serde_json::to_value(RegFileState {
+            registers: *self.registers.read().unwrap(),
+            history_len: history.len(),
+            history: with_history.then(|| history.clone()),
+            stack_depth_state: *self.stack_depth_state.read().unwrap(),
+        })
+        .ok()
+    }
+}
+
+// internal state, as saved by `save_state`, checkpoints keep the history length only
+#[derive(Serialize, Deserialize)]
+struct RegFileState {
+    registers: RegStack,
+    #[serde(default)]
+    history_len: usize,
+    #[serde(default)]
+    history: Option<Vec<RegOp>>,
+    stack_depth_state: u32,
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::rc::Rc;
+    use std::sync::Arc;
     use syncrim::{
         common::{ComponentStore, Input, Simulator},
         components::ProbeOut,
@@ -493,42 +547,40 @@ mod test {
     // an example of integration test for a mips specific component
     #[test]
     fn test_reg_file() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("stack_depth")),
-                //Rc::new(ProbeOut::new("clic_mepc")),
-                Rc::new(ProbeOut::new("clic_ra_we")),
-                Rc::new(ProbeOut::new("read_reg_1")),
-                Rc::new(ProbeOut::new("read_reg_2")),
-                Rc::new(ProbeOut::new("write_data")),
-                Rc::new(ProbeOut::new("write_addr")),
-                Rc::new(ProbeOut::new("write_enable")),
-                // regfile
-                Rc::new(RegFile {
-                    id: "reg_file".to_string(),
-                    pos: (200.0, 150.0),
-                    width: 100.0,
-                    height: 150.0,
-
-                    // ports
-                    stack_depth: Input::new("stack_depth", "out"),
-                    //clic_mepc: Input::new("clic_mepc", "out"),
-                    clic_ra_we: Input::new("clic_ra_we", "out"),
-
-                    read_addr1: Input::new("read_reg_1", "out"),
-                    read_addr2: Input::new("read_reg_2", "out"),
-                    write_data: Input::new("write_data", "out"),
-                    write_addr: Input::new("write_addr", "out"),
-                    write_enable: Input::new("write_enable", "out"),
-
-                    // data
-                    registers: RegStore::default(),
-                    history: RegHistory::new(),
-
-                    stack_depth_state: 0.into(),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("stack_depth")),
+            //Arc::new(ProbeOut::new("clic_mepc")),
+            Arc::new(ProbeOut::new("clic_ra_we")),
+            Arc::new(ProbeOut::new("read_reg_1")),
+            Arc::new(ProbeOut::new("read_reg_2")),
+            Arc::new(ProbeOut::new("write_data")),
+            Arc::new(ProbeOut::new("write_addr")),
+            Arc::new(ProbeOut::new("write_enable")),
+            // regfile
+            Arc::new(RegFile {
+                id: "reg_file".to_string(),
+                pos: (200.0, 150.0),
+                width: 100.0,
+                height: 150.0,
+
+                // ports
+                stack_depth: Input::new("stack_depth", "out"),
+                //clic_mepc: Input::new("clic_mepc", "out"),
+                clic_ra_we: Input::new("clic_ra_we", "out"),
+
+                read_addr1: Input::new("read_reg_1", "out"),
+                read_addr2: Input::new("read_reg_2", "out"),
+                write_data: Input::new("write_data", "out"),
+                write_addr: Input::new("write_addr", "out"),
+                write_enable: Input::new("write_enable", "out"),
+
+                // data
+                registers: RegStore::default(),
+                history: RegHistory::new(),
+
+                stack_depth_state: 0.into(),
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
 
@@ -1,7 +1,7 @@
 use log::trace;
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "gui-egui")]
-use std::rc::Rc;
+use std::sync::Arc;
 #[cfg(feature = "gui-egui")]
 use syncrim::common::EguiComponent;
 use syncrim::common::{
@@ -28,6 +28,10 @@ pub struct SZExt {
 
 #[typetag::serde()]
 impl Component for SZExt {
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -36,16 +40,16 @@ impl Component for SZExt {
         println!("s_z_ext");
     }
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
         let dummy = Input::new("dummy", "out");
-        Box::new(Rc::new(SZExt {
+        Some(Box::new(Arc::new(SZExt {
             height: SIGN_ZERO_EXT_HEIGHT,
             width: SIGN_ZERO_EXT_WIDTH,
             id: id.to_string(),
             pos: (pos.0, pos.1),
             data_i: dummy.clone(),
             sel_i: dummy.clone(),
-        }))
+        })))
     }
     fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
         match target_port_id.as_str() {
@@ -77,13 +81,10 @@ impl Component for SZExt {
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
         //data is zero extended as default since its a 32 bit signal
 
-        match simulator.get_input_value(&self.data_i) {
+        match simulator.get_port_value(0) {
             //if there is data, sel should be defined, otherwise panic is good.
             SignalValue::Data(mut data) => {
-                let sel: u32 = simulator
-                    .get_input_value(&self.sel_i)
-                    .try_into()
-                    .unwrap_or(0);
+                let sel: u32 = simulator.get_port_value(1).try_into().unwrap_or(0);
                 //println!("SZEDATA:{:x}", data);
                 match sel {
                     0 => {
@@ -113,27 +114,25 @@ mod test {
     #![allow(unused_imports)]
     use super::*;
 
-    use std::rc::Rc;
+    use std::sync::Arc;
     use syncrim::{
         common::{ComponentStore, Input, Simulator},
         components::ProbeOut,
     };
     #[test]
     fn test_szext() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("input")),
-                Rc::new(ProbeOut::new("sel")),
-                Rc::new(SZExt {
-                    height: 0.0,
-                    width: 0.0,
-                    id: "szext".to_string(),
-                    pos: (0.0, 0.0),
-                    data_i: Input::new("input", "out"),
-                    sel_i: Input::new("sel", "out"),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("input")),
+            Arc::new(ProbeOut::new("sel")),
+            Arc::new(SZExt {
+                height: 0.0,
+                width: 0.0,
+                id: "szext".to_string(),
+                pos: (0.0, 0.0),
+                data_i: Input::new("input", "out"),
+                sel_i: Input::new("sel", "out"),
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
         assert_eq!(simulator.cycle, 1);
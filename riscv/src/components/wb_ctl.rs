@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "gui-egui")]
-use std::rc::Rc;
+use std::sync::Arc;
 
 #[cfg(feature = "gui-egui")]
 use syncrim::common::EguiComponent;
@@ -27,6 +27,10 @@ pub struct WBCtl {
 
 #[typetag::serde()]
 impl Component for WBCtl {
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -35,16 +39,16 @@ impl Component for WBCtl {
         println!("WBCtl");
     }
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
         let dummy = Input::new("dummy", "out");
-        Box::new(Rc::new(WBCtl {
+        Some(Box::new(Arc::new(WBCtl {
             height: WB_CTL_HEIGHT,
             width: WB_CTL_WIDTH,
             id: id.to_string(),
             pos: (pos.0, pos.1),
             clic_i: dummy.clone(),
             dec_i: dummy.clone(),
-        }))
+        })))
     }
     fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
         if target_port_id.as_str() == WB_CTL_DEC_IN_ID {
@@ -74,14 +78,8 @@ impl Component for WBCtl {
     }
     #[allow(non_snake_case)]
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
-        let dec_we: u32 = simulator
-            .get_input_value(&self.dec_i)
-            .try_into()
-            .unwrap_or(0);
-        let clic_we: u32 = simulator
-            .get_input_value(&self.clic_i)
-            .try_into()
-            .unwrap_or(0);
+        let dec_we: u32 = simulator.get_port_value(1).try_into().unwrap_or(0);
+        let clic_we: u32 = simulator.get_port_value(0).try_into().unwrap_or(0);
         //assert_ne!(dec_we, clic_we);
         let mux_ctl = if dec_we != 0 { 0 } else { 1 };
         let we = if dec_we == 1 || clic_we == 1 { 1 } else { 0 };
@@ -96,25 +94,23 @@ mod test {
     use super::*;
 
     use crate::components::LSBZero;
-    use std::rc::Rc;
+    use std::sync::Arc;
     use syncrim::{
         common::{ComponentStore, Input, Simulator},
         components::ProbeOut,
     };
     #[test]
     fn lsb_zero_test() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("input")),
-                Rc::new(LSBZero {
-                    height: 0.0,
-                    width: 0.0,
-                    id: "lzero".to_string(),
-                    pos: (0.0, 0.0),
-                    data_i: Input::new("input", "out"),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("input")),
+            Arc::new(LSBZero {
+                height: 0.0,
+                width: 0.0,
+                id: "lzero".to_string(),
+                pos: (0.0, 0.0),
+                data_i: Input::new("input", "out"),
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
         assert_eq!(simulator.cycle, 1);
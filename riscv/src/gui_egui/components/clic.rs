@@ -3,7 +3,7 @@ use egui::FontId;
 use egui::{Color32, Pos2, Rect, Response, Rounding, Shape, Stroke, Ui, Vec2};
 use syncrim::common::{EguiComponent, Ports, Simulator};
 use syncrim::gui_egui::component_ui::{
-    drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
+    drag_logic, input_change_id, parameters_editor, pos_drag_value, properties_window,
     rect_with_hover, visualize_ports,
 };
 use syncrim::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
@@ -104,74 +104,9 @@ impl EguiComponent for CLIC {
             resp,
             &mut context.properties_window,
             |ui| {
-                let mut clicked_dropdown = false;
                 input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
                 pos_drag_value(ui, &mut self.pos);
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.csr_addr,
-                    crate::components::CLIC_CSR_ADDR_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.csr_ctl,
-                    crate::components::CLIC_CSR_CTL_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.csr_data,
-                    crate::components::CLIC_CSR_DATA_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.data,
-                    crate::components::CLIC_DATA_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.addr,
-                    crate::components::CLIC_ADDR_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.data_we,
-                    crate::components::CLIC_DATA_WE_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.mret,
-                    crate::components::CLIC_MRET_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.data_size,
-                    crate::components::CLIC_DATA_SIZE_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.pc,
-                    crate::components::CLIC_PC_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-
-                clicked_dropdown
+                parameters_editor(ui, self, id_ports)
             },
         );
 
@@ -2,7 +2,7 @@ use crate::components::Decoder;
 use egui::{Color32, Pos2, Rect, Response, Rounding, Shape, Stroke, Ui, Vec2};
 use syncrim::common::{EguiComponent, Ports, Simulator};
 use syncrim::gui_egui::component_ui::{
-    drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
+    drag_logic, input_change_id, parameters_editor, pos_drag_value, properties_window,
     rect_with_hover, visualize_ports,
 };
 use syncrim::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
@@ -96,17 +96,9 @@ impl EguiComponent for Decoder {
             resp,
             &mut context.properties_window,
             |ui| {
-                let mut clicked_dropdown = false;
                 input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
                 pos_drag_value(ui, &mut self.pos);
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.instruction,
-                    crate::components::DECODER_INSTRUCTION_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                clicked_dropdown
+                parameters_editor(ui, self, id_ports)
             },
         );
 
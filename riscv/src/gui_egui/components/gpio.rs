@@ -3,7 +3,7 @@ use egui::{Color32, Pos2, Rect, Response, Rounding, Shape, Stroke, Ui, Vec2};
 use egui_extras::{Column, TableBuilder};
 use syncrim::common::{EguiComponent, Ports, Simulator};
 use syncrim::gui_egui::component_ui::{
-    drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
+    drag_logic, input_change_id, parameters_editor, pos_drag_value, properties_window,
     rect_with_hover, visualize_ports,
 };
 use syncrim::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
@@ -105,49 +105,57 @@ impl EguiComponent for GPIO {
                                     row.col(|ui| {
                                         ui.label(format!(
                                             "{}",
-                                            self.pins.0.borrow().get(7).unwrap().state as u32
+                                            self.pins.0.read().unwrap().get(7).unwrap().state
+                                                as u32
                                         ));
                                     });
                                     row.col(|ui| {
                                         ui.label(format!(
                                             "{}",
-                                            self.pins.0.borrow().get(6).unwrap().state as u32
+                                            self.pins.0.read().unwrap().get(6).unwrap().state
+                                                as u32
                                         ));
                                     });
                                     row.col(|ui| {
                                         ui.label(format!(
                                             "{}",
-                                            self.pins.0.borrow().get(5).unwrap().state as u32
+                                            self.pins.0.read().unwrap().get(5).unwrap().state
+                                                as u32
                                         ));
                                     });
                                     row.col(|ui| {
                                         ui.label(format!(
                                             "{}",
-                                            self.pins.0.borrow().get(4).unwrap().state as u32
+                                            self.pins.0.read().unwrap().get(4).unwrap().state
+                                                as u32
                                         ));
                                     });
                                     row.col(|ui| {
                                         ui.label(format!(
                                             "{}",
-                                            self.pins.0.borrow().get(3).unwrap().state as u32
+                                            self.pins.0.read().unwrap().get(3).unwrap().state
+                                                as u32
                                         ));
                                     });
                                     row.col(|ui| {
                                         ui.label(format!(
                                             "{}",
-                                            self.pins.0.borrow().get(2).unwrap().state as u32
+                                            self.pins.0.read().unwrap().get(2).unwrap().state
+                                                as u32
                                         ));
                                     });
                                     row.col(|ui| {
                                         ui.label(format!(
                                             "{}",
-                                            self.pins.0.borrow().get(1).unwrap().state as u32
+                                            self.pins.0.read().unwrap().get(1).unwrap().state
+                                                as u32
                                         ));
                                     });
                                     row.col(|ui| {
                                         ui.label(format!(
                                             "{}",
-                                            self.pins.0.borrow().get(0).unwrap().state as u32
+                                            self.pins.0.read().unwrap().get(0).unwrap().state
+                                                as u32
                                         ));
                                     });
                                 })
@@ -200,45 +208,9 @@ impl EguiComponent for GPIO {
             resp,
             &mut context.properties_window,
             |ui| {
-                let mut clicked_dropdown = false;
                 input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
                 pos_drag_value(ui, &mut self.pos);
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.data_i,
-                    crate::components::GPIO_DATA_I_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.addr_i,
-                    crate::components::GPIO_ADDR_I_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.we_i,
-                    crate::components::GPIO_WE_I_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.size_i,
-                    crate::components::GPIO_SIZE_I_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.se_i,
-                    crate::components::GPIO_SE_I_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                clicked_dropdown
+                parameters_editor(ui, self, id_ports)
             },
         );
 
@@ -34,7 +34,7 @@ impl RegFile {
                                 ui.label(format!("{:?}", Reg::try_from(reg).unwrap()));
                             });
                             row.col(|ui| {
-                                let stack_depth = *self.stack_depth_state.borrow() as i32;
+                                let stack_depth = *self.stack_depth_state.read().unwrap() as i32;
                                 let stack_depth = if stack_depth >= 0 {
                                     stack_depth as usize
                                 } else {
@@ -43,12 +43,12 @@ impl RegFile {
                                 if Reg::try_from(reg).unwrap() == Reg::sp {
                                     ui.label(format!(
                                         "0x{:08X}",
-                                        self.registers.0.borrow()[0][reg as usize] //self.registers.0.borrow()[*(self.stack_depth_state.borrow()) as usize].get(reg as usize).unwrap()
+                                        self.registers.0.read().unwrap()[0][reg as usize] //self.registers.0.read().unwrap()[*(self.stack_depth_state.read().unwrap()) as usize].get(reg as usize).unwrap()
                                     ));
                                 } else {
                                     ui.label(format!(
                                         "0x{:08X}",
-                                        self.registers.0.borrow()[stack_depth][reg as usize] //self.registers.0.borrow()[*(self.stack_depth_state.borrow()) as usize].get(reg as usize).unwrap()
+                                        self.registers.0.read().unwrap()[stack_depth][reg as usize] //self.registers.0.read().unwrap()[*(self.stack_depth_state.read().unwrap()) as usize].get(reg as usize).unwrap()
                                     ));
                                 }
                             });
@@ -113,7 +113,7 @@ impl EguiComponent for RegFile {
                                 ui.label(
                                     RichText::new(format!(
                                         "Level {}",
-                                        *self.stack_depth_state.borrow() as i32
+                                        *self.stack_depth_state.read().unwrap() as i32
                                     ))
                                     .size(20.0 * scale),
                                 );
@@ -135,7 +135,8 @@ impl EguiComponent for RegFile {
                                         ));
                                     });
                                     row.col(|ui| {
-                                        let stack_depth = *self.stack_depth_state.borrow() as i32;
+                                        let stack_depth =
+                                            *self.stack_depth_state.read().unwrap() as i32;
                                         let stack_depth = if stack_depth >= 0 {
                                             stack_depth as usize
                                         } else {
@@ -145,7 +146,7 @@ impl EguiComponent for RegFile {
                                             ui.add(Label::new(
                                                 RichText::new(format!(
                                                     "0x{:08x}",
-                                                    self.registers.0.borrow()[0][index]
+                                                    self.registers.0.read().unwrap()[0][index]
                                                 ))
                                                 .size(15.0 * scale),
                                             ));
@@ -153,7 +154,8 @@ impl EguiComponent for RegFile {
                                             ui.add(Label::new(
                                                 RichText::new(format!(
                                                     "0x{:08x}",
-                                                    self.registers.0.borrow()[stack_depth][index]
+                                                    self.registers.0.read().unwrap()[stack_depth]
+                                                        [index]
                                                 ))
                                                 .size(15.0 * scale),
                                             ));
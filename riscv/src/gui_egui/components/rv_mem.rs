@@ -39,7 +39,7 @@ impl RVMem {
                         |index, mut row| {
                             //println!("{}", index);
                             let address = self.range.start as usize + index * 4;
-                            let memory = self.memory.0.borrow().clone();
+                            let memory = self.memory.0.read().unwrap().clone();
                             row.col(|ui| {
                                 ui.label(format!("0x{:08x}", address));
                             });
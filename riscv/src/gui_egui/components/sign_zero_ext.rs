@@ -72,12 +72,8 @@ impl EguiComponent for SZExt {
                     }
                 });
                 ui.label({
-                    let r: Result<SignalUnsigned, String> = s
-                        .get_input_value(&Input {
-                            id: self.id.clone(),
-                            field: "out".to_string(),
-                        })
-                        .try_into();
+                    let r: Result<SignalUnsigned, String> =
+                        s.get_input_value(&Input::new(&self.id, "out")).try_into();
                     match r {
                         Ok(data) => format!("Out {:#x}", data),
                         _ => "Undefined".to_string(),
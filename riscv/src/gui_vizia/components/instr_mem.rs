@@ -1,8 +1,7 @@
 use std::{
-    cell::RefCell,
     collections::{BTreeMap, HashMap, HashSet},
     ops::Range,
-    rc::Rc,
+    sync::{Arc, RwLock},
 };
 
 use crate::components::InstrMem;
@@ -101,7 +100,7 @@ impl ViziaComponent for InstrMem {
                                 }
                             })
                             .color(InstrMemView::breakpoints.map(move |breakpoints| {
-                                if breakpoints.borrow().contains(&(idx * 4)) {
+                                if breakpoints.read().unwrap().contains(&(idx * 4)) {
                                     Color::rgba(255, 0, 0, 255)
                                 //red
                                 } else {
@@ -146,7 +145,7 @@ pub struct InstrMemView {
     start: usize,
     data_slice: Vec<String>,
     slice_range: Range<usize>,
-    breakpoints: Rc<RefCell<HashSet<usize>>>,
+    breakpoints: Arc<RwLock<HashSet<usize>>>,
     pc_input: Input,
     pc: u32,
     symbols: HashMap<usize, String>,
@@ -171,12 +170,12 @@ impl View for InstrMemView {
                     .unwrap();
             }
             DataEvent::Breakpoint(idx) => {
-                if self.breakpoints.borrow().contains(&(idx * 4)) {
+                if self.breakpoints.read().unwrap().contains(&(idx * 4)) {
                     trace!("Breakpoint removed");
-                    self.breakpoints.borrow_mut().remove(&(idx * 4));
+                    self.breakpoints.write().unwrap().remove(&(idx * 4));
                 } else {
                     trace!("New breakpoint!");
-                    self.breakpoints.borrow_mut().insert(idx * 4);
+                    self.breakpoints.write().unwrap().insert(idx * 4);
                 }
             }
         })
@@ -4,8 +4,10 @@ use std::path::PathBuf;
 use syncrim::common::ComponentStore;
 
 fn main() {
+    syncrim::migration::register_migrations(MIGRATIONS);
+    syncrim::registry::register_components(COMPONENTS);
     let path = PathBuf::from("mips.json");
-    let _cs = ComponentStore::load_file(&path);
+    let _cs = ComponentStore::load_file(&path).unwrap_or_else(|e| panic!("{}", e));
 
     #[cfg(feature = "gui-vizia")]
     syncrim::gui_vizia::gui(_cs, &path);
@@ -1,7 +1,14 @@
+use crate::parameter::{Parameter, ParameterValue};
 use petgraph::Graph;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
-use std::{collections::HashMap, rc::Rc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
 
 #[cfg(feature = "gui-egui")]
 use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions, SnapPriority};
@@ -12,16 +19,32 @@ use vizia::prelude::*;
 #[cfg(feature = "gui-vizia")]
 use crate::gui_vizia::ViziaComponent;
 
+use crate::history::History;
+use crate::incremental::Incremental;
+use crate::profile::Profile;
 pub use crate::signal::*;
+use crate::state::Checkpoint;
+use crate::timing::CriticalPath;
+use crate::watchpoint::Watchpoint;
 
 #[cfg(not(any(feature = "gui-vizia", feature = "gui-egui")))]
-type Components = Vec<Rc<dyn Component>>;
+pub(crate) type Components = Vec<Arc<dyn Component>>;
 
 #[cfg(feature = "gui-vizia")]
-type Components = Vec<Rc<dyn ViziaComponent>>;
+pub(crate) type Components = Vec<Arc<dyn ViziaComponent>>;
 
 #[cfg(feature = "gui-egui")]
-pub type Components = Vec<Rc<dyn EguiComponent>>;
+pub type Components = Vec<Arc<dyn EguiComponent>>;
+
+// a component of `Components`, as created by the factories of `registry`
+#[cfg(not(any(feature = "gui-vizia", feature = "gui-egui")))]
+pub type ComponentRc = Arc<dyn Component>;
+
+#[cfg(feature = "gui-vizia")]
+pub type ComponentRc = Arc<dyn ViziaComponent>;
+
+#[cfg(feature = "gui-egui")]
+pub type ComponentRc = Arc<dyn EguiComponent>;
 
 #[cfg_attr(feature = "gui-vizia", derive(Lens))]
 #[derive(Clone)]
@@ -29,21 +52,66 @@ pub struct Simulator {
     pub cycle: usize,
     pub id_start_index: IdStartIndex,
 
+    // Components as given by the model, with subcircuits not flattened
+    pub components: Components,
     // Components stored in topological evaluation order
     pub ordered_components: Components,
     pub sim_state: Vec<Signal>,
+    // declared output widths, indexed as `sim_state`
+    pub(crate) widths: Vec<Option<u8>>,
     pub id_nr_outputs: IdNrOutputs,
     pub id_field_index: IdFieldIndex,
-    pub history: Vec<Vec<Signal>>,
+    pub history: History,
     pub component_ids: Vec<Id>,
     pub graph: Graph<Id, ()>,
+    // a mapping (id -> field -> index) into `sim_state`, allows lookup by `&str`
+    pub(crate) id_field_sim_index: IdFieldSimIndex,
+    // resolved inputs, indexed as `Ports::inputs`, by index in `ordered_components`
+    pub(crate) input_indices: Vec<Vec<SignalIndex>>,
+    // index in `ordered_components` of the component being clocked
+    pub(crate) clocking: usize,
+    // number of sequential components, first in `ordered_components`
+    pub(crate) nr_sequential: usize,
+    // output writes deferred while sequential components sample their inputs
+    pub(crate) pending_writes: Option<Vec<(usize, SignalWrite)>>,
+    // outputs driven from outside the model (index -> value), overriding component writes
+    pub(crate) forced: HashMap<usize, SignalValue>,
+    // outputs to force once sequential components sampled, see `drive_signal`
+    pub(crate) drives: Vec<(usize, SignalValue)>,
+    // watchpoints, checked after each clock
+    pub(crate) watchpoints: Vec<Watchpoint>,
+    // indices of the watchpoints hit by the last clock
+    pub(crate) watchpoint_hits: Vec<usize>,
+    // state of the simulation every `checkpoint_interval` cycles, by cycle
+    pub(crate) checkpoints: BTreeMap<usize, Checkpoint>,
+    pub(crate) checkpoint_interval: usize,
+    // component statistics, if profiling
+    pub(crate) profile: Option<Profile>,
+    // delay annotations of the model, by component id or type
+    pub(crate) delays: BTreeMap<Id, f64>,
+    // longest combinational path, found once the model is built
+    pub(crate) critical_path: CriticalPath,
+    // change tracking, if evaluating incrementally
+    pub(crate) incremental: Option<Incremental>,
     // Running state, (do we need it accessible from other crates?)
     pub(crate) running: bool,
+    // Conditions raised by components during the last clock
+    pub component_condition: Vec<(Id, Condition)>,
+}
+
+// a deferred write to `sim_state`
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum SignalWrite {
+    Value(SignalValue),
+    Fmt(SignalFmt),
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default)]
 pub struct ComponentStore {
     pub store: Components,
+    // delay annotations by component id or type (e.g., `"Add"`), see `Component::delay`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) delays: BTreeMap<Id, f64>,
 }
 
 // a mapping (id -> index)
@@ -58,9 +126,14 @@ pub type IdNrOutputs = HashMap<Id, usize>;
 
 pub type IdFieldIndex = HashMap<(Id, Id), usize>;
 
+pub type IdFieldSimIndex = HashMap<Id, HashMap<Id, usize>>;
+
 // Common functionality for all components
+//
+// Components are shared with the simulation thread, any interior state must thus
+// be behind a lock (e.g., `RwLock`) rather than a `RefCell`.
 #[typetag::serde(tag = "type")]
-pub trait Component {
+pub trait Component: Send + Sync {
     // placeholder
     fn to_(&self) {}
 
@@ -70,9 +143,12 @@ pub trait Component {
     fn set_id_port(&mut self, _target_port_id: Id, _new_input: Input) {
         todo!("Set set_id_port for this Component");
     }
+    /// new instance of the same type, by default from the factory of `registry`, none
+    /// if the type is not registered
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, _id: &str, _pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
-        todo!("implement dummy component factory for this component")
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
+        crate::registry::component_type(self.typetag_name())
+            .map(|component_type| Box::new((component_type.new)(id, pos)))
     }
     /// evaluate component based on current internal state
     fn clock(&self, _simulator: &mut Simulator) -> Result<(), Condition> {
@@ -82,6 +158,61 @@ pub trait Component {
     fn un_clock(&self) {}
     /// reset component internal state to initial value
     fn reset(&self) {}
+    /// propagation delay (in arbitrary units) from inputs to outputs, or from clock to
+    /// outputs for sequential components, unless annotated in the model
+    ///
+    /// By default 1 for combinational components, and 0 for sequential components and
+    /// components without inputs (e.g., constants) or outputs (e.g., probes, wires).
+    fn delay(&self) -> f64 {
+        let ports = self.get_id_ports().1;
+        match ports.out_type {
+            _ if ports.inputs.is_empty() || ports.outputs.is_empty() => 0.0,
+            OutputType::Combinatorial => 1.0,
+            OutputType::Sequential => 0.0,
+        }
+    }
+    /// library (crate) providing the component type, listed in model files
+    fn library(&self) -> &'static str {
+        let path = std::any::type_name::<Self>();
+        path.split("::").next().unwrap_or(path)
+    }
+    /// true if the outputs depend only on the current inputs, without internal state
+    /// or side effects, allowing evaluation to be skipped while the inputs are
+    /// unchanged, see `Simulator::set_incremental`
+    fn is_pure(&self) -> bool {
+        false
+    }
+    /// internal state not kept in `sim_state` (e.g., memory contents), if any
+    fn save_state(&self) -> Option<serde_json::Value> {
+        None
+    }
+    /// internal state for the checkpoints of `Simulator::goto_cycle`, taken often, thus
+    /// the live contents only, without undo logs (e.g., of `un_clock`), restored by
+    /// `load_state` keeping the undo log up to the checkpoint
+    fn save_checkpoint(&self) -> Option<serde_json::Value> {
+        self.save_state()
+    }
+    /// restore internal state saved by `save_state` or `save_checkpoint`
+    fn load_state(&self, _state: serde_json::Value) -> Result<(), String> {
+        Ok(())
+    }
+    /// editable parameters, see `parameter`
+    fn parameters(&self) -> Vec<Parameter> {
+        vec![]
+    }
+    /// set parameter listed by `parameters`, use `parameter::set_parameter` to check
+    /// the value against its constraints first
+    fn set_parameter(&mut self, name: &str, _value: ParameterValue) -> Result<(), String> {
+        Err(crate::parameter::unknown_parameter(name))
+    }
+    /// directory of the model file the component is loaded from, for relative paths
+    /// (e.g., the model file of a `SubCircuit`)
+    fn set_model_dir(&mut self, _dir: &Path) {}
+    /// expand hierarchical component (e.g., `SubCircuit`) into flat components,
+    /// done when building the simulator
+    fn flatten(&self) -> Option<Result<Components, String>> {
+        None
+    }
     /// any
     fn as_any(&self) -> &dyn Any;
 }
@@ -94,6 +225,160 @@ pub enum Condition {
     Halt(String),
 }
 
+/// Errors found in a model when building the `Simulator`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModelError {
+    /// component identifier defined more than once
+    DuplicateId(Id),
+    /// output field defined more than once for the component
+    DuplicateField { id: Id, field: Id },
+    /// input port of component `id` refers to an undefined component
+    UnknownComponent { id: Id, port: Id, input: Input },
+    /// input port of component `id` refers to an undefined output field
+    UnknownField { id: Id, port: Id, input: Input },
+    /// combinational loop, with the identifiers of the components involved
+    CombinationalLoop(Vec<Id>),
+    /// hierarchical component `id` could not be flattened
+    SubCircuit { id: Id, error: String },
+    /// port of the type of component `id` missing from the component, see `registry`
+    MissingPort { id: Id, type_name: String, port: Id },
+    /// input port of component `id` is connected to an output of another width
+    WidthMismatch {
+        id: Id,
+        port: Id,
+        width: u8,
+        input: Input,
+        input_width: u8,
+    },
+}
+
+impl fmt::Display for ModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelError::DuplicateId(id) => {
+                write!(f, "Component identifier {:?} is defined twice", id)
+            }
+            ModelError::DuplicateField { id, field } => {
+                write!(f, "Component {:?} field {:?} is defined twice", id, field)
+            }
+            ModelError::UnknownComponent { id, port, input } => write!(
+                f,
+                "Component {:?} port {:?} is connected to {}, but there is no component {:?}",
+                id, port, input, input.id
+            ),
+            ModelError::UnknownField { id, port, input } => write!(
+                f,
+                "Component {:?} port {:?} is connected to {}, but {:?} has no output {:?}",
+                id, port, input, input.id, input.field
+            ),
+            ModelError::CombinationalLoop(ids) => {
+                write!(f, "Combinational loop through {}", ids.join(" -> "))
+            }
+            ModelError::SubCircuit { id, error } => {
+                write!(f, "Subcircuit {:?}: {}", id, error)
+            }
+            ModelError::MissingPort {
+                id,
+                type_name,
+                port,
+            } => write!(
+                f,
+                "Component {:?} has no port {:?} of its type {}",
+                id, port, type_name
+            ),
+            ModelError::WidthMismatch {
+                id,
+                port,
+                width,
+                input,
+                input_width,
+            } => write!(
+                f,
+                "Component {:?} port {:?} ({} bits) is connected to {} ({} bits)",
+                id, port, width, input, input_width
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ModelError {}
+
+/// Errors reading or writing a model file
+#[derive(Debug)]
+pub struct FileError {
+    /// model file, if any
+    pub path: Option<PathBuf>,
+    /// line and column (1-based) in the file, if known
+    pub position: Option<(usize, usize)>,
+    /// identifier of the component involved, if any
+    pub id: Option<Id>,
+    pub kind: FileErrorKind,
+}
+
+#[derive(Debug)]
+pub enum FileErrorKind {
+    /// file could not be read or written
+    Io(io::Error),
+    /// not valid JSON, or not a model
+    Json(String),
+    /// model could not be upgraded to the current format version, see `migration`
+    Migration(String),
+    /// component type not known, with the known types (`typetag` names)
+    UnknownType {
+        type_name: String,
+        known: Vec<String>,
+    },
+    /// component could not be read, e.g., a field is missing
+    Component(String),
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(path) = &self.path {
+            write!(f, "{}:", path.display())?;
+        }
+        if let Some((line, column)) = self.position {
+            write!(f, "{}:{}:", line, column)?;
+        }
+        if self.path.is_some() || self.position.is_some() {
+            write!(f, " ")?;
+        }
+        if let Some(id) = &self.id {
+            write!(f, "component {:?}: ", id)?;
+        }
+        match &self.kind {
+            FileErrorKind::Io(e) => write!(f, "{}", e),
+            FileErrorKind::Json(e) | FileErrorKind::Migration(e) | FileErrorKind::Component(e) => {
+                write!(f, "{}", e)
+            }
+            FileErrorKind::UnknownType { type_name, known } => write!(
+                f,
+                "unknown component type {:?}, known types are {}",
+                type_name,
+                known.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FileError {}
+
+/// All errors found in a model, reported at once
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModelErrors(pub Vec<ModelError>);
+
+impl fmt::Display for ModelErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "The model contains {} error(s):", self.0.len())?;
+        for e in &self.0 {
+            writeln!(f, "  {}", e)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ModelErrors {}
+
 #[cfg(feature = "gui-egui")]
 use crate::gui_egui::gui::EguiExtra;
 
@@ -165,6 +450,10 @@ pub struct Ports {
     pub inputs: Vec<InputPort>,
     pub out_type: OutputType,
     pub outputs: Vec<Id>,
+    // width (in bits) of input ports, by port id, see `with_input_width`
+    input_widths: Vec<(Id, u8)>,
+    // width (in bits) of outputs, by output id, see `with_output_width`
+    output_widths: Vec<(Id, u8)>,
 }
 
 impl Ports {
@@ -173,11 +462,42 @@ impl Ports {
             inputs: inputs.into_iter().cloned().collect(),
             out_type,
             outputs: outputs.into_iter().map(|s| s.into()).collect(),
+            input_widths: vec![],
+            output_widths: vec![],
         }
     }
+
+    /// declare the width of an input port, ports not declared accept any width
+    pub fn with_input_width(mut self, port_id: &str, width: u8) -> Self {
+        self.input_widths.push((port_id.into(), width));
+        self
+    }
+
+    /// declare the width of an output, used for checking and VCD export
+    pub fn with_output_width(mut self, output: &str, width: u8) -> Self {
+        self.output_widths.push((output.into(), width));
+        self
+    }
+
+    /// declared width of input port
+    pub fn input_width(&self, port_id: &str) -> Option<u8> {
+        find_width(&self.input_widths, port_id)
+    }
+
+    /// declared width of output
+    pub fn output_width(&self, output: &str) -> Option<u8> {
+        find_width(&self.output_widths, output)
+    }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+fn find_width(widths: &[(Id, u8)], id: &str) -> Option<u8> {
+    widths
+        .iter()
+        .find(|(i, _)| i == id)
+        .map(|(_, width)| *width)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct Input {
     pub id: Id,
     pub field: Id,
@@ -192,6 +512,31 @@ impl Input {
     }
 }
 
+/// Flat index into `Simulator::sim_state`, see `Simulator::resolve_input`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SignalIndex(pub usize);
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.id, self.field)
+    }
+}
+
+// parse a signal path on the form `id/field`
+impl FromStr for Input {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.rsplit_once('/') {
+            Some((id, field)) if !id.is_empty() && !field.is_empty() => Ok(Input::new(id, field)),
+            _ => Err(format!(
+                "Expected signal on the form `id/field`, found {:?}",
+                s
+            )),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct InputPort {
     pub port_id: Id,
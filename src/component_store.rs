@@ -1,28 +1,91 @@
-use crate::common::ComponentStore;
+use crate::common::{ComponentStore, Components, FileError, FileErrorKind, Id};
+use crate::registry;
 
-use std::{fs::File, io::prelude::*, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use log::*;
 
 impl ComponentStore {
-    pub fn load(json: &str) -> Self {
-        serde_json::from_str(json).unwrap()
+    /// model of the components, without delay annotations
+    pub fn new(store: Components) -> Self {
+        ComponentStore {
+            store,
+            delays: BTreeMap::new(),
+        }
     }
 
-    pub fn load_file(path: &PathBuf) -> Self {
-        let mut file = File::open(path).unwrap();
-        let mut json = String::new();
-        file.read_to_string(&mut json).unwrap();
+    /// model with delay annotations by component id or type (e.g., `"Add"`), see
+    /// `Component::delay`
+    pub fn with_delays(self, delays: BTreeMap<Id, f64>) -> Self {
+        ComponentStore { delays, ..self }
+    }
+
+    /// delay annotations of the model
+    pub fn delays(&self) -> &BTreeMap<Id, f64> {
+        &self.delays
+    }
+
+    /// model from JSON, upgraded to the current format version, see `migration`
+    pub fn load(json: &str) -> Result<Self, FileError> {
+        ComponentStore::from_json(json)
+    }
 
-        ComponentStore::load(&json)
+    /// model from a file, relative paths of components are resolved against the
+    /// directory of the file, see `Component::set_model_dir`
+    pub fn load_file(path: &PathBuf) -> Result<Self, FileError> {
+        let json = fs::read_to_string(path).map_err(|e| FileError {
+            path: Some(path.clone()),
+            position: None,
+            id: None,
+            kind: FileErrorKind::Io(e),
+        })?;
+        let mut cs = ComponentStore::from_json(&json).map_err(|e| FileError {
+            path: Some(path.clone()),
+            ..e
+        })?;
+        cs.set_model_dir(path.parent().unwrap_or(Path::new("")));
+        Ok(cs)
     }
 
-    pub fn save_file(&self, path: &PathBuf) {
-        let json = serde_json::to_string(self).unwrap();
+    /// set the directory of the model file of all components, see
+    /// `Component::set_model_dir`
+    pub fn set_model_dir(&mut self, dir: &Path) {
+        for component in &mut self.store {
+            // only components not shared yet, as after loading
+            if let Some(component) = Arc::get_mut(component) {
+                component.set_model_dir(dir);
+            }
+        }
+    }
+
+    pub fn save_file(&self, path: &PathBuf) -> Result<(), FileError> {
+        let error = |kind| FileError {
+            path: Some(path.clone()),
+            position: None,
+            id: None,
+            kind,
+        };
+        let json = self
+            .to_json()
+            .map_err(|e| error(FileErrorKind::Json(e.to_string())))?;
         trace!("json: {}", json);
         trace!("path {:?}", path);
-        let mut file = File::create(path).unwrap();
-        file.write_all(json.as_bytes()).unwrap();
+        fs::write(path, json).map_err(|e| error(FileErrorKind::Io(e)))
+    }
+
+    /// names of the component types known when loading models, as registered
+    pub fn known_types() -> Vec<String> {
+        let mut names: Vec<String> = registry::component_types()
+            .iter()
+            .map(|t| t.name.to_string())
+            .collect();
+        names.sort();
+        names
     }
 
     pub fn to_(&self) {
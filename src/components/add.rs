@@ -7,7 +7,7 @@ use crate::common::{
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
-use std::rc::Rc;
+use std::sync::Arc;
 pub const ADD_A_IN_ID: &str = "a_in";
 pub const ADD_B_IN_ID: &str = "b_in";
 
@@ -28,14 +28,14 @@ impl Component for Add {
         trace!("Add");
     }
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
         let dummy_input = Input::new("dummy", "out");
-        Box::new(Rc::new(Add {
+        Some(Box::new(Arc::new(Add {
             id: id.to_string(),
             pos: (pos.0, pos.1),
             a_in: dummy_input.clone(),
             b_in: dummy_input.clone(),
-        }))
+        })))
     }
     fn get_id_ports(&self) -> (Id, Ports) {
         (
@@ -53,15 +53,19 @@ impl Component for Add {
                 ],
                 OutputType::Combinatorial,
                 vec![ADD_OUT_ID, ADD_OVERFLOW_ID],
-            ),
+            )
+            .with_input_width(ADD_A_IN_ID, SignalUnsigned::BITS as u8)
+            .with_input_width(ADD_B_IN_ID, SignalUnsigned::BITS as u8)
+            .with_output_width(ADD_OUT_ID, SignalUnsigned::BITS as u8)
+            .with_output_width(ADD_OVERFLOW_ID, 1),
         )
     }
 
     // propagate addition to output
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
-        // get input values
-        let a_in = u32::try_from(simulator.get_input_value(&self.a_in));
-        let b_in = u32::try_from(simulator.get_input_value(&self.b_in));
+        // get input values, ports as in `get_id_ports`
+        let a_in = u32::try_from(simulator.get_port_value(0));
+        let b_in = u32::try_from(simulator.get_port_value(1));
 
         let (value, overflow, res) = match (&a_in, &b_in) {
             (Ok(a), Ok(b)) => {
@@ -102,6 +106,10 @@ impl Component for Add {
         }
     }
 
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -117,8 +125,8 @@ impl Add {
         }
     }
 
-    pub fn rc_new(id: &str, pos: (f32, f32), a_in: Input, b_in: Input) -> Rc<Self> {
-        Rc::new(Add::new(id, pos, a_in, b_in))
+    pub fn arc_new(id: &str, pos: (f32, f32), a_in: Input, b_in: Input) -> Arc<Self> {
+        Arc::new(Add::new(id, pos, a_in, b_in))
     }
 }
 
@@ -130,22 +138,20 @@ mod test {
         common::{ComponentStore, Input, SignalUnsigned, Simulator},
         components::ProbeOut,
     };
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     #[test]
     fn test_add() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("po1")),
-                Rc::new(ProbeOut::new("po2")),
-                Rc::new(Add {
-                    id: "add".to_string(),
-                    pos: (0.0, 0.0),
-                    a_in: Input::new("po1", "out"),
-                    b_in: Input::new("po2", "out"),
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("po1")),
+            Arc::new(ProbeOut::new("po2")),
+            Arc::new(Add {
+                id: "add".to_string(),
+                pos: (0.0, 0.0),
+                a_in: Input::new("po1", "out"),
+                b_in: Input::new("po2", "out"),
+            }),
+        ]);
         let mut simulator = Simulator::new(cs).unwrap();
 
         assert_eq!(simulator.cycle, 1);
@@ -155,7 +161,7 @@ mod test {
         let add_overflow = &Input::new("add", "overflow");
 
         // reset
-        assert_eq!(simulator.get_input_value(add_val), (0 + 0).into());
+        assert_eq!(simulator.get_input_value(add_val), 0.into());
         assert_eq!(
             simulator.get_input_value(add_overflow),
             (false as SignalUnsigned).into()
@@ -193,5 +199,16 @@ mod test {
             simulator.get_input_value(add_overflow),
             (true as SignalUnsigned).into()
         );
+
+        // unknown inputs give unknown outputs
+        for value in [SignalValue::Unknown, SignalValue::Uninitialized] {
+            simulator.set_out_value("po2", "out", value);
+            simulator.clock();
+            assert_eq!(simulator.get_input_value(add_val), SignalValue::Unknown);
+            assert_eq!(
+                simulator.get_input_value(add_overflow),
+                SignalValue::Unknown
+            );
+        }
     }
 }
@@ -1,9 +1,9 @@
 #[cfg(feature = "gui-egui")]
 use crate::common::EguiComponent;
-use crate::common::{Component, Condition, Id, OutputType, Ports, Signal, Simulator};
+use crate::common::{Component, Condition, Id, OutputType, Ports, Signal, SignalValue, Simulator};
 use log::*;
 use serde::{Deserialize, Serialize};
-use std::{convert::Into, rc::Rc};
+use std::{convert::Into, sync::Arc};
 pub const CONSTANT_OUT_ID: &str = "out";
 use std::any::Any;
 #[derive(Serialize, Deserialize, Clone)]
@@ -19,23 +19,26 @@ impl Component for Constant {
         trace!("constant {:?}", self.value);
     }
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
-        Box::new(Rc::new(Constant {
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
+        Some(Box::new(Arc::new(Constant {
             id: id.to_string(),
             pos: (pos.0, pos.1),
             value: 0.into(),
-        }))
+        })))
     }
     fn get_id_ports(&self) -> (Id, Ports) {
-        (
-            self.id.clone(),
-            Ports::new(
-                // Constants do not take any inputs
-                vec![],
-                OutputType::Combinatorial,
-                vec![CONSTANT_OUT_ID],
-            ),
-        )
+        let ports = Ports::new(
+            // Constants do not take any inputs
+            vec![],
+            OutputType::Combinatorial,
+            vec![CONSTANT_OUT_ID],
+        );
+        // only wide constants carry their width
+        let ports = match self.value.get_value() {
+            SignalValue::Wide { width, .. } => ports.with_output_width(CONSTANT_OUT_ID, width),
+            _ => ports,
+        };
+        (self.id.clone(), ports)
     }
 
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
@@ -43,6 +46,10 @@ impl Component for Constant {
         Ok(())
     }
 
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -57,7 +64,7 @@ impl Constant {
         }
     }
 
-    pub fn rc_new(id: &str, pos: (f32, f32), value: impl Into<Signal>) -> Rc<Self> {
-        Rc::new(Constant::new(id, pos, value))
+    pub fn arc_new(id: &str, pos: (f32, f32), value: impl Into<Signal>) -> Arc<Self> {
+        Arc::new(Constant::new(id, pos, value))
     }
 }
@@ -4,6 +4,8 @@ use crate::common::{
     Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalSigned, SignalUnsigned,
     SignalValue, Simulator,
 };
+use crate::parameter::{input_parameters, set_input_parameter, Parameter, ParameterValue};
+use crate::profile::Access;
 use log::*;
 use num_enum::IntoPrimitive;
 use num_enum::TryFromPrimitive;
@@ -11,7 +13,11 @@ use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::ops::Deref;
 use std::ops::Range;
-use std::{cell::RefCell, collections::BTreeMap, convert::TryFrom, rc::Rc};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    convert::TryFrom,
+    sync::{Arc, RwLock},
+};
 
 pub const MEM_DATA_ID: &str = "data";
 pub const MEM_ADDR_ID: &str = "addr";
@@ -42,7 +48,28 @@ pub struct Mem {
     // memory
     pub(crate) memory: Memory,
     pub(crate) range: Range<u32>,
-    // later history... tbd
+    // bytes overwritten by the last clocks, for reverse simulation
+    #[serde(skip)]
+    pub(crate) history: Arc<RwLock<MemHistory>>,
+}
+
+// previous contents of the bytes written by a clock (`None` if not present)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub(crate) struct MemOp {
+    // clock (since reset) writing
+    clock: usize,
+    bytes: Vec<(usize, Option<u8>)>,
+}
+
+// writes of the clocks kept in simulator history
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub(crate) struct MemHistory {
+    // clocks since reset
+    clocks: usize,
+    // writes, oldest first, only of clocks writing
+    ops: VecDeque<MemOp>,
+    // contents before the first write, restored by `reset`
+    initial: Option<BTreeMap<usize, u8>>,
 }
 
 impl Mem {
@@ -74,11 +101,12 @@ impl Mem {
             size,
             memory: Memory::new(memory),
             range,
+            history: Arc::default(),
         }
     }
 
     #[allow(clippy::too_many_arguments)]
-    pub fn rc_new(
+    pub fn arc_new(
         id: &str,
         pos: (f32, f32),
         width: f32,
@@ -90,19 +118,17 @@ impl Mem {
         sext: Input,
         size: Input,
         range: Range<u32>,
-    ) -> Rc<Self> {
+    ) -> Arc<Self> {
         let mut mem = BTreeMap::new();
         //fill the defined memory range with zeroes
-        for i in range.clone() {
-            mem.insert(i as usize, 0u8);
-        }
-        Rc::new(Mem::new(
+        fill_range(&mut mem, &range);
+        Arc::new(Mem::new(
             id, pos, width, height, big_endian, data, addr, ctrl, sext, size, mem, range,
         ))
     }
 
     #[allow(clippy::too_many_arguments)]
-    pub fn rc_new_from_bytes(
+    pub fn arc_new_from_bytes(
         id: &str,
         pos: (f32, f32),
         width: f32,
@@ -115,15 +141,22 @@ impl Mem {
         size: Input,
         memory: BTreeMap<usize, u8>,
         range: Range<u32>,
-    ) -> Rc<Self> {
-        Rc::new(Mem::new(
+    ) -> Arc<Self> {
+        Arc::new(Mem::new(
             id, pos, width, height, big_endian, data, addr, ctrl, sext, size, memory, range,
         ))
     }
 }
 
+// fill the bytes of `range` not present with zeroes
+fn fill_range(memory: &mut BTreeMap<usize, u8>, range: &Range<u32>) {
+    for i in range.clone() {
+        memory.entry(i as usize).or_insert(0);
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct Memory(pub Rc<RefCell<BTreeMap<usize, u8>>>);
+pub struct Memory(pub Arc<RwLock<BTreeMap<usize, u8>>>);
 
 impl Default for Memory {
     fn default() -> Self {
@@ -133,16 +166,16 @@ impl Default for Memory {
 
 impl Memory {
     pub fn new(data: BTreeMap<usize, u8>) -> Self {
-        Memory(Rc::new(RefCell::new(data)))
+        Memory(Arc::new(RwLock::new(data)))
     }
 
     fn align(&self, addr: usize, size: usize) -> SignalValue {
-        ((addr % size != 0) as SignalUnsigned).into()
+        (!addr.is_multiple_of(size) as SignalUnsigned).into()
     }
 
     fn read(&self, addr: usize, size: usize, sign: bool, big_endian: bool) -> SignalValue {
         let data: Vec<u8> = (0..size)
-            .map(|i| *self.0.borrow().get(&(addr + i)).unwrap_or(&0))
+            .map(|i| *self.0.read().unwrap().get(&(addr + i)).unwrap_or(&0))
             .collect();
 
         let data = data.as_slice();
@@ -208,12 +241,11 @@ impl Memory {
         .into()
     }
 
-    fn write(&self, addr: usize, size: usize, big_endian: bool, data: SignalValue) {
-        let data: SignalUnsigned = data.try_into().unwrap();
+    fn write(&self, addr: usize, size: usize, big_endian: bool, data: SignalUnsigned) {
         match size {
             1 => {
                 trace!("write byte");
-                self.0.borrow_mut().insert(addr, data as u8);
+                self.0.write().unwrap().insert(addr, data as u8);
             }
             2 => {
                 if big_endian {
@@ -223,7 +255,7 @@ impl Memory {
                         .iter()
                         .enumerate()
                         .for_each(|(i, bytes)| {
-                            self.0.borrow_mut().insert(addr + i, *bytes);
+                            self.0.write().unwrap().insert(addr + i, *bytes);
                         })
                 } else {
                     trace!("write half word le");
@@ -232,7 +264,7 @@ impl Memory {
                         .iter()
                         .enumerate()
                         .for_each(|(i, bytes)| {
-                            self.0.borrow_mut().insert(addr + i, *bytes);
+                            self.0.write().unwrap().insert(addr + i, *bytes);
                         })
                 }
             }
@@ -244,7 +276,7 @@ impl Memory {
                         .iter()
                         .enumerate()
                         .for_each(|(i, bytes)| {
-                            self.0.borrow_mut().insert(addr + i, *bytes);
+                            self.0.write().unwrap().insert(addr + i, *bytes);
                         })
                 } else {
                     trace!("write word le");
@@ -252,7 +284,7 @@ impl Memory {
                         .iter()
                         .enumerate()
                         .for_each(|(i, bytes)| {
-                            self.0.borrow_mut().insert(addr + i, *bytes);
+                            self.0.write().unwrap().insert(addr + i, *bytes);
                         })
                 }
             }
@@ -283,9 +315,9 @@ impl Component for Mem {
         trace!("Mem");
     }
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
         let dummy_input = Input::new("dummy", "out");
-        Box::new(Rc::new(Mem {
+        Some(Box::new(Arc::new(Mem {
             id: id.to_string(),
             pos: (pos.0, pos.1),
             width: 100.0,
@@ -301,7 +333,8 @@ impl Component for Mem {
                 end: 0x20,
             },
             memory: Memory::new(BTreeMap::new()),
-        }))
+            history: Arc::default(),
+        })))
     }
     fn get_id_ports(&self) -> (Id, Ports) {
         (
@@ -335,68 +368,145 @@ impl Component for Mem {
         )
     }
 
+    // unknown (or invalid) inputs yield unknown outputs, and writes are skipped
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
-        let data = simulator.get_input_value(&self.data);
-        let addr = simulator.get_input_value(&self.addr);
-        let size = simulator.get_input_value(&self.size);
-        let sign = simulator.get_input_value(&self.sext);
-
-        match simulator.get_input_value(&self.ctrl) {
-            SignalValue::Data(ctrl) => {
-                let ctrl = MemCtrl::try_from(ctrl as u8).unwrap();
-                match ctrl {
-                    MemCtrl::Read => {
-                        let addr: u32 = addr.try_into().unwrap();
-                        let size: u32 = size.try_into().unwrap();
-                        let sign: u32 = sign.try_into().unwrap();
-                        trace!("read addr {:?} size {:?}", addr, size);
-                        let value = self.memory.read(
-                            addr as usize,
-                            size as usize,
-                            sign != 0,
-                            self.big_endian,
-                        );
-                        simulator.set_out_value(&self.id, "data_o", value);
-                        let value = self.memory.align(addr as usize, size as usize);
-                        trace!("align {:?}", value);
-                        simulator.set_out_value(&self.id, "err", value); // align
-                    }
-                    MemCtrl::Write => {
-                        let addr: u32 = addr.try_into().unwrap();
-                        let size: u32 = size.try_into().unwrap();
-                        trace!("write addr {:?} size {:?}", addr, size);
+        // ports as in `get_id_ports`
+        let data = SignalUnsigned::try_from(simulator.get_port_value(0));
+        let addr = SignalUnsigned::try_from(simulator.get_port_value(1));
+        let size = SignalUnsigned::try_from(simulator.get_port_value(4))
+            .ok()
+            .filter(|size| matches!(size, 1 | 2 | 4));
+        let sign = SignalUnsigned::try_from(simulator.get_port_value(3));
+        let ctrl = SignalUnsigned::try_from(simulator.get_port_value(2))
+            .ok()
+            .and_then(|ctrl| u8::try_from(ctrl).ok())
+            .and_then(|ctrl| MemCtrl::try_from(ctrl).ok());
+
+        let mut res = Ok(());
+        let mut written = vec![];
+        match (ctrl, addr, size) {
+            (Some(MemCtrl::Read), Ok(addr), Some(size)) => {
+                trace!("read addr {:?} size {:?}", addr, size);
+                simulator.record_access(Access::Read);
+                let value = match sign {
+                    Ok(sign) => {
                         self.memory
-                            .write(addr as usize, size as usize, self.big_endian, data);
-                        let value = self.memory.align(addr as usize, size as usize);
-                        trace!("align {:?}", value);
-                        simulator.set_out_value(&self.id, "err", value); // align
+                            .read(addr as usize, size as usize, sign != 0, self.big_endian)
                     }
-                    MemCtrl::None => {
-                        trace!("no read/write");
+                    Err(_) => SignalValue::Unknown,
+                };
+                simulator.set_out_value(&self.id, "data_o", value);
+                let value = self.memory.align(addr as usize, size as usize);
+                trace!("align {:?}", value);
+                simulator.set_out_value(&self.id, "err", value); // align
+            }
+            (Some(MemCtrl::Write), Ok(addr), Some(size)) => {
+                trace!("write addr {:?} size {:?}", addr, size);
+                match data {
+                    Ok(data) => {
+                        simulator.record_access(Access::Write);
+                        let (addr, size) = (addr as usize, size as usize);
+                        let memory = self.memory.0.read().unwrap();
+                        written = (addr..addr + size)
+                            .map(|a| (a, memory.get(&a).copied()))
+                            .collect();
+                        drop(memory);
+                        self.memory.write(addr, size, self.big_endian, data)
+                    }
+                    Err(_) => {
+                        res = Err(Condition::Warning(format!(
+                            "write of unknown data to {:#x} skipped",
+                            addr
+                        )))
                     }
                 }
+                let value = self.memory.align(addr as usize, size as usize);
+                trace!("align {:?}", value);
+                simulator.set_out_value(&self.id, "err", value); // align
             }
-            _ => {
+            (Some(MemCtrl::None), ..) => {
+                trace!("no read/write");
+            }
+            (ctrl, ..) => {
+                if ctrl.is_some() {
+                    res = Err(Condition::Warning(
+                        "read/write with unknown address or size skipped".to_string(),
+                    ));
+                }
                 simulator.set_out_value(&self.id, "data_o", SignalValue::Unknown);
                 simulator.set_out_value(&self.id, "err", SignalValue::Unknown); // align
             }
         }
 
-        for (idx, i) in self.memory.0.borrow().iter().enumerate() {
+        let memory = self.memory.0.read().unwrap();
+        for (idx, i) in memory.iter().enumerate() {
             if i.0 % 4 == 0 && idx < 40 {
                 //only print 40 bytes so the trace isn't busy
                 trace!(
                     "0x{:08x} : 0x{:02x}{:02x}{:02x}{:02x}",
                     i.0,
-                    self.memory.0.borrow().get(i.0).unwrap_or(&0u8),
-                    self.memory.0.borrow().get(&(i.0 + 1)).unwrap_or(&0u8),
-                    self.memory.0.borrow().get(&(i.0 + 2)).unwrap_or(&0u8),
-                    self.memory.0.borrow().get(&(i.0 + 3)).unwrap_or(&0u8),
+                    memory.get(i.0).unwrap_or(&0u8),
+                    memory.get(&(i.0 + 1)).unwrap_or(&0u8),
+                    memory.get(&(i.0 + 2)).unwrap_or(&0u8),
+                    memory.get(&(i.0 + 3)).unwrap_or(&0u8),
                 )
             }
         }
+        drop(memory);
+        let mut history = self.history.write().unwrap();
+        history.clocks += 1;
+        if !written.is_empty() {
+            if history.initial.is_none() {
+                let mut initial = self.memory.0.read().unwrap().clone();
+                // before this write
+                for (addr, byte) in written.iter().rev() {
+                    match byte {
+                        Some(byte) => initial.insert(*addr, *byte),
+                        None => initial.remove(addr),
+                    };
+                }
+                history.initial = Some(initial);
+            }
+            let clock = history.clocks;
+            history.ops.push_back(MemOp {
+                clock,
+                bytes: written,
+            });
+        }
+        // writes of clocks beyond history depth cannot be reversed
+        if let Some(depth) = simulator.history.depth() {
+            while history
+                .ops
+                .front()
+                .is_some_and(|op| op.clock + depth <= history.clocks)
+            {
+                history.ops.pop_front();
+            }
+        }
 
-        Ok(())
+        res
+    }
+
+    // restore the bytes overwritten by the last clock
+    fn un_clock(&self) {
+        let mut history = self.history.write().unwrap();
+        if history
+            .ops
+            .back()
+            .is_some_and(|op| op.clock == history.clocks)
+        {
+            let op = history.ops.pop_back().unwrap();
+            self.undo(op);
+        }
+        history.clocks = history.clocks.saturating_sub(1);
+    }
+
+    // restore the contents before the first write
+    fn reset(&self) {
+        let history = std::mem::take(&mut *self.history.write().unwrap());
+        if let Some(initial) = history.initial {
+            *self.memory.0.write().unwrap() = initial;
+        }
     }
 
     fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
@@ -410,13 +520,108 @@ impl Component for Mem {
         }
     }
 
+    fn parameters(&self) -> Vec<Parameter> {
+        let endianness = match self.big_endian {
+            true => "big",
+            false => "little",
+        };
+        vec![
+            Parameter::int("width", self.width as i64, 0, 400),
+            Parameter::int("height", self.height as i64, 0, 400),
+            Parameter::choice("endianness", endianness, &["little", "big"]),
+            Parameter::range(
+                "range",
+                self.range.start as u64..self.range.end as u64,
+                0,
+                u32::MAX as u64,
+            ),
+        ]
+        .into_iter()
+        .chain(input_parameters(self))
+        .collect()
+    }
+
+    fn set_parameter(&mut self, name: &str, value: ParameterValue) -> Result<(), String> {
+        match name {
+            "width" => self.width = value.as_int()? as f32,
+            "height" => self.height = value.as_int()? as f32,
+            "endianness" => self.big_endian = value.as_str()? == "big",
+            "range" => {
+                // contents of the new range are kept, as created by `arc_new` otherwise
+                let range = value.as_range()?;
+                self.range = range.start as u32..range.end as u32;
+                let mut memory = self.memory.0.write().unwrap();
+                memory.retain(|addr, _| self.range.contains(&(*addr as u32)));
+                fill_range(&mut memory, &self.range);
+            }
+            _ => return set_input_parameter(self, name, value),
+        }
+        Ok(())
+    }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        let history = self.history.read().unwrap().clone();
+        serde_json::to_value(MemState {
+            memory: self.memory.0.read().unwrap().clone(),
+            clocks: history.clocks,
+            history: Some(history),
+        })
+        .ok()
+    }
+
+    fn save_checkpoint(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(MemState {
+            memory: self.memory.0.read().unwrap().clone(),
+            clocks: self.history.read().unwrap().clocks,
+            history: None,
+        })
+        .ok()
+    }
+
+    fn load_state(&self, state: serde_json::Value) -> Result<(), String> {
+        let state: MemState = serde_json::from_value(state).map_err(|e| e.to_string())?;
+        *self.memory.0.write().unwrap() = state.memory;
+        let mut history = self.history.write().unwrap();
+        match state.history {
+            Some(saved) => *history = saved,
+            // a checkpoint, the writes up to it are kept
+            None => {
+                history.ops.retain(|op| op.clock <= state.clocks);
+                history.clocks = state.clocks;
+            }
+        }
+        Ok(())
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
 }
 
+impl Mem {
+    fn undo(&self, op: MemOp) {
+        let mut memory = self.memory.0.write().unwrap();
+        for (addr, byte) in op.bytes.into_iter().rev() {
+            match byte {
+                Some(byte) => memory.insert(addr, byte),
+                None => memory.remove(&addr),
+            };
+        }
+    }
+}
+
+// internal state, as saved by `save_state`, checkpoints without history
+#[derive(Serialize, Deserialize)]
+struct MemState {
+    memory: BTreeMap<usize, u8>,
+    #[serde(default)]
+    clocks: usize,
+    #[serde(default)]
+    history: Option<MemHistory>,
+}
+
 impl Deref for Memory {
-    type Target = RefCell<BTreeMap<usize, u8>>;
+    type Target = RwLock<BTreeMap<usize, u8>>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -427,43 +632,42 @@ impl Deref for Memory {
 mod test {
     use super::*;
     use crate::common::ComponentStore;
-    use crate::components::ProbeOut;
-    use std::rc::Rc;
+    use crate::components::{Constant, ProbeOut};
+    use std::sync::Arc;
 
     #[test]
     fn test_mem_be() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("data_o")),
-                Rc::new(ProbeOut::new("addr")),
-                Rc::new(ProbeOut::new("ctrl")),
-                Rc::new(ProbeOut::new("size")),
-                Rc::new(ProbeOut::new("sign")),
-                Rc::new(Mem {
-                    id: "mem".into(),
-                    pos: (0.0, 0.0),
-                    width: 0.0,
-                    height: 0.0,
-
-                    // configuration
-                    big_endian: true, // i.e., big endian
-
-                    // ports
-                    data: Input::new("data_o", "out"),
-                    addr: Input::new("addr", "out"),
-                    ctrl: Input::new("ctrl", "out"),
-                    size: Input::new("size", "out"),
-                    sext: Input::new("sign", "out"),
-
-                    // memory
-                    memory: Memory(Rc::new(RefCell::new(BTreeMap::new()))),
-                    range: Range {
-                        start: 0u32,
-                        end: 1u32,
-                    },
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("data_o")),
+            Arc::new(ProbeOut::new("addr")),
+            Arc::new(ProbeOut::new("ctrl")),
+            Arc::new(ProbeOut::new("size")),
+            Arc::new(ProbeOut::new("sign")),
+            Arc::new(Mem {
+                id: "mem".into(),
+                pos: (0.0, 0.0),
+                width: 0.0,
+                height: 0.0,
+
+                // configuration
+                big_endian: true, // i.e., big endian
+
+                // ports
+                data: Input::new("data_o", "out"),
+                addr: Input::new("addr", "out"),
+                ctrl: Input::new("ctrl", "out"),
+                size: Input::new("size", "out"),
+                sext: Input::new("sign", "out"),
+
+                // memory
+                memory: Memory(Arc::new(RwLock::new(BTreeMap::new()))),
+                history: Arc::default(),
+                range: Range {
+                    start: 0u32,
+                    end: 1u32,
+                },
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
 
@@ -614,39 +818,38 @@ mod test {
 
     #[test]
     fn test_mem_le() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("data_o")),
-                Rc::new(ProbeOut::new("addr")),
-                Rc::new(ProbeOut::new("ctrl")),
-                Rc::new(ProbeOut::new("size")),
-                Rc::new(ProbeOut::new("sign")),
-                Rc::new(Mem {
-                    id: "mem".into(),
-                    pos: (0.0, 0.0),
-                    width: 0.0,
-                    height: 0.0,
-
-                    // configuration
-                    big_endian: false, // i.e., little endian
-
-                    // ports
-                    data: Input::new("data_o", "out"),
-                    addr: Input::new("addr", "out"),
-                    ctrl: Input::new("ctrl", "out"),
-                    size: Input::new("size", "out"),
-                    sext: Input::new("sign", "out"),
-
-                    // memory
-                    memory: Memory(Rc::new(RefCell::new(BTreeMap::new()))),
-                    // later history... tbd
-                    range: Range {
-                        start: 0u32,
-                        end: 1u32,
-                    },
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("data_o")),
+            Arc::new(ProbeOut::new("addr")),
+            Arc::new(ProbeOut::new("ctrl")),
+            Arc::new(ProbeOut::new("size")),
+            Arc::new(ProbeOut::new("sign")),
+            Arc::new(Mem {
+                id: "mem".into(),
+                pos: (0.0, 0.0),
+                width: 0.0,
+                height: 0.0,
+
+                // configuration
+                big_endian: false, // i.e., little endian
+
+                // ports
+                data: Input::new("data_o", "out"),
+                addr: Input::new("addr", "out"),
+                ctrl: Input::new("ctrl", "out"),
+                size: Input::new("size", "out"),
+                sext: Input::new("sign", "out"),
+
+                // memory
+                memory: Memory(Arc::new(RwLock::new(BTreeMap::new()))),
+                history: Arc::default(),
+                // later history... tbd
+                range: Range {
+                    start: 0u32,
+                    end: 1u32,
+                },
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
 
@@ -737,4 +940,201 @@ mod test {
         assert_eq!(simulator.cycle, 9);
         assert_eq!(simulator.get_input_value(out), 0x12.into());
     }
+
+    #[test]
+    fn test_mem_unknown() {
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("data_o")),
+            Arc::new(ProbeOut::new("addr")),
+            Arc::new(ProbeOut::new("ctrl")),
+            Arc::new(ProbeOut::new("size")),
+            Arc::new(ProbeOut::new("sign")),
+            Mem::arc_new(
+                "mem",
+                (0.0, 0.0),
+                0.0,
+                0.0,
+                false,
+                Input::new("data_o", "out"),
+                Input::new("addr", "out"),
+                Input::new("ctrl", "out"),
+                Input::new("sign", "out"),
+                Input::new("size", "out"),
+                0..8,
+            ),
+        ]);
+        let mut simulator = Simulator::new(cs).unwrap();
+        let out = &Input::new("mem", "data_o");
+        let err = &Input::new("mem", "err");
+
+        // unknown address
+        simulator.set_out_value("addr", "out", SignalValue::Unknown);
+        simulator.set_out_value("ctrl", "out", MemCtrl::Read as SignalUnsigned);
+        simulator.set_out_value("size", "out", 4);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(out), SignalValue::Unknown);
+        assert_eq!(simulator.get_input_value(err), SignalValue::Unknown);
+
+        // unknown data is not written
+        simulator.set_out_value("addr", "out", 4);
+        simulator.set_out_value("ctrl", "out", MemCtrl::Write as SignalUnsigned);
+        simulator.set_out_value("data_o", "out", SignalValue::Uninitialized);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(err), false.into());
+        assert!(matches!(
+            simulator.component_condition.as_slice(),
+            [(id, Condition::Warning(_))] if id == "mem"
+        ));
+
+        // unknown ctrl, or illegal size
+        simulator.set_out_value("ctrl", "out", SignalValue::Unknown);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(out), SignalValue::Unknown);
+        simulator.set_out_value("ctrl", "out", MemCtrl::Read as SignalUnsigned);
+        simulator.set_out_value("size", "out", 3);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(out), SignalValue::Unknown);
+
+        simulator.set_out_value("size", "out", 4);
+        simulator.set_out_value("sign", "out", 0);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(out), 0.into());
+    }
+
+    #[test]
+    fn test_mem_un_clock() {
+        use crate::components::{Add, Constant, Register};
+        // counter, writing its value to the address given by its value
+        let cs = ComponentStore::new(vec![
+            Arc::new(Constant::new("c1", (0.0, 0.0), 1)),
+            Arc::new(Constant::new("size", (0.0, 0.0), 1)),
+            Arc::new(Constant::new("write", (0.0, 0.0), MemCtrl::Write as u32)),
+            Register::arc_new("r1", (0.0, 0.0), Input::new("add", "out")),
+            Add::arc_new(
+                "add",
+                (0.0, 0.0),
+                Input::new("r1", "out"),
+                Input::new("c1", "out"),
+            ),
+            Mem::arc_new(
+                "mem",
+                (0.0, 0.0),
+                0.0,
+                0.0,
+                false,
+                Input::new("add", "out"),
+                Input::new("r1", "out"),
+                Input::new("write", "out"),
+                Input::new("c1", "out"),
+                Input::new("size", "out"),
+                0..4,
+            ),
+        ]);
+        let mut simulator = Simulator::new(cs).unwrap();
+        let mem = simulator
+            .ordered_components
+            .iter()
+            .find_map(|c| c.as_any().downcast_ref::<Mem>())
+            .map(|mem| mem.memory.clone())
+            .unwrap();
+        let contents = |memory: &Memory| memory.0.read().unwrap().clone();
+        let initial = contents(&mem);
+        assert_eq!(initial.get(&0), Some(&1));
+
+        for _ in 0..5 {
+            simulator.clock();
+        }
+        let after = contents(&mem);
+        assert_eq!(after.get(&5), Some(&6)); // beyond the initial range
+
+        // step back over the writes
+        simulator.clock();
+        simulator.un_clock();
+        assert_eq!(contents(&mem), after);
+        for _ in 0..5 {
+            simulator.un_clock();
+        }
+        assert_eq!(contents(&mem), initial);
+
+        for _ in 0..5 {
+            simulator.clock();
+        }
+        // components are reset before the initial clock, which writes again
+        simulator.reset();
+        assert_eq!(contents(&mem), initial);
+    }
+
+    #[test]
+    fn test_mem_history() {
+        // memory written at address 0 when `ctrl` is set
+        let model = |ctrl: MemCtrl| {
+            let cs = ComponentStore::new(vec![
+                Arc::new(Constant::new("zero", (0.0, 0.0), 0)),
+                Arc::new(Constant::new("size", (0.0, 0.0), 1)),
+                Arc::new(Constant::new("ctrl", (0.0, 0.0), ctrl as u32)),
+                Mem::arc_new(
+                    "mem",
+                    (0.0, 0.0),
+                    0.0,
+                    0.0,
+                    false,
+                    Input::new("zero", "out"),
+                    Input::new("zero", "out"),
+                    Input::new("ctrl", "out"),
+                    Input::new("zero", "out"),
+                    Input::new("size", "out"),
+                    0..4,
+                ),
+            ]);
+            Simulator::new(cs).unwrap()
+        };
+        let history = |simulator: &Simulator| {
+            let mem = simulator
+                .ordered_components
+                .iter()
+                .find_map(|c| c.as_any().downcast_ref::<Mem>())
+                .unwrap();
+            mem.history.read().unwrap().clone()
+        };
+
+        // reads are not recorded
+        let mut simulator = model(MemCtrl::Read);
+        for _ in 0..10 {
+            simulator.clock();
+        }
+        assert_eq!(history(&simulator).clocks, 11);
+        assert!(history(&simulator).ops.is_empty());
+
+        // writes are kept for the cycles in history
+        let mut simulator = model(MemCtrl::Write);
+        simulator.set_history_depth(Some(4));
+        for _ in 0..10 {
+            simulator.clock();
+        }
+        assert_eq!(history(&simulator).ops.len(), 4);
+    }
+
+    #[test]
+    fn test_mem_range_parameter() {
+        let input = Input::new("c", "out");
+        let mut mem = Mem::arc_new(
+            "mem",
+            (0.0, 0.0),
+            0.0,
+            0.0,
+            false,
+            input.clone(),
+            input.clone(),
+            input.clone(),
+            input.clone(),
+            input,
+            0..4,
+        );
+        let mem = Arc::get_mut(&mut mem).unwrap();
+        mem.memory.0.write().unwrap().insert(2, 0xab);
+        let range = Parameter::range("range", 2..6, 0, 0).value;
+        crate::parameter::set_parameter(mem, "range", range).unwrap();
+        let memory = mem.memory.0.read().unwrap().clone();
+        assert_eq!(memory, BTreeMap::from([(2, 0xab), (3, 0), (4, 0), (5, 0)]));
+    }
 }
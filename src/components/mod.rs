@@ -8,8 +8,10 @@ mod probe_assert;
 mod probe_edit;
 mod probe_out;
 mod probe_stim;
+mod probe_stim_file;
 mod register;
 mod sext;
+mod sub_circuit;
 mod wire;
 
 pub use add::*;
@@ -22,6 +24,138 @@ pub use probe_assert::*;
 pub use probe_edit::*;
 pub use probe_out::*;
 pub use probe_stim::*;
+pub use probe_stim_file::*;
 pub use register::*;
 pub use sext::*;
+pub use sub_circuit::*;
 pub use wire::*;
+
+use crate::migration::Migration;
+use crate::registry::{dummy_input, ComponentType};
+use std::sync::Arc;
+
+/// migrations of the provided components, see `migration`
+pub const MIGRATIONS: &[Migration] = &[];
+
+/// the provided component types, see `registry`
+pub const COMPONENTS: &[ComponentType] = &[
+    ComponentType {
+        name: "Add",
+        category: "Arithmetic",
+        description: "Sum of two inputs, with overflow",
+        library: true,
+        new: |id, pos| Add::arc_new(id, pos, dummy_input(), dummy_input()),
+    },
+    ComponentType {
+        name: "Sext",
+        category: "Arithmetic",
+        description: "Sign extension",
+        library: true,
+        new: |id, pos| Sext::arc_new(id, pos, dummy_input(), 16, 24),
+    },
+    ComponentType {
+        name: "SubCircuit",
+        category: "Hierarchy",
+        description: "Model of a file, instantiated as a component",
+        library: false,
+        new: |id, pos| SubCircuit::arc_new(id, pos, "", vec![], vec![]),
+    },
+    ComponentType {
+        name: "Mem",
+        category: "Memory",
+        description: "Data memory, read and written by bytes, half words and words",
+        library: true,
+        new: |id, pos| {
+            Mem::arc_new(
+                id,
+                pos,
+                100.0,
+                50.0,
+                true,
+                dummy_input(),
+                dummy_input(),
+                dummy_input(),
+                dummy_input(),
+                dummy_input(),
+                0..0x20,
+            )
+        },
+    },
+    ComponentType {
+        name: "Probe",
+        category: "Probes",
+        description: "Shows the value of its input",
+        library: true,
+        new: |id, pos| Probe::arc_new(id, pos, dummy_input()),
+    },
+    ComponentType {
+        name: "ProbeAssert",
+        category: "Probes",
+        description: "Asserts its input to be given values, cycle by cycle",
+        library: false,
+        new: |id, pos| ProbeAssert::arc_new(id, pos, dummy_input(), Vec::<u32>::new()),
+    },
+    ComponentType {
+        name: "ProbeOut",
+        category: "Probes",
+        description: "Output of a value, for testing",
+        library: false,
+        new: |id, _pos| Arc::new(ProbeOut::new(id)),
+    },
+    ComponentType {
+        name: "Register",
+        category: "Sequential",
+        description: "Register, outputs its input of the previous cycle",
+        library: true,
+        new: |id, pos| Register::arc_new(id, pos, dummy_input()),
+    },
+    ComponentType {
+        name: "Constant",
+        category: "Sources",
+        description: "Constant value",
+        library: true,
+        new: |id, pos| Constant::arc_new(id, pos, 0),
+    },
+    ComponentType {
+        name: "ProbeEdit",
+        category: "Sources",
+        description: "Value edited while simulating",
+        library: true,
+        new: |id, pos| ProbeEdit::arc_new(id, pos),
+    },
+    ComponentType {
+        name: "ProbeStim",
+        category: "Sources",
+        description: "Given values, cycle by cycle",
+        library: false,
+        new: |id, pos| ProbeStim::arc_new(id, pos, Vec::<u32>::new()),
+    },
+    ComponentType {
+        name: "ProbeStimFile",
+        category: "Sources",
+        description: "Values of a stimulus file, a channel per output",
+        library: true,
+        new: |id, pos| ProbeStimFile::arc_new(id, pos, "", vec![]),
+    },
+    ComponentType {
+        name: "Cross",
+        category: "Wiring",
+        description: "Marks a connection of wires",
+        library: true,
+        new: |id, pos| Cross::arc_new(id, pos, dummy_input()),
+    },
+    ComponentType {
+        name: "Mux",
+        category: "Wiring",
+        description: "Multiplexer, outputs the input selected",
+        library: true,
+        new: |id, pos| Mux::arc_new(id, pos, dummy_input(), vec![dummy_input(), dummy_input()]),
+    },
+    ComponentType {
+        name: "Wire",
+        category: "Wiring",
+        description: "Wire, drawn in the editor wire mode",
+        library: false,
+        new: |id, pos| Wire::arc_new(id, vec![pos, (pos.0 + 20.0, pos.1)], dummy_input()),
+    },
+];
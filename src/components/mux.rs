@@ -7,7 +7,7 @@ use crate::common::{
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub const MUX_SELECT_ID: &str = "select";
 pub const MUX_TEMPLATE_ID: &str = "in";
@@ -27,14 +27,14 @@ impl Component for Mux {
         trace!("mux");
     }
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
         let dummy_input = Input::new("dummy", "out");
-        Box::new(Rc::new(Mux {
+        Some(Box::new(Arc::new(Mux {
             id: id.to_string(),
             pos: (pos.0, pos.1),
             select: dummy_input.clone(),
             m_in: vec![dummy_input.clone(), dummy_input.clone()],
-        }))
+        })))
     }
     fn get_id_ports(&self) -> (Id, Ports) {
         let mut inputs: Vec<InputPort> = Vec::with_capacity(self.m_in.len() + 1);
@@ -51,24 +51,24 @@ impl Component for Mux {
 
         (
             self.id.clone(),
-            Ports {
-                inputs,
-                out_type: OutputType::Combinatorial,
-                outputs: vec![MUX_OUT_ID.to_string()],
-            },
+            Ports::new(
+                inputs.iter().collect(),
+                OutputType::Combinatorial,
+                vec![MUX_OUT_ID],
+            ),
         )
     }
 
     // propagate selected input value to output
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
-        // get input value
-        let select: SignalValue = simulator.get_input_value(&self.select);
+        // get input value, ports as in `get_id_ports`
+        let select: SignalValue = simulator.get_port_value(0);
         trace!("-----------{}------------", self.id);
         let (value, res) = if let Ok(select) = TryInto::<SignalUnsigned>::try_into(select) {
             let select = select as usize;
             trace!("select {}", select);
             if select < self.m_in.len() {
-                (simulator.get_input_value(&self.m_in[select]), Ok(()))
+                (simulator.get_port_value(1 + select), Ok(()))
             } else {
                 (
                     SignalValue::Unknown,
@@ -76,10 +76,15 @@ impl Component for Mux {
                 )
             }
         } else {
-            (
-                SignalValue::Unknown,
-                Err(Condition::Warning("select unknown".to_string())),
-            )
+            // an unknown select does not matter if all inputs agree
+            let mut values = (1..=self.m_in.len()).map(|port| simulator.get_port_value(port));
+            match values.next() {
+                Some(first) if values.all(|value| value == first) => (first, Ok(())),
+                _ => (
+                    SignalValue::Unknown,
+                    Err(Condition::Warning("select unknown".to_string())),
+                ),
+            }
         };
         trace!("-----------------value:{:?}, end---------------", value);
         // set output
@@ -101,6 +106,10 @@ impl Component for Mux {
         }
     }
 
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -116,7 +125,50 @@ impl Mux {
         }
     }
 
-    pub fn rc_new(id: &str, pos: (f32, f32), select: Input, m_in: Vec<Input>) -> Rc<Self> {
-        Rc::new(Mux::new(id, pos, select, m_in))
+    pub fn arc_new(id: &str, pos: (f32, f32), select: Input, m_in: Vec<Input>) -> Arc<Self> {
+        Arc::new(Mux::new(id, pos, select, m_in))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        common::ComponentStore,
+        components::{Constant, ProbeOut},
+    };
+
+    #[test]
+    fn test_mux_unknown_select() {
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("select")),
+            Arc::new(Constant::new("c1", (0.0, 0.0), 1)),
+            Arc::new(Constant::new("c2", (0.0, 0.0), 2)),
+            Mux::arc_new(
+                "mux",
+                (0.0, 0.0),
+                Input::new("select", "out"),
+                vec![Input::new("c1", "out"), Input::new("c2", "out")],
+            ),
+            // inputs agree
+            Mux::arc_new(
+                "mux_agree",
+                (0.0, 0.0),
+                Input::new("select", "out"),
+                vec![Input::new("c2", "out"), Input::new("c2", "out")],
+            ),
+        ]);
+        let mut simulator = Simulator::new(cs).unwrap();
+        let out = &Input::new("mux", "out");
+        let out_agree = &Input::new("mux_agree", "out");
+
+        simulator.set_out_value("select", "out", 1);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(out), 2.into());
+
+        simulator.set_out_value("select", "out", SignalValue::Unknown);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(out), SignalValue::Unknown);
+        assert_eq!(simulator.get_input_value(out_agree), 2.into());
     }
 }
@@ -4,7 +4,7 @@ use crate::common::{Component, Id, Input, InputPort, OutputType, Ports};
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub const PROBE_IN_ID: &str = "in";
 
@@ -22,13 +22,13 @@ impl Component for Probe {
     }
 
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
         let dummy_input = Input::new("dummy", "out");
-        Box::new(Rc::new(Probe {
+        Some(Box::new(Arc::new(Probe {
             id: id.to_string(),
             pos: (pos.0, pos.1),
             input: dummy_input.clone(),
-        }))
+        })))
     }
 
     fn get_id_ports(&self) -> (Id, Ports) {
@@ -53,6 +53,10 @@ impl Component for Probe {
         }
     }
 
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -67,7 +71,7 @@ impl Probe {
         }
     }
 
-    pub fn rc_new(id: &str, pos: (f32, f32), input: Input) -> Rc<Self> {
-        Rc::new(Probe::new(id, pos, input))
+    pub fn arc_new(id: &str, pos: (f32, f32), input: Input) -> Arc<Self> {
+        Arc::new(Probe::new(id, pos, input))
     }
 }
@@ -5,7 +5,7 @@ use crate::{
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub const PROBE_ASSERT_IN_ID: &str = "in";
 
@@ -37,9 +37,15 @@ impl Component for ProbeAssert {
         )
     }
 
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        if target_port_id == PROBE_ASSERT_IN_ID {
+            self.input = new_input;
+        }
+    }
+
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
         trace!("-- cycle {} --", simulator.cycle);
-        let lhs = simulator.get_input_value(&self.input);
+        let lhs = simulator.get_port_value(0);
         let rhs = match self.values.get(simulator.cycle) {
             Some(rhs) => rhs.get_value(),
             _ => SignalValue::Unknown,
@@ -75,13 +81,13 @@ impl ProbeAssert {
         }
     }
 
-    pub fn rc_new(
+    pub fn arc_new(
         id: &str,
         pos: (f32, f32),
         input: Input,
         values: Vec<impl Into<Signal>>,
-    ) -> Rc<Self> {
-        Rc::new(ProbeAssert::new(id, pos, input, values))
+    ) -> Arc<Self> {
+        Arc::new(ProbeAssert::new(id, pos, input, values))
     }
 }
 
@@ -95,17 +101,15 @@ mod test {
 
     #[test]
     fn test_probe_stim() {
-        let cs = ComponentStore {
-            store: vec![
-                ProbeStim::rc_new("stim", (0.0, 0.0), vec![0, 1, 2]),
-                ProbeAssert::rc_new(
-                    "assert",
-                    (0.0, 0.0),
-                    Input::new("stim", "out"),
-                    vec![0, 1, 2],
-                ),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            ProbeStim::arc_new("stim", (0.0, 0.0), vec![0, 1, 2]),
+            ProbeAssert::arc_new(
+                "assert",
+                (0.0, 0.0),
+                Input::new("stim", "out"),
+                vec![0, 1, 2],
+            ),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
         // output
@@ -169,17 +173,15 @@ mod test {
     #[test]
     #[should_panic]
     fn test_probe_stim_fail() {
-        let cs = ComponentStore {
-            store: vec![
-                ProbeStim::rc_new("stim", (0.0, 0.0), vec![0, 1, 2]),
-                ProbeAssert::rc_new(
-                    "assert",
-                    (0.0, 0.0),
-                    Input::new("stim", "out"),
-                    vec![0, 0, 2],
-                ),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            ProbeStim::arc_new("stim", (0.0, 0.0), vec![0, 1, 2]),
+            ProbeAssert::arc_new(
+                "assert",
+                (0.0, 0.0),
+                Input::new("stim", "out"),
+                vec![0, 0, 2],
+            ),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
         // output
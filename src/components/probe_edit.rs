@@ -4,10 +4,7 @@ use crate::common::{Component, Condition, Id, OutputType, Ports, Signal, Simulat
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
-use std::{
-    rc::Rc,
-    sync::{Arc, RwLock},
-};
+use std::sync::{Arc, RwLock};
 
 pub const PROBE_EDIT_OUT_ID: &str = "out";
 
@@ -30,8 +27,8 @@ impl Component for ProbeEdit {
         trace!("ProbeEdit");
     }
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
-        Box::new(Rc::new(ProbeEdit::new(id, (pos.0, pos.1))))
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
+        Some(Box::new(Arc::new(ProbeEdit::new(id, (pos.0, pos.1)))))
     }
     fn get_id_ports(&self) -> (Id, Ports) {
         (
@@ -72,6 +69,21 @@ impl Component for ProbeEdit {
         edit_history.push(prev); // push as next (to be edited)
     }
 
+    // edited values, kept for reverse simulation
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(&*self.edit_history.read().unwrap()).ok()
+    }
+
+    fn load_state(&self, state: serde_json::Value) -> Result<(), String> {
+        let edit_history: Vec<TextSignal> =
+            serde_json::from_value(state).map_err(|e| e.to_string())?;
+        if edit_history.is_empty() {
+            return Err("empty edit history".to_string());
+        }
+        *self.edit_history.write().unwrap() = edit_history;
+        Ok(())
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -90,7 +102,7 @@ impl ProbeEdit {
         }
     }
 
-    pub fn rc_new(id: &str, pos: (f32, f32)) -> Rc<Self> {
-        Rc::new(ProbeEdit::new(id, pos))
+    pub fn arc_new(id: &str, pos: (f32, f32)) -> Arc<Self> {
+        Arc::new(ProbeEdit::new(id, pos))
     }
 }
@@ -2,7 +2,7 @@ use crate::common::{Component, Condition, Id, OutputType, Ports, Signal, SignalV
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
-use std::rc::Rc;
+use std::sync::Arc;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ProbeStim {
@@ -61,8 +61,8 @@ impl ProbeStim {
         }
     }
 
-    pub fn rc_new(id: &str, pos: (f32, f32), values: Vec<impl Into<Signal>>) -> Rc<Self> {
-        Rc::new(ProbeStim::new(id, pos, values))
+    pub fn arc_new(id: &str, pos: (f32, f32), values: Vec<impl Into<Signal>>) -> Arc<Self> {
+        Arc::new(ProbeStim::new(id, pos, values))
     }
 }
 
@@ -73,9 +73,11 @@ mod test {
 
     #[test]
     fn test_probe_stim() {
-        let cs = ComponentStore {
-            store: vec![ProbeStim::rc_new("stim", (0.0, 0.0), vec![0, 1, 2, 3])],
-        };
+        let cs = ComponentStore::new(vec![ProbeStim::arc_new(
+            "stim",
+            (0.0, 0.0),
+            vec![0, 1, 2, 3],
+        )]);
 
         let mut simulator = Simulator::new(cs).unwrap();
         // output
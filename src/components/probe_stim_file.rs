@@ -0,0 +1,541 @@
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{
+    Component, Condition, Id, OutputType, Ports, SignalUnsigned, SignalValue, SignalWide,
+    Simulator, MAX_WIDTH,
+};
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::SplitWhitespace;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+/// Stimulus read from a VCD or CSV file
+///
+/// Each channel is an output field, named as the corresponding column (CSV)
+/// or variable (VCD) in the file. Values are given by their changes, a channel
+/// holds its last value until the next change. After the last cycle in the file
+/// values are either held, or repeated from the start if `looping` is set.
+/// The path is relative to the directory of the model file.
+#[derive(Serialize, Deserialize)]
+pub struct ProbeStimFile {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) path: PathBuf,
+    pub(crate) channels: Vec<Id>,
+    #[serde(default)]
+    pub(crate) looping: bool,
+    // VCD time units per cycle
+    #[serde(default = "default_period")]
+    pub(crate) period: u64,
+    // directory of the model file
+    #[serde(skip)]
+    pub(crate) dir: PathBuf,
+    // loaded on first clock after reset
+    #[serde(skip)]
+    pub(crate) stimulus: RwLock<Option<Result<Stimulus, String>>>,
+}
+
+fn default_period() -> u64 {
+    1
+}
+
+#[typetag::serde]
+impl Component for ProbeStimFile {
+    fn to_(&self) {
+        trace!("probe_stim_file {:?} {:?}", self.path, self.channels);
+    }
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
+        Some(Box::new(Arc::new(ProbeStimFile::new(
+            id,
+            pos,
+            "",
+            vec!["out"],
+        ))))
+    }
+
+    fn get_id_ports(&self) -> (Id, Ports) {
+        (
+            self.id.clone(),
+            Ports::new(
+                // ProbeStimFile do not take any inputs
+                vec![],
+                OutputType::Combinatorial,
+                self.channels.iter().map(|c| c.as_str()).collect(),
+            ),
+        )
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        let mut stimulus = self.stimulus.write().unwrap();
+        let stimulus = stimulus
+            .get_or_insert_with(|| Stimulus::load(&self.file(), &self.channels, self.period));
+        let stimulus = match stimulus {
+            Ok(stimulus) => stimulus,
+            Err(e) => {
+                for channel in &self.channels {
+                    simulator.set_out_value(&self.id, channel, SignalValue::Unknown);
+                }
+                return Err(Condition::Error(e.clone()));
+            }
+        };
+
+        let cycle = if self.looping && stimulus.length > 0 {
+            simulator.cycle % stimulus.length
+        } else {
+            simulator.cycle
+        };
+        for (i, channel) in self.channels.iter().enumerate() {
+            simulator.set_out_value(&self.id, channel, stimulus.value(i, cycle));
+        }
+        Ok(())
+    }
+
+    // notice we don't implement `un_clock` since the state is already kept in history
+
+    // reload the file on next clock
+    fn reset(&self) {
+        *self.stimulus.write().unwrap() = None;
+    }
+
+    fn set_model_dir(&mut self, dir: &Path) {
+        self.dir = dir.to_path_buf();
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl ProbeStimFile {
+    pub fn new(id: &str, pos: (f32, f32), path: impl Into<PathBuf>, channels: Vec<&str>) -> Self {
+        ProbeStimFile {
+            id: id.to_string(),
+            pos,
+            path: path.into(),
+            channels: channels.into_iter().map(|c| c.into()).collect(),
+            looping: false,
+            period: default_period(),
+            dir: PathBuf::new(),
+            stimulus: RwLock::new(None),
+        }
+    }
+
+    pub fn arc_new(
+        id: &str,
+        pos: (f32, f32),
+        path: impl Into<PathBuf>,
+        channels: Vec<&str>,
+    ) -> Arc<Self> {
+        Arc::new(ProbeStimFile::new(id, pos, path, channels))
+    }
+
+    /// repeat the stimulus from the start after its last cycle
+    pub fn looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// number of VCD time units per cycle
+    pub fn period(mut self, period: u64) -> Self {
+        self.period = period.max(1);
+        self
+    }
+
+    // stimulus file, relative to the directory of the model file
+    fn file(&self) -> PathBuf {
+        self.dir.join(&self.path)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stimulus {
+    /// value changes `(cycle, value)` for each channel, in cycle order
+    pub changes: Vec<Vec<(usize, SignalValue)>>,
+    /// number of cycles covered by the stimulus
+    pub length: usize,
+}
+
+impl Stimulus {
+    /// load stimulus from `.vcd` file, or `.csv` file (any other extension)
+    pub fn load(path: &Path, channels: &[Id], period: u64) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        let stimulus = match path.extension().and_then(|e| e.to_str()) {
+            Some("vcd") => Stimulus::from_vcd(&text, channels, period),
+            _ => Stimulus::from_csv(&text, channels),
+        };
+        stimulus.map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// value of channel at cycle, `Unknown` before the first change
+    pub fn value(&self, channel: usize, cycle: usize) -> SignalValue {
+        let changes = &self.changes[channel];
+        match changes.partition_point(|(c, _)| *c <= cycle) {
+            0 => SignalValue::Unknown,
+            i => changes[i - 1].1,
+        }
+    }
+
+    // record change, a later change in the same cycle takes precedence
+    fn push(&mut self, channel: usize, cycle: usize, value: SignalValue) {
+        let changes = &mut self.changes[channel];
+        match changes.last_mut() {
+            Some((c, v)) if *c == cycle => *v = value,
+            _ => changes.push((cycle, value)),
+        }
+        self.length = self.length.max(cycle + 1);
+    }
+
+    /// parse CSV, with a header line naming the columns
+    ///
+    /// The first column holds the cycle, in increasing order. Cycles without
+    /// changes may be left out, as well as values that do not change (empty cell).
+    /// Values are given in decimal, hex (`0x`) or binary (`0b`), or `x` for unknown,
+    /// values not fitting 32 bits are 64 bits wide.
+    /// Lines starting with `#` are ignored.
+    pub fn from_csv(text: &str, channels: &[Id]) -> Result<Self, String> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(n, line)| (n + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let (_, header) = lines.next().ok_or("missing header")?;
+        let header: Vec<&str> = header.split(',').map(|c| c.trim()).collect();
+        let columns = channels
+            .iter()
+            .map(|channel| {
+                header
+                    .iter()
+                    .skip(1)
+                    .position(|c| c == channel)
+                    .map(|i| i + 1)
+                    .ok_or(format!("channel {} not found", channel))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stimulus = Stimulus {
+            changes: vec![vec![]; channels.len()],
+            length: 0,
+        };
+        let mut last_cycle = None;
+        for (n, line) in lines {
+            let cells: Vec<&str> = line.split(',').map(|c| c.trim()).collect();
+            let cycle: usize = cells[0]
+                .parse()
+                .map_err(|_| format!("line {}: invalid cycle {}", n, cells[0]))?;
+            if last_cycle.is_some_and(|last| cycle <= last) {
+                return Err(format!("line {}: cycle {} out of order", n, cycle));
+            }
+            last_cycle = Some(cycle);
+            for (channel, column) in columns.iter().enumerate() {
+                match cells.get(*column) {
+                    None | Some(&"") => {}
+                    Some(cell) => {
+                        let value = parse_value(cell)
+                            .ok_or(format!("line {}: invalid value {}", n, cell))?;
+                        stimulus.push(channel, cycle, value);
+                    }
+                }
+            }
+        }
+        Ok(stimulus)
+    }
+
+    /// parse VCD, one cycle amounting to `period` time units
+    ///
+    /// Channels are matched to variables by reference name, or by hierarchical
+    /// name with scopes separated by `.` (e.g., `top.reg.out` or `reg.out`).
+    /// Values take the declared width of their variable (at most 64 bits).
+    pub fn from_vcd(text: &str, channels: &[Id], period: u64) -> Result<Self, String> {
+        let period = period.max(1);
+        let mut tokens = text.split_whitespace();
+        let mut scopes: Vec<&str> = vec![];
+        // (reference, hierarchical name, code) in order of declaration
+        let mut vars: Vec<(&str, String, &str)> = vec![];
+        // identifier code -> declared width
+        let mut widths: HashMap<&str, u8> = HashMap::new();
+        // identifier code -> channels, set by `$enddefinitions`
+        let mut codes: Option<HashMap<&str, Vec<usize>>> = None;
+        let mut stimulus = Stimulus {
+            changes: vec![vec![]; channels.len()],
+            length: 0,
+        };
+        let mut cycle = 0;
+
+        while let Some(token) = tokens.next() {
+            match token {
+                "$scope" => {
+                    expect(&mut tokens, "scope type")?;
+                    scopes.push(expect(&mut tokens, "scope name")?);
+                    expect(&mut tokens, "$end")?;
+                }
+                "$upscope" => {
+                    scopes.pop();
+                    expect(&mut tokens, "$end")?;
+                }
+                "$var" => {
+                    expect(&mut tokens, "var type")?;
+                    let width = expect(&mut tokens, "var width")?;
+                    let width: u8 = match width.parse::<u32>() {
+                        Ok(width) => width.min(MAX_WIDTH as u32) as u8,
+                        Err(_) => return Err(format!("invalid var width {}", width)),
+                    };
+                    let code = expect(&mut tokens, "var code")?;
+                    widths.insert(code, width);
+                    let reference = expect(&mut tokens, "var reference")?;
+                    // skip optional bit range
+                    while expect(&mut tokens, "$end")? != "$end" {}
+                    let mut name = scopes.join(".");
+                    if !name.is_empty() {
+                        name.push('.');
+                    }
+                    name.push_str(reference);
+                    vars.push((reference, name, code));
+                }
+                "$enddefinitions" => {
+                    expect(&mut tokens, "$end")?;
+                    let codes = codes.insert(HashMap::new());
+                    for (channel, id) in channels.iter().enumerate() {
+                        let suffix = format!(".{}", id);
+                        let (_, _, code) = vars
+                            .iter()
+                            .find(|(reference, name, _)| {
+                                reference == id || name == id || name.ends_with(&suffix)
+                            })
+                            .ok_or(format!("channel {} not found", id))?;
+                        codes.entry(*code).or_default().push(channel);
+                    }
+                }
+                // value changes follow
+                "$dumpvars" | "$dumpall" | "$dumpon" | "$dumpoff" | "$end" => {}
+                _ if token.starts_with('$') => {
+                    // $date, $version, $timescale, $comment, ...
+                    while expect(&mut tokens, "$end")? != "$end" {}
+                }
+                _ if token.starts_with('#') => {
+                    let time: u64 = token[1..]
+                        .parse()
+                        .map_err(|_| format!("invalid time {}", token))?;
+                    cycle = (time / period) as usize;
+                }
+                _ => {
+                    let codes = codes
+                        .as_ref()
+                        .ok_or(format!("value change {} before $enddefinitions", token))?;
+                    // tokens are not empty
+                    let first = token.chars().next().unwrap();
+                    let (value, rest) = token.split_at(first.len_utf8());
+                    let (bits, code) = match first {
+                        'b' | 'B' => (Some(rest), expect(&mut tokens, "var code")?),
+                        'r' | 'R' => (None, expect(&mut tokens, "var code")?),
+                        '0' | '1' | 'x' | 'X' | 'z' | 'Z' => (Some(value), rest),
+                        _ => return Err(format!("invalid value change {}", token)),
+                    };
+                    let width = widths.get(code).copied().unwrap_or(1);
+                    let value = bits.map_or(SignalValue::Unknown, |bits| parse_bits(bits, width));
+                    for channel in codes.get(code).into_iter().flatten() {
+                        stimulus.push(*channel, cycle, value);
+                    }
+                }
+            }
+        }
+        if codes.is_none() {
+            return Err("missing $enddefinitions".to_string());
+        }
+        Ok(stimulus)
+    }
+}
+
+// next token, expected to be `what`
+fn expect<'a>(tokens: &mut SplitWhitespace<'a>, what: &str) -> Result<&'a str, String> {
+    tokens
+        .next()
+        .ok_or(format!("unexpected end of file, expected {}", what))
+}
+
+// parse decimal, hex (`0x`), binary (`0b`), or unknown (`x`) value
+fn parse_value(s: &str) -> Option<SignalValue> {
+    if s.eq_ignore_ascii_case("x") {
+        return Some(SignalValue::Unknown);
+    }
+    let value: SignalWide = if let Some(hex) = s.strip_prefix("0x") {
+        SignalWide::from_str_radix(hex, 16).ok()?
+    } else if let Some(bin) = s.strip_prefix("0b") {
+        SignalWide::from_str_radix(bin, 2).ok()?
+    } else if s.starts_with('-') {
+        s.parse::<i32>().ok()? as SignalUnsigned as SignalWide
+    } else {
+        s.parse().ok()?
+    };
+    let width = match SignalUnsigned::try_from(value) {
+        Ok(_) => SignalUnsigned::BITS as u8,
+        Err(_) => MAX_WIDTH,
+    };
+    Some(SignalValue::from_bits(value, width))
+}
+
+// parse VCD bit vector of the declared width, unknown if any bit is `x` or `z`
+fn parse_bits(bits: &str, width: u8) -> SignalValue {
+    // keep the `MAX_WIDTH` least significant bits
+    let bits = &bits[bits.len().saturating_sub(MAX_WIDTH as usize)..];
+    match SignalWide::from_str_radix(bits, 2) {
+        Ok(value) => SignalValue::from_bits(value, width),
+        Err(_) => SignalValue::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::{ComponentStore, Input};
+
+    fn channels(channels: &[&str]) -> Vec<Id> {
+        channels.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_csv() {
+        let csv = "\
+# a comment
+cycle, a, b
+0, 1, 0x10
+2, , 0b11
+3, x, -1
+";
+        let stimulus = Stimulus::from_csv(csv, &channels(&["b", "a"])).unwrap();
+        assert_eq!(stimulus.length, 4);
+        // b
+        assert_eq!(stimulus.value(0, 0), 0x10.into());
+        assert_eq!(stimulus.value(0, 1), 0x10.into());
+        assert_eq!(stimulus.value(0, 2), 3.into());
+        assert_eq!(stimulus.value(0, 3), u32::MAX.into());
+        // a
+        assert_eq!(stimulus.value(1, 2), 1.into());
+        assert_eq!(stimulus.value(1, 3), SignalValue::Unknown);
+
+        let stimulus = Stimulus::from_csv("cycle,a\n0,0x123456789\n", &channels(&["a"])).unwrap();
+        assert_eq!(
+            stimulus.value(0, 0),
+            SignalValue::from_bits(0x1_2345_6789, 64)
+        );
+
+        assert!(Stimulus::from_csv(csv, &channels(&["c"])).is_err());
+        assert!(Stimulus::from_csv("cycle,a\n1,0\n0,1\n", &channels(&["a"])).is_err());
+    }
+
+    #[test]
+    fn test_vcd() {
+        let vcd = "\
+$timescale 1ns $end
+$scope module top $end
+$scope module reg $end
+$var wire 8 ! out [7:0] $end
+$upscope $end
+$var wire 1 \" clk $end
+$var wire 40 # wide $end
+$upscope $end
+$enddefinitions $end
+#0
+$dumpvars
+bx !
+0\"
+$end
+#10
+b101 !
+1\"
+#20
+0\"
+#40
+b1 !
+b1000000000000000000000000000000000000001 #
+";
+        let stimulus = Stimulus::from_vcd(vcd, &channels(&["reg.out", "clk"]), 10).unwrap();
+        assert_eq!(stimulus.length, 5);
+        assert_eq!(stimulus.value(0, 0), SignalValue::Unknown);
+        assert_eq!(stimulus.value(0, 1), 5.into());
+        assert_eq!(stimulus.value(0, 3), 5.into());
+        assert_eq!(stimulus.value(0, 4), 1.into());
+        assert_eq!(stimulus.value(1, 1), 1.into());
+        assert_eq!(stimulus.value(1, 2), 0.into());
+
+        let stimulus = Stimulus::from_vcd(vcd, &channels(&["wide"]), 10).unwrap();
+        assert_eq!(
+            stimulus.value(0, 4),
+            SignalValue::from_bits(1 << 39 | 1, 40)
+        );
+
+        assert!(Stimulus::from_vcd(vcd, &channels(&["missing"]), 1).is_err());
+        let (definitions, changes) = vcd.split_once("$enddefinitions $end").unwrap();
+        let missing = format!("{}{}", definitions, changes);
+        assert!(Stimulus::from_vcd(&missing, &channels(&["clk"]), 1).is_err());
+        assert!(Stimulus::from_vcd(definitions, &channels(&["clk"]), 1).is_err());
+        let invalid = format!("{}$enddefinitions $end\n#0\né!\n", definitions);
+        assert!(Stimulus::from_vcd(&invalid, &channels(&["clk"]), 1).is_err());
+    }
+
+    #[test]
+    fn test_probe_stim_file() {
+        let path = std::env::temp_dir().join(format!("probe_stim_file_{}.csv", std::process::id()));
+        std::fs::write(&path, "cycle,a,b\n0,1,2\n2,3,\n").unwrap();
+
+        let cs = ComponentStore::new(vec![Arc::new(
+            ProbeStimFile::new("stim", (0.0, 0.0), &path, vec!["a", "b"]).looping(true),
+        )]);
+        let mut simulator = Simulator::new(cs).unwrap();
+        let a = &Input::new("stim", "a");
+        let b = &Input::new("stim", "b");
+
+        assert_eq!(simulator.get_input_value(a), 1.into());
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(a), 1.into());
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(a), 3.into());
+        assert_eq!(simulator.get_input_value(b), 2.into());
+        // looping
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(a), 1.into());
+        simulator.un_clock();
+        assert_eq!(simulator.get_input_value(a), 3.into());
+
+        // the file is reloaded after reset
+        std::fs::remove_file(&path).unwrap();
+        simulator.reset();
+        simulator.clock();
+        assert!(matches!(
+            simulator.component_condition.as_slice(),
+            [(_, Condition::Error(_))]
+        ));
+    }
+
+    #[test]
+    fn test_probe_stim_file_relative() {
+        let dir = std::env::temp_dir().join(format!("probe_stim_file_rel_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("stim.csv"), "cycle,a\n0,7\n").unwrap();
+        let path = dir.join("model.json");
+        ComponentStore::new(vec![ProbeStimFile::arc_new(
+            "stim",
+            (0.0, 0.0),
+            "stim.csv",
+            vec!["a"],
+        )])
+        .save_file(&path)
+        .unwrap();
+
+        // resolved against the model file, not the working directory
+        let cs = ComponentStore::load_file(&path).unwrap();
+        let simulator = Simulator::new(cs).unwrap();
+        assert_eq!(
+            simulator.get_input_value(&Input::new("stim", "a")),
+            7.into()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -4,7 +4,7 @@ use crate::common::{Component, Condition, Id, Input, InputPort, OutputType, Port
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub const REGISTER_R_IN_ID: &str = "r_in";
 
@@ -23,13 +23,13 @@ impl Component for Register {
         trace!("register");
     }
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
         let dummy_input = Input::new("dummy", "out");
-        Box::new(Rc::new(Register {
+        Some(Box::new(Arc::new(Register {
             id: id.to_string(),
             pos: (pos.0, pos.1),
             r_in: dummy_input.clone(),
-        }))
+        })))
     }
     fn get_id_ports(&self) -> (Id, Ports) {
         (
@@ -49,7 +49,7 @@ impl Component for Register {
     // propagate input value to output
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
         // get input value
-        let value = simulator.get_input_value(&self.r_in);
+        let value = simulator.get_port_value(0);
         // set output
         simulator.set_out_value(&self.id, "out", value);
         trace!("eval: register id {} in {:?}", self.id, value);
@@ -76,7 +76,38 @@ impl Register {
         }
     }
 
-    pub fn rc_new(id: &str, pos: (f32, f32), r_in: Input) -> Rc<Self> {
-        Rc::new(Register::new(id, pos, r_in))
+    pub fn arc_new(id: &str, pos: (f32, f32), r_in: Input) -> Arc<Self> {
+        Arc::new(Register::new(id, pos, r_in))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        common::{ComponentStore, SignalValue},
+        components::ProbeOut,
+    };
+
+    #[test]
+    fn test_register_unknown() {
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("po")),
+            Register::arc_new("reg", (0.0, 0.0), Input::new("po", "out")),
+        ]);
+        let mut simulator = Simulator::new(cs).unwrap();
+        let out = &Input::new("reg", "out");
+
+        // the input is passed as is, also if unknown, a cycle later
+        for value in [
+            SignalValue::Unknown,
+            SignalValue::DontCare,
+            SignalValue::Uninitialized,
+            7.into(),
+        ] {
+            simulator.set_out_value("po", "out", value);
+            simulator.clock();
+            assert_eq!(simulator.get_input_value(out), value);
+        }
     }
 }
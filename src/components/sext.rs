@@ -2,13 +2,14 @@
 #[cfg(feature = "gui-egui")]
 use crate::common::EguiComponent;
 use crate::common::{
-    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalSigned, SignalUnsigned,
-    SignalValue, Simulator,
+    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalValue, SignalWide,
+    Simulator, MAX_WIDTH,
 };
+use crate::parameter::{unknown_parameter, Parameter, ParameterValue};
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub const SEXT_IN_ID: &str = "sext_in";
 
@@ -29,15 +30,15 @@ impl Component for Sext {
         trace!("Sign Extension");
     }
     #[cfg(feature = "gui-egui")]
-    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Option<Box<Arc<dyn EguiComponent>>> {
         let dummy_input = Input::new("dummy", "out");
-        Box::new(Rc::new(Sext {
+        Some(Box::new(Arc::new(Sext {
             id: id.to_string(),
             pos: (pos.0, pos.1),
             sext_in: dummy_input.clone(),
             in_size: 16,
             out_size: 24,
-        }))
+        })))
     }
     fn get_id_ports(&self) -> (Id, Ports) {
         (
@@ -49,7 +50,9 @@ impl Component for Sext {
                 }],
                 OutputType::Combinatorial,
                 vec![SEXT_OUT_ID],
-            ),
+            )
+            // the input is not checked, only its `in_size` least significant bits are used
+            .with_output_width(SEXT_OUT_ID, self.out_size as u8),
         )
     }
 
@@ -63,28 +66,32 @@ impl Component for Sext {
     // TODO: always extend to Signal size? (it should not matter and should be slightly cheaper)
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
         assert!(
-            self.out_size <= SignalUnsigned::BITS,
+            self.out_size <= MAX_WIDTH as u32,
             "{}: Output size {} is larger than maximum size {}",
             self.id,
             self.out_size,
-            SignalUnsigned::BITS
+            MAX_WIDTH
         );
 
         // get input values
-        match simulator.get_input_value(&self.sext_in) {
-            SignalValue::Data(mut value) => {
+        match SignalWide::try_from(simulator.get_port_value(0)) {
+            Ok(mut value) => {
                 let to_sext = self.out_size - self.in_size; // Amount to be arithmetically shifted
-                let to_shl = SignalUnsigned::BITS - self.in_size; // To move input to MSB
+                let to_shl = SignalWide::BITS - self.in_size; // To move input to MSB
                 let to_shr = to_shl - to_sext; // To shift the result back to LSB
 
                 value <<= to_shl;
-                value = ((value as SignalSigned) >> to_sext) as SignalUnsigned;
+                value = ((value as i64) >> to_sext) as SignalWide;
                 value >>= to_shr;
 
                 // set output
-                simulator.set_out_value(&self.id, "out", SignalValue::Data(value));
+                simulator.set_out_value(
+                    &self.id,
+                    "out",
+                    SignalValue::from_bits(value, self.out_size as u8),
+                );
             }
-            _ => {
+            Err(_) => {
                 simulator.set_out_value(&self.id, "out", SignalValue::Unknown);
                 trace!("{} unknown input", self.id);
             }
@@ -92,6 +99,41 @@ impl Component for Sext {
         Ok(())
     }
 
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        let max = MAX_WIDTH as i64;
+        vec![
+            Parameter::input(SEXT_IN_ID, &self.sext_in),
+            Parameter::int("in_size", self.in_size as i64, 1, max),
+            Parameter::int("out_size", self.out_size as i64, 1, max),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: ParameterValue) -> Result<(), String> {
+        match name {
+            SEXT_IN_ID => self.sext_in = value.as_input()?,
+            "in_size" | "out_size" => {
+                let size = value.as_int()? as u32;
+                let (in_size, out_size) = match name {
+                    "in_size" => (size, self.out_size),
+                    _ => (self.in_size, size),
+                };
+                if in_size > out_size {
+                    return Err(format!(
+                        "in_size {} is larger than out_size {}",
+                        in_size, out_size
+                    ));
+                }
+                (self.in_size, self.out_size) = (in_size, out_size);
+            }
+            _ => return Err(unknown_parameter(name)),
+        }
+        Ok(())
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -108,14 +150,14 @@ impl Sext {
         }
     }
 
-    pub fn rc_new(
+    pub fn arc_new(
         id: &str,
         pos: (f32, f32),
         sext_in: Input,
         in_size: u32,
         out_size: u32,
-    ) -> Rc<Self> {
-        Rc::new(Sext::new(id, pos, sext_in, in_size, out_size))
+    ) -> Arc<Self> {
+        Arc::new(Sext::new(id, pos, sext_in, in_size, out_size))
     }
 }
 #[cfg(test)]
@@ -125,25 +167,23 @@ mod test {
 
     #[test]
     fn test_sext() {
-        let cs = ComponentStore {
-            store: vec![
-                Rc::new(ProbeOut::new("po")),
-                Rc::new(Sext {
-                    id: "sext32".to_string(),
-                    pos: (0.0, 0.0),
-                    sext_in: Input::new("po", "out"),
-                    in_size: 4,
-                    out_size: 32,
-                }),
-                Rc::new(Sext {
-                    id: "sext16".to_string(),
-                    pos: (0.0, 0.0),
-                    sext_in: Input::new("po", "out"),
-                    in_size: 4,
-                    out_size: 16,
-                }),
-            ],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("po")),
+            Arc::new(Sext {
+                id: "sext32".to_string(),
+                pos: (0.0, 0.0),
+                sext_in: Input::new("po", "out"),
+                in_size: 4,
+                out_size: 32,
+            }),
+            Arc::new(Sext {
+                id: "sext16".to_string(),
+                pos: (0.0, 0.0),
+                sext_in: Input::new("po", "out"),
+                in_size: 4,
+                out_size: 16,
+            }),
+        ]);
 
         let mut simulator = Simulator::new(cs).unwrap();
 
@@ -189,5 +229,38 @@ mod test {
         assert_eq!(simulator.cycle, 4);
         assert_eq!(simulator.get_input_value(sext32_out), 0b111.into());
         assert_eq!(simulator.get_input_value(sext16_out), 0b111.into());
+
+        // unknown input gives unknown output
+        simulator.set_out_value("po", "out", SignalValue::DontCare);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(sext32_out), SignalValue::Unknown);
+        assert_eq!(simulator.get_input_value(sext16_out), SignalValue::Unknown);
+    }
+
+    #[test]
+    fn test_sext_wide() {
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("po")),
+            Sext::arc_new("sext64", (0.0, 0.0), Input::new("po", "out"), 32, 64),
+        ]);
+        let mut simulator = Simulator::new(cs).unwrap();
+        let out = &Input::new("sext64", "out");
+
+        simulator.set_out_value("po", "out", 0x8000_0001);
+        simulator.clock();
+        assert_eq!(
+            simulator.get_input_value(out),
+            SignalValue::from_bits(0xFFFF_FFFF_8000_0001, 64)
+        );
+
+        simulator.set_out_value("po", "out", 0x7000_0001);
+        simulator.clock();
+        assert_eq!(
+            simulator.get_input_value(out),
+            SignalValue::Wide {
+                value: 0x7000_0001,
+                width: 64
+            }
+        );
     }
 }
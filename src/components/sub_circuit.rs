@@ -0,0 +1,405 @@
+use crate::common::{
+    Component, ComponentStore, Components, Condition, Id, Input, InputPort, OutputType, Ports,
+    Simulator,
+};
+use log::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// separator of hierarchical identifiers, e.g., `alu0/add`
+pub const HIERARCHY_SEPARATOR: &str = "/";
+
+/// Input port of a subcircuit
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SubCircuitInput {
+    pub port: Id,
+    /// signal connected to the port
+    pub input: Input,
+    /// internal signal driven by the port
+    ///
+    /// The internal component providing the signal (typically a `Constant` or `ProbeEdit`,
+    /// allowing the subcircuit model to be simulated on its own) is replaced by the port.
+    pub internal: Input,
+}
+
+/// Output port of a subcircuit
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SubCircuitOutput {
+    pub port: Id,
+    /// internal signal driving the port
+    pub internal: Input,
+}
+
+/// Subcircuit embedding another model file
+///
+/// When the simulator is built, the subcircuit is flattened: the internal components
+/// are added to the model, their identifiers prefixed by the subcircuit identifier
+/// (e.g., `alu0/add`, thus the internal signal `alu0/add/out`). The path is relative
+/// to the directory of the embedding model file, see `Component::set_model_dir`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SubCircuit {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) path: PathBuf,
+    pub(crate) inputs: Vec<SubCircuitInput>,
+    pub(crate) outputs: Vec<SubCircuitOutput>,
+    // set once flattened, the outputs then refer to (prefixed) internal signals
+    #[serde(skip)]
+    pub(crate) flattened: bool,
+    // directory of the embedding model file
+    #[serde(skip)]
+    pub(crate) dir: PathBuf,
+}
+
+#[typetag::serde]
+impl Component for SubCircuit {
+    fn to_(&self) {
+        trace!("sub_circuit {:?}", self.path);
+    }
+    fn get_id_ports(&self) -> (Id, Ports) {
+        let outputs = self.outputs.iter().map(|o| o.port.as_str()).collect();
+        let inputs: Vec<InputPort> = match self.flattened {
+            // the internal components are connected to the inputs,
+            // the outputs depend on internal signals
+            true => self
+                .outputs
+                .iter()
+                .map(|o| InputPort {
+                    port_id: o.port.clone(),
+                    input: o.internal.clone(),
+                })
+                .collect(),
+            false => self
+                .inputs
+                .iter()
+                .map(|i| InputPort {
+                    port_id: i.port.clone(),
+                    input: i.input.clone(),
+                })
+                .collect(),
+        };
+        (
+            self.id.clone(),
+            Ports::new(inputs.iter().collect(), OutputType::Combinatorial, outputs),
+        )
+    }
+
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        if let Some(i) = self.inputs.iter_mut().find(|i| i.port == target_port_id) {
+            i.input = new_input;
+        }
+    }
+
+    // propagate internal signals to outputs
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        // flattened, the inputs are the internal signals of the outputs
+        for (port, output) in self.outputs.iter().enumerate() {
+            let signal = simulator.get_port_signal(port);
+            simulator.set_out_value(&self.id, &output.port, signal.get_value());
+            simulator.set_out_fmt(&self.id, &output.port, signal.get_fmt());
+        }
+        Ok(())
+    }
+
+    fn set_model_dir(&mut self, dir: &Path) {
+        self.dir = dir.to_path_buf();
+    }
+
+    fn flatten(&self) -> Option<Result<Components, String>> {
+        if self.flattened {
+            return None;
+        }
+        Some(
+            self.load_flat()
+                .map_err(|e| format!("{}: {}", self.file().display(), e)),
+        )
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl SubCircuit {
+    pub fn new(
+        id: &str,
+        pos: (f32, f32),
+        path: impl Into<PathBuf>,
+        inputs: Vec<SubCircuitInput>,
+        outputs: Vec<SubCircuitOutput>,
+    ) -> Self {
+        SubCircuit {
+            id: id.to_string(),
+            pos,
+            path: path.into(),
+            inputs,
+            outputs,
+            flattened: false,
+            dir: PathBuf::new(),
+        }
+    }
+
+    pub fn arc_new(
+        id: &str,
+        pos: (f32, f32),
+        path: impl Into<PathBuf>,
+        inputs: Vec<SubCircuitInput>,
+        outputs: Vec<SubCircuitOutput>,
+    ) -> Arc<Self> {
+        Arc::new(SubCircuit::new(id, pos, path, inputs, outputs))
+    }
+
+    // model file, relative to the directory of the embedding model file
+    fn file(&self) -> PathBuf {
+        self.dir.join(&self.path)
+    }
+
+    // internal components with prefixed identifiers, followed by the flattened subcircuit
+    fn load_flat(&self) -> Result<Components, String> {
+        let file = self.file();
+        let json = std::fs::read_to_string(&file).map_err(|e| e.to_string())?;
+        let prefix = format!("{}{}", self.id, HIERARCHY_SEPARATOR);
+        let rename = |c: &mut serde_json::Map<String, Value>| {
+            if let Some(Value::String(id)) = c.get_mut("id") {
+                *id = format!("{}{}", prefix, id);
+            }
+        };
+        let mut cs =
+            ComponentStore::from_json_with(&json, Some(&rename)).map_err(|e| e.to_string())?;
+        cs.set_model_dir(file.parent().unwrap_or(Path::new("")));
+
+        let ports: HashMap<(&str, &str), &Input> = self
+            .inputs
+            .iter()
+            .map(|i| {
+                (
+                    (i.internal.id.as_str(), i.internal.field.as_str()),
+                    &i.input,
+                )
+            })
+            .collect();
+        let replaced: HashSet<&str> = self.inputs.iter().map(|i| i.internal.id.as_str()).collect();
+        let internal_id = |c: &dyn Component| {
+            let id = c.get_id_ports().0;
+            id.strip_prefix(&prefix).unwrap_or(&id).to_string()
+        };
+        let ids: HashSet<String> = cs.store.iter().map(|c| internal_id(c.as_ref())).collect();
+        let scope = Scope {
+            prefix: prefix.clone(),
+            ids: &ids,
+            ports: &ports,
+        };
+
+        let mut components: Components = vec![];
+        for mut c in cs.store {
+            if replaced.contains(internal_id(c.as_ref()).as_str()) {
+                continue;
+            }
+            // just deserialized, thus not shared
+            let component = Arc::get_mut(&mut c).unwrap();
+            for port in component.get_id_ports().1.inputs {
+                component.set_id_port(port.port_id, scope.input(&port.input));
+            }
+            components.push(c);
+        }
+
+        let mut flat = self.clone();
+        flat.flattened = true;
+        for output in &mut flat.outputs {
+            output.internal = scope.input(&output.internal);
+        }
+        components.push(Arc::new(flat));
+        Ok(components)
+    }
+}
+
+// renaming of the signals of a subcircuit model
+struct Scope<'a> {
+    prefix: String,
+    // identifiers of the components in the model
+    ids: &'a HashSet<String>,
+    // internal signals driven by the subcircuit input ports
+    ports: &'a HashMap<(&'a str, &'a str), &'a Input>,
+}
+
+impl Scope<'_> {
+    // signal as seen from outside of the subcircuit
+    fn input(&self, input: &Input) -> Input {
+        match self.ports.get(&(input.id.as_str(), input.field.as_str())) {
+            Some(outer) => (*outer).clone(),
+            None if self.ids.contains(&input.id) => {
+                Input::new(&format!("{}{}", self.prefix, input.id), &input.field)
+            }
+            None => input.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::components::*;
+
+    // adder model, with placeholders for its inputs
+    fn adder(path: &Path) {
+        let cs = ComponentStore::new(vec![
+            Arc::new(Constant::new("a", (0.0, 0.0), 0)),
+            Arc::new(Constant::new("b", (0.0, 0.0), 0)),
+            Add::arc_new(
+                "add",
+                (0.0, 0.0),
+                Input::new("a", "out"),
+                Input::new("b", "out"),
+            ),
+            Register::arc_new("reg", (0.0, 0.0), Input::new("add", "out")),
+        ]);
+        cs.save_file(&path.to_path_buf()).unwrap();
+    }
+
+    fn adder_ports(id: &str, path: &Path, a: Input, b: Input) -> Arc<SubCircuit> {
+        SubCircuit::arc_new(
+            id,
+            (0.0, 0.0),
+            path,
+            vec![
+                SubCircuitInput {
+                    port: "a".into(),
+                    input: a,
+                    internal: Input::new("a", "out"),
+                },
+                SubCircuitInput {
+                    port: "b".into(),
+                    input: b,
+                    internal: Input::new("b", "out"),
+                },
+            ],
+            vec![SubCircuitOutput {
+                port: "sum".into(),
+                internal: Input::new("reg", "out"),
+            }],
+        )
+    }
+
+    #[test]
+    fn test_sub_circuit() {
+        let dir = std::env::temp_dir().join(format!("sub_circuit_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("adder.json");
+        adder(&path);
+
+        // two adders in a chain
+        let cs = ComponentStore::new(vec![
+            Arc::new(Constant::new("c1", (0.0, 0.0), 1)),
+            Arc::new(Constant::new("c2", (0.0, 0.0), 2)),
+            adder_ports(
+                "alu0",
+                &path,
+                Input::new("c1", "out"),
+                Input::new("c2", "out"),
+            ),
+            adder_ports(
+                "alu1",
+                &path,
+                Input::new("alu0", "sum"),
+                Input::new("c2", "out"),
+            ),
+        ]);
+        let mut simulator = Simulator::new(cs).unwrap();
+        assert_eq!(simulator.components.len(), 4);
+        assert!(simulator.component_ids.contains(&"alu1/add".to_string()));
+        assert!(!simulator.component_ids.contains(&"alu1/a".to_string()));
+
+        simulator.clock();
+        let internal: Input = "alu0/add/out".parse().unwrap();
+        assert_eq!(simulator.get_input_value(&internal), 3.into());
+        assert_eq!(
+            simulator.get_input_value(&Input::new("alu0", "sum")),
+            3.into()
+        );
+        assert_eq!(
+            simulator.get_input_value(&Input::new("alu1", "sum")),
+            2.into()
+        );
+        simulator.clock();
+        assert_eq!(
+            simulator.get_input_value(&Input::new("alu1", "sum")),
+            5.into()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_sub_circuit_relative_path() {
+        let dir = std::env::temp_dir().join(format!("sub_circuit_rel_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("alu")).unwrap();
+        adder(&dir.join("alu").join("adder.json"));
+
+        // alu/alu.json refers to adder.json next to it
+        let alu = ComponentStore::new(vec![
+            Arc::new(Constant::new("x", (0.0, 0.0), 0)),
+            adder_ports(
+                "add0",
+                Path::new("adder.json"),
+                Input::new("x", "out"),
+                Input::new("x", "out"),
+            ),
+        ]);
+        alu.save_file(&dir.join("alu").join("alu.json")).unwrap();
+        let top = ComponentStore::new(vec![
+            Arc::new(Constant::new("c", (0.0, 0.0), 2)),
+            SubCircuit::arc_new(
+                "alu0",
+                (0.0, 0.0),
+                Path::new("alu").join("alu.json"),
+                vec![SubCircuitInput {
+                    port: "x".into(),
+                    input: Input::new("c", "out"),
+                    internal: Input::new("x", "out"),
+                }],
+                vec![SubCircuitOutput {
+                    port: "sum".into(),
+                    internal: Input::new("add0", "sum"),
+                }],
+            ),
+        ]);
+        let path = dir.join("top.json");
+        top.save_file(&path).unwrap();
+
+        // resolved against the model file, not the working directory
+        let cs = ComponentStore::load_file(&path).unwrap();
+        let mut simulator = Simulator::new(cs).unwrap();
+        assert!(simulator
+            .component_ids
+            .contains(&"alu0/add0/add".to_string()));
+        simulator.clock();
+        assert_eq!(
+            simulator.get_input_value(&Input::new("alu0", "sum")),
+            4.into()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_sub_circuit_missing_file() {
+        let cs = ComponentStore::new(vec![
+            Arc::new(Constant::new("c", (0.0, 0.0), 1)),
+            adder_ports(
+                "alu0",
+                Path::new("missing.json"),
+                Input::new("c", "out"),
+                Input::new("c", "out"),
+            ),
+        ]);
+        let errors = Simulator::new(cs).err().unwrap();
+        assert!(matches!(
+            errors.0.as_slice(),
+            [crate::common::ModelError::SubCircuit { id, .. }] if id == "alu0"
+        ));
+    }
+}
@@ -2,7 +2,7 @@ use crate::common::{Component, Id, Input, InputPort, OutputType, Ports};
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub const WIRE_INPUT_ID: &str = "in";
 
@@ -35,6 +35,16 @@ impl Component for Wire {
         )
     }
 
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        if target_port_id == WIRE_INPUT_ID {
+            self.input = new_input;
+        }
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -49,7 +59,7 @@ impl Wire {
         }
     }
 
-    pub fn rc_new(id: &str, pos: Vec<(f32, f32)>, input: Input) -> Rc<Wire> {
-        Rc::new(Wire::new(id, pos, input))
+    pub fn arc_new(id: &str, pos: Vec<(f32, f32)>, input: Input) -> Arc<Wire> {
+        Arc::new(Wire::new(id, pos, input))
     }
 }
@@ -0,0 +1,201 @@
+// Export of the model graph in Graphviz DOT format
+//
+// Each (flattened) component is a node labeled by its identifier and type, each
+// connected input an edge from the driving component, optionally labeled
+// `field -> port` and annotated with the current signal value. Sequential
+// components are drawn as boxes, optionally clustered, combinational components
+// as ellipses. Wires only connect, thus are hidden by default.
+use crate::common::{Component, Id, OutputType, Simulator};
+use crate::timing::CriticalPath;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Options of the DOT export
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotOptions {
+    /// label edges by output field and input port, `field -> port`
+    pub labels: bool,
+    /// annotate edges with the current signal value
+    pub values: bool,
+    /// gather sequential components in a cluster
+    pub cluster_sequential: bool,
+    /// hide `Wire` components
+    pub hide_wires: bool,
+    /// highlight the critical path, see `Simulator::critical_path`
+    pub critical_path: bool,
+}
+
+impl Default for DotOptions {
+    fn default() -> Self {
+        DotOptions {
+            labels: true,
+            values: false,
+            cluster_sequential: true,
+            hide_wires: true,
+            critical_path: true,
+        }
+    }
+}
+
+// quoted and escaped identifier
+fn quote(s: &str) -> String {
+    let s = s.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{}\"", s.replace('\n', "\\n"))
+}
+
+const HIGHLIGHT: &str = ", color = red, penwidth = 2";
+
+/// write model graph of the simulator as DOT
+pub fn write_dot(
+    simulator: &Simulator,
+    options: &DotOptions,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    let critical = match options.critical_path {
+        true => simulator.critical_path().clone(),
+        false => CriticalPath::default(),
+    };
+    let components: Vec<_> = simulator
+        .ordered_components
+        .iter()
+        .filter(|c| !(options.hide_wires && Component::typetag_name(c.as_ref()) == "Wire"))
+        .collect();
+
+    writeln!(w, "digraph {{")?;
+    writeln!(w, "    rankdir = LR;")?;
+    let node = |w: &mut dyn Write, c: &dyn Component, indent: &str| -> io::Result<()> {
+        let (id, ports) = c.get_id_ports();
+        let shape = match ports.out_type {
+            OutputType::Sequential => "box",
+            OutputType::Combinatorial => "ellipse",
+        };
+        let highlight = if critical.contains(&id) {
+            HIGHLIGHT
+        } else {
+            ""
+        };
+        writeln!(
+            w,
+            "{}{} [label = {}, shape = {}{}];",
+            indent,
+            quote(&id),
+            quote(&format!("{}\n{}", id, c.typetag_name())),
+            shape,
+            highlight
+        )
+    };
+    // sequential components come first, wires are combinational
+    let (sequential, combinatorial) = components.split_at(simulator.nr_sequential);
+    if options.cluster_sequential && !sequential.is_empty() {
+        writeln!(w, "    subgraph cluster_sequential {{")?;
+        writeln!(w, "        label = \"sequential\";")?;
+        writeln!(w, "        style = dashed;")?;
+        for c in sequential {
+            node(w, c.as_ref(), "        ")?;
+        }
+        writeln!(w, "    }}")?;
+    } else {
+        for c in sequential {
+            node(w, c.as_ref(), "    ")?;
+        }
+    }
+    for c in combinatorial {
+        node(w, c.as_ref(), "    ")?;
+    }
+
+    let ids: Vec<Id> = components.iter().map(|c| c.get_id_ports().0).collect();
+    for c in &components {
+        let (id, ports) = c.get_id_ports();
+        for port in &ports.inputs {
+            let input = &port.input;
+            // unconnected (or connected to hidden components)
+            if !ids.contains(&input.id) {
+                continue;
+            }
+            let mut label = vec![];
+            if options.labels {
+                label.push(format!("{} -> {}", input.field, port.port_id));
+            }
+            if options.values {
+                if let Some(index) = simulator.resolve_input(input) {
+                    label.push(format!("{}", simulator.get_signal(index)));
+                }
+            }
+            let highlight = match critical.contains_edge(&input.id, &id) {
+                true => HIGHLIGHT,
+                false => "",
+            };
+            writeln!(
+                w,
+                "    {} -> {} [label = {}{}];",
+                quote(&input.id),
+                quote(&id),
+                quote(&label.join("\n")),
+                highlight
+            )?;
+        }
+    }
+    writeln!(w, "}}")
+}
+
+impl Simulator {
+    /// save model graph as `dot` file
+    pub fn save_dot_with(&self, path: &Path, options: &DotOptions) -> io::Result<()> {
+        let mut file = io::BufWriter::new(File::create(path)?);
+        write_dot(self, options, &mut file)?;
+        file.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::{ComponentStore, Input};
+    use crate::components::*;
+    use crate::fixtures::counter_components;
+
+    // counter, with a wire from its adder
+    fn counter() -> Simulator {
+        let mut store = counter_components();
+        store.push(Wire::arc_new(
+            "w",
+            vec![(0.0, 0.0)],
+            Input::new("add", "out"),
+        ));
+        Simulator::new(ComponentStore::new(store)).unwrap()
+    }
+
+    fn dot(simulator: &Simulator, options: &DotOptions) -> String {
+        let mut dot = vec![];
+        write_dot(simulator, options, &mut dot).unwrap();
+        String::from_utf8(dot).unwrap()
+    }
+
+    #[test]
+    fn test_dot() {
+        let simulator = counter();
+        let default = dot(&simulator, &DotOptions::default());
+        assert!(default.starts_with("digraph {"));
+        assert!(default.contains("subgraph cluster_sequential"));
+        assert!(default.contains("\"r1\" [label = \"r1\\nRegister\", shape = box, color = red"));
+        assert!(default.contains("\"r1\" -> \"add\" [label = \"out -> a_in\", color = red"));
+        assert!(default.contains("\"add\" -> \"r1\" [label = \"out -> r_in\", color = red"));
+        assert!(!default.contains("\"w\""));
+
+        let options = DotOptions {
+            labels: false,
+            values: true,
+            cluster_sequential: false,
+            hide_wires: false,
+            critical_path: false,
+        };
+        let annotated = dot(&simulator, &options);
+        assert!(!annotated.contains("cluster"));
+        assert!(!annotated.contains("red"));
+        assert!(annotated.contains("\"add\" -> \"w\""));
+        // r1 is 1 after the initial clock
+        let value = format!("{}", simulator.get_input_signal(&Input::new("r1", "out")));
+        assert!(annotated.contains(&format!("\"r1\" -> \"add\" [label = {}]", quote(&value))));
+    }
+}
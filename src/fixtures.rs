@@ -0,0 +1,23 @@
+// Models shared by the unit tests
+use crate::common::{ComponentStore, Components, Input, Simulator};
+use crate::components::*;
+use std::sync::Arc;
+
+/// counter `r1`, incremented by `c1` through `add`, to be extended by tests
+pub(crate) fn counter_components() -> Components {
+    vec![
+        Arc::new(Constant::new("c1", (0.0, 0.0), 1)),
+        Register::arc_new("r1", (0.0, 0.0), Input::new("add", "out")),
+        Add::arc_new(
+            "add",
+            (0.0, 0.0),
+            Input::new("r1", "out"),
+            Input::new("c1", "out"),
+        ),
+    ]
+}
+
+/// simulator of the counter, see `counter_components`
+pub(crate) fn counter() -> Simulator {
+    Simulator::new(ComponentStore::new(counter_components())).unwrap()
+}
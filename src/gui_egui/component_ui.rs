@@ -1,9 +1,10 @@
-use crate::common::{Input, Ports};
+use crate::common::{Component, Input, Ports};
 use crate::gui_egui::editor::{EditorMode, GridOptions};
 use crate::gui_egui::editor_wire_mode::get_grid_snap;
 use crate::gui_egui::helper::{
     editor_mode_to_sense, offset_helper, out_of_bounds, unique_component_name,
 };
+use crate::parameter::{set_parameter, ParameterValue};
 use egui::{
     containers, Color32, ComboBox, Context, DragValue, Frame, Key, KeyboardShortcut, Margin,
     Modifiers, PointerButton, Pos2, Rect, Response, Rounding, Shape, Stroke, Ui, Vec2, Window,
@@ -150,6 +151,67 @@ pub fn input_selector(
     input_selector_removeable(ui, input, port_name, id_ports, own_id, false).0
 }
 
+/// Editors of the parameters of a component (`Component::parameters`), an edited
+/// value is applied if valid, otherwise the error is shown below its editor.
+/// Returns true if an input was selected, as `input_selector`
+pub fn parameters_editor(
+    ui: &mut Ui,
+    component: &mut dyn Component,
+    id_ports: &[(crate::common::Id, Ports)],
+) -> bool {
+    let own_id = component.get_id_ports().0;
+    let mut clicked_dropdown = false;
+    for parameter in component.parameters() {
+        let mut value = parameter.value.clone();
+        match &mut value {
+            ParameterValue::Int { value, min, max } => {
+                ui.horizontal(|ui| {
+                    ui.label(&parameter.name);
+                    ui.add(DragValue::new(value).range(*min..=*max));
+                });
+            }
+            ParameterValue::Bool(value) => {
+                ui.checkbox(value, &parameter.name);
+            }
+            ParameterValue::Enum { value, options } => {
+                ComboBox::new((&own_id, &parameter.name), &parameter.name)
+                    .selected_text(value.as_str())
+                    .show_ui(ui, |ui| {
+                        for option in options.iter() {
+                            ui.selectable_value(value, option.clone(), option);
+                        }
+                    });
+            }
+            ParameterValue::Range { value, min, max } => {
+                ui.horizontal(|ui| {
+                    ui.label(&parameter.name);
+                    ui.add(DragValue::new(&mut value.start).range(*min..=*max));
+                    ui.label("..");
+                    ui.add(DragValue::new(&mut value.end).range(*min..=*max));
+                });
+            }
+            ParameterValue::Input { value, port_id } => {
+                clicked_dropdown |=
+                    input_selector(ui, value, port_id.clone(), id_ports, own_id.clone());
+            }
+        }
+
+        // the error of the last edit is kept until a valid edit
+        let error_id = egui::Id::new((&own_id, &parameter.name, "parameter error"));
+        if value != parameter.value {
+            let result = set_parameter(component, &parameter.name, value);
+            ui.data_mut(|data| match result {
+                Ok(()) => data.remove::<String>(error_id),
+                Err(e) => data.insert_temp(error_id, e),
+            });
+        }
+        if let Some(e) = ui.data(|data| data.get_temp::<String>(error_id)) {
+            ui.colored_label(Color32::RED, e);
+        }
+    }
+    clicked_dropdown
+}
+
 pub fn input_change_id(
     ui: &mut Ui,
     id_tmp: &mut String,
@@ -1,13 +1,13 @@
 use crate::common::{EguiComponent, Ports, Simulator};
 use crate::components::Mem;
 use crate::gui_egui::component_ui::{
-    drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
+    drag_logic, input_change_id, parameters_editor, pos_drag_value, properties_window,
     rect_with_hover, visualize_ports,
 };
 use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
 use crate::gui_egui::gui::EguiExtra;
 use crate::gui_egui::helper::offset_helper;
-use egui::{Color32, Pos2, Rect, Response, Rounding, Shape, Slider, Stroke, Ui, Vec2};
+use egui::{Color32, Pos2, Rect, Response, Rounding, Shape, Stroke, Ui, Vec2};
 
 #[typetag::serde]
 impl EguiComponent for Mem {
@@ -97,50 +97,9 @@ impl EguiComponent for Mem {
             resp,
             &mut context.properties_window,
             |ui| {
-                let mut clicked_dropdown = false;
                 input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
                 pos_drag_value(ui, &mut self.pos);
-                ui.horizontal(|ui| {
-                    ui.add(Slider::new(&mut self.width, 0f32..=400f32).text("width"));
-                    ui.add(Slider::new(&mut self.height, 0f32..=400f32).text("height"));
-                });
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.data,
-                    crate::components::MEM_DATA_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.addr,
-                    crate::components::MEM_ADDR_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.ctrl,
-                    crate::components::MEM_CTRL_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.sext,
-                    crate::components::MEM_SEXT_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.size,
-                    crate::components::MEM_SIZE_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                // todo: something about memory?
-                clicked_dropdown
+                parameters_editor(ui, self, id_ports)
             },
         );
 
@@ -151,10 +151,7 @@ impl EguiComponent for Mux {
                     !delete
                 });
                 if ui.button("+ Add new input").clicked() {
-                    self.m_in.push(Input {
-                        id: "id".to_string(),
-                        field: "field".to_string(),
-                    });
+                    self.m_in.push(Input::new("id", "field"));
                 }
                 clicked_dropdown
             },
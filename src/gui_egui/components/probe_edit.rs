@@ -57,6 +57,7 @@ impl EguiComponent for ProbeEdit {
                     SignalValue::Uninitialized => ui.label("Uninitialized"),
                     SignalValue::DontCare => ui.label("DontCare"),
                     SignalValue::Unknown => ui.label("Unknown"),
+                    SignalValue::Wide { value, .. } => ui.label(format!("{:#x}", value)),
                 };
                 r.on_hover_text(format!(
                     "{:?}",
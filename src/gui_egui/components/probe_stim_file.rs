@@ -0,0 +1,184 @@
+use crate::common::{EguiComponent, Id, Input, Ports, Simulator};
+use crate::components::ProbeStimFile;
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, pos_drag_value, properties_window, rect_with_hover,
+    visualize_ports,
+};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::EguiExtra;
+use egui::{Align2, Area, Color32, Order, Pos2, Rect, Response, RichText, TextWrapMode, Ui, Vec2};
+
+// height of each channel row
+const ROW_HEIGHT: f32 = 16f32;
+
+#[typetag::serde]
+impl EguiComponent for ProbeStimFile {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        let offset_old = offset;
+        let mut offset = offset;
+        offset.x += self.pos.0 * scale;
+        offset.y += self.pos.1 * scale;
+        let values: Vec<String> = self
+            .channels
+            .iter()
+            .map(|channel| match &simulator {
+                Some(s) => format!(
+                    "{}: {}",
+                    channel,
+                    s.get_input_signal(&Input::new(&self.id, channel))
+                ),
+                None => channel.clone(),
+            })
+            .collect();
+        let area = Area::new(egui::Id::from(self.id.to_string()))
+            .order(Order::Middle)
+            .current_pos(offset.to_pos2())
+            .movable(false)
+            .enabled(true)
+            .interactable(false)
+            .pivot(Align2::CENTER_CENTER)
+            .constrain(false)
+            .show(ui.ctx(), |ui| {
+                ui.set_clip_rect(clip_rect);
+                ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                ui.spacing_mut().item_spacing.y = 0f32;
+                for value in &values {
+                    ui.label(
+                        RichText::new(value)
+                            .size(scale * 12f32)
+                            .background_color(Color32::LIGHT_BLUE),
+                    );
+                }
+            });
+        let r = rect_with_hover(
+            area.response.rect,
+            clip_rect,
+            editor_mode,
+            ui,
+            self.id.clone(),
+            |ui| {
+                ui.label(format!("Id: {}", self.id.clone()));
+                ui.label(format!("File: {}", self.path.display()));
+                if self.looping {
+                    ui.label("Looping");
+                }
+            },
+        );
+        match editor_mode {
+            EditorMode::Simulator => (),
+            _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
+        }
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = ProbeStimFile::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+                let mut path = self.path.display().to_string();
+                let mut channels = self.channels.join(", ");
+                ui.horizontal(|ui| {
+                    ui.label("File:");
+                    ui.text_edit_singleline(&mut path);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Channels:");
+                    ui.text_edit_singleline(&mut channels);
+                });
+                let channels: Vec<Id> = channels
+                    .split(',')
+                    .map(|c| c.trim())
+                    .filter(|c| !c.is_empty())
+                    .map(|c| c.into())
+                    .collect();
+                // the stimulus is loaded again for a new file or channels
+                if self.path.as_os_str() != path.as_str() || self.channels != channels {
+                    self.path = path.into();
+                    self.channels = channels;
+                    *self.stimulus.write().unwrap() = None;
+                }
+                ui.checkbox(&mut self.looping, "Looping");
+                false
+            },
+        );
+
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    fn ports_location(&self) -> Vec<(Id, Pos2)> {
+        let own_pos = Vec2::new(self.pos.0, self.pos.1);
+        let top = -ROW_HEIGHT * (self.channels.len() as f32 - 1f32) / 2f32;
+        self.channels
+            .iter()
+            .enumerate()
+            .map(|(i, channel)| {
+                (
+                    channel.clone(),
+                    Pos2::new(0f32, top + ROW_HEIGHT * i as f32) + own_pos,
+                )
+            })
+            .collect()
+    }
+
+    fn top_padding(&self) -> f32 {
+        ROW_HEIGHT * self.channels.len() as f32 / 2f32
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
@@ -1,13 +1,13 @@
 use crate::common::{EguiComponent, Ports, SignalUnsigned, Simulator};
 use crate::components::Sext;
 use crate::gui_egui::component_ui::{
-    drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
+    drag_logic, input_change_id, parameters_editor, pos_drag_value, properties_window,
     rect_with_hover, visualize_ports,
 };
 use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
 use crate::gui_egui::gui::EguiExtra;
 use crate::gui_egui::helper::offset_helper;
-use egui::{Color32, Pos2, Rect, Response, Shape, Slider, Stroke, Ui, Vec2};
+use egui::{Color32, Pos2, Rect, Response, Shape, Stroke, Ui, Vec2};
 
 #[typetag::serde]
 impl EguiComponent for Sext {
@@ -110,21 +110,9 @@ impl EguiComponent for Sext {
             resp,
             &mut context.properties_window,
             |ui| {
-                let mut clicked_dropdown = false;
                 input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
                 pos_drag_value(ui, &mut self.pos);
-                clicked_dropdown |= input_selector(
-                    ui,
-                    &mut self.sext_in,
-                    crate::components::SEXT_IN_ID.to_string(),
-                    id_ports,
-                    self.id.clone(),
-                );
-                ui.horizontal(|ui| {
-                    ui.add(Slider::new(&mut self.in_size, 0..=32).text("in_size"));
-                    ui.add(Slider::new(&mut self.out_size, self.in_size..=32).text("out_size"));
-                });
-                clicked_dropdown
+                parameters_editor(ui, self, id_ports)
             },
         );
         EditorRenderReturn {
@@ -0,0 +1,189 @@
+use crate::common::{EguiComponent, Input, Ports, Simulator};
+use crate::components::SubCircuit;
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
+    rect_with_hover, visualize_ports,
+};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::EguiExtra;
+use crate::gui_egui::helper::offset_helper;
+use egui::{Align2, Color32, FontId, Pos2, Rect, Response, Rounding, Shape, Stroke, Ui, Vec2};
+
+// distance between ports
+const PORT_SPACING: f32 = 20f32;
+// half width of the box
+const HALF_WIDTH: f32 = 30f32;
+
+impl SubCircuit {
+    // half height of the box, fitting the ports of the widest side
+    fn half_height(&self) -> f32 {
+        PORT_SPACING * self.inputs.len().max(self.outputs.len()).max(1) as f32 / 2f32
+    }
+}
+
+#[typetag::serde]
+impl EguiComponent for SubCircuit {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        let oh: fn((f32, f32), f32, Vec2) -> Pos2 = offset_helper;
+        let offset_old = offset;
+        let mut offset = offset;
+        offset.x += self.pos.0 * scale;
+        offset.y += self.pos.1 * scale;
+        let s = scale;
+        let o = offset;
+        let h = self.half_height();
+
+        // The shape
+        let rect = Rect {
+            min: oh((-HALF_WIDTH, -h), s, o),
+            max: oh((HALF_WIDTH, h), s, o),
+        };
+        ui.painter().add(Shape::rect_stroke(
+            rect,
+            Rounding::ZERO,
+            Stroke {
+                width: scale,
+                color: Color32::BLACK,
+            },
+        ));
+        ui.painter().text(
+            oh((0f32, 0f32), s, o),
+            Align2::CENTER_CENTER,
+            &self.id,
+            FontId::monospace(10f32 * scale),
+            Color32::BLACK,
+        );
+
+        let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
+            ui.label(format!("Id: {}", self.id.clone()));
+            ui.label(format!("File: {}", self.path.display()));
+            if let Some(s) = &simulator {
+                for output in &self.outputs {
+                    ui.label(format!(
+                        "{}: {}",
+                        output.port,
+                        s.get_input_signal(&Input::new(&self.id, &output.port))
+                    ));
+                }
+            }
+        });
+
+        match editor_mode {
+            EditorMode::Simulator => (),
+            _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
+        }
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(crate::common::Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = SubCircuit::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                let mut clicked_dropdown = false;
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+                let mut path = self.path.display().to_string();
+                ui.horizontal(|ui| {
+                    ui.label("File:");
+                    ui.text_edit_singleline(&mut path);
+                });
+                self.path = path.into();
+                for input in &mut self.inputs {
+                    clicked_dropdown |= input_selector(
+                        ui,
+                        &mut input.input,
+                        input.port.clone(),
+                        id_ports,
+                        self.id.clone(),
+                    );
+                }
+                clicked_dropdown
+            },
+        );
+
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
+        let own_pos = Vec2::new(self.pos.0, self.pos.1);
+        let h = self.half_height();
+        // ports evenly spread along the side
+        let side = |ports: Vec<&String>, x: f32| {
+            let n = ports.len() as f32;
+            ports
+                .into_iter()
+                .enumerate()
+                .map(move |(i, port)| {
+                    let y = -h + 2f32 * h * (i as f32 + 0.5f32) / n;
+                    (port.clone(), Pos2::new(x, y) + own_pos)
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut ports = side(self.inputs.iter().map(|i| &i.port).collect(), -HALF_WIDTH);
+        ports.extend(side(
+            self.outputs.iter().map(|o| &o.port).collect(),
+            HALF_WIDTH,
+        ));
+        ports
+    }
+
+    fn top_padding(&self) -> f32 {
+        self.half_height()
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
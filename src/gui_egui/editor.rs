@@ -1,5 +1,4 @@
 use crate::common::{Components, EguiComponent, Id, Input};
-use crate::components::*;
 use crate::gui_egui::gui::EguiExtra;
 use crate::gui_egui::{
     editor_wire_mode::WireMode,
@@ -9,13 +8,13 @@ use crate::gui_egui::{
     library::InputMode,
     menu::Menu,
 };
+use crate::registry::component_types;
 use eframe::{egui, Frame};
 use egui::{Color32, Context, LayerId, PointerButton, Pos2, Rect, Shape, Vec2};
 use std::{
     collections::{BTreeMap, HashMap},
-    ops::Range,
     path::Path,
-    rc::Rc,
+    sync::Arc,
 };
 
 pub struct Editor {
@@ -35,11 +34,13 @@ pub struct Editor {
     pub wm: WireMode,
     pub im: InputMode,
     pub contexts: HashMap<crate::common::Id, EguiExtra>,
+    // delay annotations of the model, kept for saving
+    pub delays: BTreeMap<Id, f64>,
 }
 
 #[derive(Clone)]
 pub struct CloseToComponent {
-    pub comp: Rc<dyn EguiComponent>,
+    pub comp: Arc<dyn EguiComponent>,
     pub pos: Pos2,
     pub dist: f32,
     pub port_id: Id,
@@ -78,62 +79,13 @@ pub struct GridOptions {
 #[derive(Clone)]
 pub struct Library(pub Components);
 impl Default for Library {
+    // the component types of the registry shown in the library
     fn default() -> Library {
-        let dummy_input = Input::new("id", "field");
-        let library: Components = vec![
-            Rc::new(Add {
-                id: "add".to_string(),
-                pos: (0.0, 0.0),
-                a_in: dummy_input.clone(),
-                b_in: dummy_input.clone(),
-            }),
-            Rc::new(Constant {
-                id: "c".to_string(),
-                pos: (0.0, 0.0),
-                value: 0.into(),
-            }),
-            Rc::new(Cross {
-                id: "p".to_string(),
-                pos: (0.0, 0.0),
-                input: dummy_input.clone(),
-            }),
-            Rc::new(ProbeEdit::new("pe", (0.0, 0.0))),
-            Rc::new(Sext {
-                id: "sext".to_string(),
-                pos: (0.0, 0.0),
-                sext_in: dummy_input.clone(),
-                in_size: 16,
-                out_size: 24,
-            }),
-            Rc::new(Mem {
-                id: "mem".to_string(),
-                pos: (0.0, 0.0),
-                width: 100.0,
-                height: 50.0,
-                big_endian: true,
-                data: dummy_input.clone(),
-                addr: dummy_input.clone(),
-                ctrl: dummy_input.clone(),
-                size: dummy_input.clone(),
-                sext: dummy_input.clone(),
-                range: Range {
-                    start: 0,
-                    end: 0x20,
-                },
-                memory: Memory::new(BTreeMap::new()),
-            }),
-            Rc::new(Mux {
-                id: "mux".to_string(),
-                pos: (0.0, 0.0),
-                select: dummy_input.clone(),
-                m_in: vec![dummy_input.clone(), dummy_input.clone()],
-            }),
-            Rc::new(Register {
-                id: "reg".to_string(),
-                pos: (0.0, 0.0),
-                r_in: dummy_input.clone(),
-            }),
-        ];
+        let library = component_types()
+            .iter()
+            .filter(|t| t.library)
+            .map(|t| (t.new)(&t.name.to_lowercase(), (0.0, 0.0)))
+            .collect();
         Library(library)
     }
 }
@@ -182,6 +134,7 @@ impl Editor {
                 library_contexts,
             },
             contexts: HashMap::new(),
+            delays: BTreeMap::new(),
         };
         e.contexts = crate::gui_egui::gui::create_contexts(&e.components);
         e
@@ -357,7 +310,7 @@ impl Editor {
                 _ => e.components.retain_mut(|c| {
                     let old_key = c.as_ref().get_id_ports().0;
                     let mut context = e.contexts.remove(&old_key).unwrap();
-                    let render_return = (*Rc::get_mut(c).unwrap()).render_editor(
+                    let render_return = (*Arc::get_mut(c).unwrap()).render_editor(
                         ui,
                         &mut context,
                         None,
@@ -411,7 +364,7 @@ pub fn get_component(components: &Components, comp: CloseToComponent) -> Option<
         // doing std::ptr::eq doesn't work and this works so I'm going to keep it
         // even if clippy errors on it
         #[allow(ambiguous_wide_pointer_comparisons)]
-        if Rc::ptr_eq(c, &comp.comp) {
+        if Arc::ptr_eq(c, &comp.comp) {
             drop(comp);
             return Some(i);
         }
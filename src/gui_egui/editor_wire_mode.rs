@@ -11,7 +11,7 @@ use crate::gui_egui::helper::{
 use egui::{
     Color32, Context, CursorIcon, LayerId, PointerButton, Pos2, Rect, Response, Shape, Stroke, Vec2,
 };
-use std::{path::PathBuf, rc::Rc};
+use std::{path::PathBuf, sync::Arc};
 
 pub struct WireMode {
     pub mode_ended: bool,
@@ -99,9 +99,9 @@ pub fn last_click(e: &mut Editor, closest_uw: CloseToComponent) {
 
             // Now actually set the input of the wired component
             #[allow(ambiguous_wide_pointer_comparisons)]
-            if !Rc::ptr_eq(&in_c.comp, &out_c.comp) {
+            if !Arc::ptr_eq(&in_c.comp, &out_c.comp) {
                 let comp = if is_input_in_comp_start { out_c } else { in_c };
-                e.components.push(Rc::new(Wire {
+                e.components.push(Arc::new(Wire {
                     id: id.to_string(),
                     pos: pos_v,
                     input: i.clone(),
@@ -119,7 +119,7 @@ pub fn last_click(e: &mut Editor, closest_uw: CloseToComponent) {
                 if let Some(c) = get_component(&e.components, comp) {
                     println!("setting id_port");
 
-                    Rc::get_mut(&mut e.components[c])
+                    Arc::get_mut(&mut e.components[c])
                         .unwrap()
                         .set_id_port(field_name, i);
                 }
@@ -132,10 +132,13 @@ pub fn last_click(e: &mut Editor, closest_uw: CloseToComponent) {
         }
     }
     let path = PathBuf::from("autosave.json");
-    ComponentStore {
+    let cs = ComponentStore {
         store: e.components.clone(),
+        delays: e.delays.clone(),
+    };
+    if let Err(e) = cs.save_file(&path) {
+        log::warn!("couldn't autosave: {}", e);
     }
-    .save_file(&path);
     reset_wire_mode(&mut e.wm);
 }
 
@@ -180,7 +183,7 @@ pub fn wire_mode(ctx: &Context, e: &mut Editor, cpr: Response, layer_id: Option<
                 (wire_shown_location.x, wire_shown_location.y),
             );
             let mut draw_vec: Vec<Pos2> = vec![];
-            for (posx, posy) in e.wm.temp_positions.clone().into_iter().chain(v.into_iter()) {
+            for (posx, posy) in e.wm.temp_positions.clone().into_iter().chain(v) {
                 draw_vec.push(Pos2::new(posx, posy))
             }
 
@@ -243,10 +246,7 @@ pub fn get_outputs_from_port(
                 let (id, ports_start) = comp_start.comp.get_id_ports();
                 for port_id in ports_start.outputs {
                     if port_id == *comp_start.port_id {
-                        o = Some(Input {
-                            id: id.clone(),
-                            field: port_id,
-                        });
+                        o = Some(Input::new(&id, &port_id));
                     }
                 }
                 o
@@ -262,10 +262,7 @@ pub fn get_outputs_from_port(
                 let (id, ports_end) = comp_end.comp.get_id_ports();
                 for port_id in ports_end.outputs {
                     if port_id == *comp_end.port_id {
-                        o = Some(Input {
-                            id: id.clone(),
-                            field: port_id,
-                        });
+                        o = Some(Input::new(&id, &port_id));
                     }
                 }
                 o
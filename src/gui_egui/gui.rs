@@ -1,4 +1,5 @@
 use crate::common::{ComponentStore, Components, Simulator};
+use crate::components::HIERARCHY_SEPARATOR;
 use crate::gui_egui::editor::EditorMode;
 use crate::gui_egui::{
     editor::{Editor, Library},
@@ -6,16 +7,28 @@ use crate::gui_egui::{
     keymap::Shortcuts,
     menu::Menu,
 };
+use crate::profile::Column;
+use crate::simulator_thread::{Command, SimulatorThread};
+use crate::speed::Speed;
+use crate::watchpoint::Watchpoint;
 use eframe::egui;
 use egui::{
-    containers, CentralPanel, Color32, Context, PointerButton, Pos2, Rect, Sense, TopBottomPanel,
-    Vec2,
+    containers, CentralPanel, Color32, Context, Grid, Key, PointerButton, Pos2, Rect, RichText,
+    Rounding, ScrollArea, Sense, Shape, Stroke, TextEdit, TopBottomPanel, Vec2, Window,
 };
+use rfd::FileDialog;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
 pub struct Gui {
     pub simulator: Option<Simulator>,
+    // Simulator running in the background, `simulator` then shows its snapshots
+    pub simulator_thread: Option<SimulatorThread>,
+    // Speed when playing, and the clock rate achieved
+    pub speed: Speed,
+    pub achieved_hz: f64,
+    // Last cycle reached, the end of the timeline
+    pub timeline_end: usize,
     pub path: PathBuf,
     // History, acts like a stack
     pub scale: f32,
@@ -30,6 +43,30 @@ pub struct Gui {
     pub editor_use: bool,
     pub contexts: HashMap<crate::common::Id, EguiExtra>,
     pub library: Library,
+    pub watchpoints: WatchpointsUi,
+    pub profile: ProfileUi,
+    // Highlight the critical path
+    pub critical_path: bool,
+    // Error shown in a dialog, e.g., failing to open a model
+    pub error: Option<String>,
+}
+
+/// State of the watchpoints window
+#[derive(Clone, Debug, Default)]
+pub struct WatchpointsUi {
+    pub open: bool,
+    // watchpoint being entered
+    pub text: String,
+    pub error: Option<String>,
+}
+
+/// State of the profile window
+#[derive(Clone, Debug, Default)]
+pub struct ProfileUi {
+    pub open: bool,
+    // column sorted by
+    pub sort: Column,
+    pub descending: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -50,6 +87,10 @@ pub fn gui(cs: ComponentStore, path: &PathBuf, library: Library) -> Result<(), e
     let gui = Gui {
         path,
         simulator: Some(simulator),
+        simulator_thread: None,
+        speed: Speed::default(),
+        achieved_hz: 0.0,
+        timeline_end: 0,
         scale: 1.0f32,
         ui_change: true,
         offset: Vec2 { x: 0f32, y: 0f32 },
@@ -61,6 +102,10 @@ pub fn gui(cs: ComponentStore, path: &PathBuf, library: Library) -> Result<(), e
         editor_use: false,
         contexts,
         library,
+        watchpoints: WatchpointsUi::default(),
+        profile: ProfileUi::default(),
+        critical_path: false,
+        error: None,
     };
 
     eframe::run_native("SyncRim", options, Box::new(|_cc| Ok(Box::new(gui))))
@@ -69,6 +114,7 @@ pub fn gui(cs: ComponentStore, path: &PathBuf, library: Library) -> Result<(), e
 impl eframe::App for Gui {
     fn update(&mut self, ctx: &Context, frame: &mut eframe::Frame) {
         self.shortcuts.inputs(ctx, self);
+        self.error_window(ctx);
         if self.editor_use {
             crate::gui_egui::editor::Editor::update(ctx, frame, self);
             return;
@@ -103,13 +149,20 @@ impl eframe::App for Gui {
             self.top_bar(ctx);
             if self.simulator.is_some() {
                 // self.side_panel(ctx);
-                if self.simulator.as_ref().unwrap().running {
-                    self.simulator.as_mut().unwrap().run();
-
+                if let Some(thread) = &self.simulator_thread {
+                    let simulator = self.simulator.as_mut().unwrap();
+                    if let Some(snapshot) = thread.latest() {
+                        self.achieved_hz = snapshot.hz;
+                        simulator.apply_snapshot(snapshot);
+                    }
                     // This makes the ui run agin as to not stop the simulation
                     // when no ui events are happening
-                    ctx.request_repaint();
+                    if simulator.running {
+                        ctx.request_repaint();
+                    }
                 }
+                self.watchpoints_window(ctx);
+                self.profile_window(ctx);
                 self.draw_area(ctx, frame);
             }
         }
@@ -117,6 +170,32 @@ impl eframe::App for Gui {
 }
 
 impl Gui {
+    /// take back the simulator from the simulation thread, if any
+    pub fn join_simulator_thread(&mut self) {
+        if let Some(thread) = self.simulator_thread.take() {
+            self.simulator = Some(thread.join());
+        }
+    }
+
+    /// move to cycle, pausing the simulation
+    pub fn goto_cycle(&mut self, cycle: usize) {
+        self.join_simulator_thread();
+        if let Some(simulator) = self.simulator.as_mut() {
+            if let Err(e) = simulator.goto_cycle(cycle) {
+                self.error = Some(format!("Couldn't go to cycle {}\n\n{}", cycle, e));
+            }
+        }
+        self.pause = true;
+    }
+
+    /// set speed, also of a running simulation
+    pub fn set_speed(&mut self, speed: Speed) {
+        self.speed = speed;
+        if let Some(thread) = &self.simulator_thread {
+            thread.send(Command::SetSpeed(speed));
+        }
+    }
+
     fn should_area_update(&mut self, _ctx: &Context) -> bool {
         if self.ui_change {
             self.ui_change = false;
@@ -129,12 +208,13 @@ impl Gui {
     fn draw_area(&mut self, ctx: &Context, frame: egui::Frame) {
         let central_panel = CentralPanel::default().frame(frame).show(ctx, |ui| {
             let sim = self.simulator.as_mut().unwrap();
+            let critical = self.critical_path.then(|| sim.critical_path().clone());
             ui.set_clip_rect(self.clip_rect);
             // Don't draw over the rest of the ui
-            for c in &sim.ordered_components.clone() {
+            for c in &sim.components.clone() {
                 let old_key = c.as_ref().get_id_ports().0;
                 let mut context = self.contexts.remove(&old_key).unwrap();
-                c.render(
+                let responses = c.render(
                     ui,
                     &mut context,
                     Some(sim),
@@ -143,6 +223,23 @@ impl Gui {
                     self.clip_rect,
                     EditorMode::Simulator,
                 );
+                // outline components on the critical path, or holding part of it
+                let prefix = format!("{}{}", old_key, HIERARCHY_SEPARATOR);
+                let on_path = critical.as_ref().is_some_and(|critical| {
+                    critical
+                        .path
+                        .iter()
+                        .any(|id| *id == old_key || id.starts_with(&prefix))
+                });
+                if let (true, Some(responses)) = (on_path, responses) {
+                    for r in responses {
+                        ui.painter().add(Shape::rect_stroke(
+                            r.rect.expand(2.0 * self.scale),
+                            Rounding::ZERO,
+                            Stroke::new(2.0 * self.scale, Color32::RED),
+                        ));
+                    }
+                }
                 self.contexts.insert(context.id_tmp.clone(), context);
             }
         });
@@ -161,6 +258,158 @@ impl Gui {
         }
     }
 
+    fn error_window(&mut self, ctx: &Context) {
+        let Some(error) = &self.error else {
+            return;
+        };
+        let mut close = false;
+        Window::new("Error")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(RichText::new(error).monospace());
+                ui.separator();
+                close = ui.button("OK").clicked();
+            });
+        if close {
+            self.error = None;
+        }
+    }
+
+    fn watchpoints_window(&mut self, ctx: &Context) {
+        let simulator = self.simulator.as_mut().unwrap();
+        let thread = &self.simulator_thread;
+        let state = &mut self.watchpoints;
+        Window::new("Watchpoints")
+            .open(&mut state.open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let mut remove = None;
+                for (i, watchpoint) in simulator.watchpoints().iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.button("🗑").clicked() {
+                            remove = Some(i);
+                        }
+                        let text = RichText::new(watchpoint.to_string());
+                        match simulator.watchpoint_hits().contains(&i) {
+                            true => ui.label(text.color(Color32::RED)),
+                            false => ui.label(text),
+                        };
+                    });
+                }
+                if let Some(i) = remove {
+                    simulator.remove_watchpoint(i);
+                    if let Some(thread) = thread {
+                        thread.send(Command::RemoveWatchpoint(i));
+                    }
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let edit = ui.add(
+                        TextEdit::singleline(&mut state.text)
+                            .hint_text("id/field == value, id/field changes, cycle >= n"),
+                    );
+                    let enter = edit.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+                    if ui.button("Add").clicked() || enter {
+                        match state.text.parse().and_then(|watchpoint: Watchpoint| {
+                            simulator.add_watchpoint(watchpoint.clone())?;
+                            if let Some(thread) = thread {
+                                thread.send(Command::AddWatchpoint(watchpoint));
+                            }
+                            Ok(())
+                        }) {
+                            Ok(()) => {
+                                state.text.clear();
+                                state.error = None;
+                            }
+                            Err(e) => state.error = Some(e),
+                        }
+                    }
+                });
+                if let Some(error) = &state.error {
+                    ui.colored_label(Color32::RED, error);
+                }
+            });
+    }
+
+    fn profile_window(&mut self, ctx: &Context) {
+        let simulator = self.simulator.as_mut().unwrap();
+        let thread = &self.simulator_thread;
+        let state = &mut self.profile;
+        let error = &mut self.error;
+        Window::new("Profile")
+            .open(&mut state.open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let mut enabled = simulator.profile().is_some();
+                    if ui.checkbox(&mut enabled, "Collect").changed() {
+                        simulator.set_profiling(enabled);
+                        if let Some(thread) = thread {
+                            thread.send(Command::SetProfiling(enabled));
+                        }
+                    }
+                    if ui.button("Export CSV").clicked() {
+                        let files = FileDialog::new().add_filter("csv", &["csv"]).save_file();
+                        if let Some(path_buf) = files {
+                            if let Err(e) = simulator.save_profile_csv(&path_buf) {
+                                *error = Some(format!("Couldn't export profile\n\n{}", e));
+                            }
+                        }
+                    }
+                    if let Some(profile) = simulator.profile() {
+                        ui.label(format!("{} cycles", profile.cycles));
+                    }
+                });
+                let Some(profile) = simulator.profile() else {
+                    return;
+                };
+                ui.separator();
+                ScrollArea::vertical().show(ui, |ui| {
+                    Grid::new("profile").striped(true).show(ui, |ui| {
+                        // click a column header to sort by it, again to reverse
+                        for column in Column::ALL {
+                            let text = match (column == state.sort, state.descending) {
+                                (true, true) => format!("{} ⏷", column),
+                                (true, false) => format!("{} ⏶", column),
+                                (false, _) => column.to_string(),
+                            };
+                            if ui.selectable_label(column == state.sort, text).clicked() {
+                                state.descending = match column == state.sort {
+                                    true => !state.descending,
+                                    false => column != Column::Id,
+                                };
+                                state.sort = column;
+                            }
+                        }
+                        ui.end_row();
+                        for stats in profile.sorted(state.sort, state.descending) {
+                            ui.label(&stats.id);
+                            ui.label(stats.evaluations.to_string());
+                            ui.label(format!("{:.1}", stats.time.as_secs_f64() * 1e6));
+                            ui.label(format!(
+                                "{:.0}",
+                                stats.time_per_evaluation().as_secs_f64() * 1e9
+                            ));
+                            ui.label(stats.changes.to_string());
+                            ui.label(stats.toggles.to_string());
+                            ui.label(format!(
+                                "{} ({:.1}%)",
+                                stats.reads,
+                                profile.read_fraction(stats) * 100.0
+                            ));
+                            ui.label(format!(
+                                "{} ({:.1}%)",
+                                stats.writes,
+                                profile.write_fraction(stats) * 100.0
+                            ));
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+    }
+
     fn top_bar(&mut self, ctx: &Context) {
         TopBottomPanel::top("topBar").show(ctx, |ui| Menu::new(ui, self));
     }
@@ -4,6 +4,7 @@ use crate::gui_egui::editor_wire_mode::reset_wire_mode;
 use crate::gui_egui::gui::create_contexts;
 use crate::gui_egui::library::reset_input_mode;
 use crate::gui_egui::Gui;
+use crate::simulator_thread::Command;
 use egui::{Key, KeyboardShortcut, Modifiers};
 use rfd::FileDialog;
 use std::path::PathBuf;
@@ -247,10 +248,15 @@ impl Shortcuts {
 pub fn file_new_fn(_gui: &mut Gui) {}
 pub fn file_open_fn(gui: &mut Gui) {
     let files = FileDialog::new().add_filter("json", &["json"]).pick_file();
-    if let Some(path_buf) = files {
-        gui.path = path_buf;
-    }
-    let cs = ComponentStore::load_file(&gui.path);
+    let path = files.unwrap_or_else(|| gui.path.clone());
+    let cs = match ComponentStore::load_file(&path) {
+        Ok(cs) => cs,
+        Err(e) => {
+            gui.error = Some(format!("Couldn't open model\n\n{}", e));
+            return;
+        }
+    };
+    gui.path = path;
     let contexts = create_contexts(&cs.store);
     match gui.editor_use {
         true => {
@@ -259,38 +265,48 @@ pub fn file_open_fn(gui: &mut Gui) {
                 reset_wire_mode(&mut e.wm);
                 reset_input_mode(&mut e.im);
                 e.components = cs.store;
+                e.delays = cs.delays;
                 e.contexts = contexts;
             }
         }
         false => {
+            gui.join_simulator_thread();
             let simulator = Simulator::new(cs);
             gui.contexts = contexts;
             match simulator {
                 Err(e) => {
-                    println!("couldn't open file with simulator: {}", e);
+                    gui.error = Some(format!("Couldn't open model\n\n{}", e));
                 }
                 Ok(s) => {
                     let _ = gui.simulator.take();
                     gui.simulator = Some(s);
+                    gui.timeline_end = 0;
                 }
             }
         }
     }
 }
 pub fn file_save_fn(gui: &mut Gui) {
-    match gui.editor_use {
-        true => {
-            if let Some(e) = gui.editor.as_mut() {
-                ComponentStore {
-                    store: e.components.clone(),
-                }
-                .save_file(&gui.path)
+    let result = match gui.editor_use {
+        true => match gui.editor.as_mut() {
+            Some(e) => ComponentStore {
+                store: e.components.clone(),
+                delays: e.delays.clone(),
             }
+            .save_file(&gui.path),
+            None => Ok(()),
+        },
+        false => {
+            let simulator = gui.simulator.as_ref().unwrap();
+            ComponentStore {
+                store: simulator.components.clone(),
+                delays: simulator.delays.clone(),
+            }
+            .save_file(&PathBuf::from("file.json"))
         }
-        false => ComponentStore {
-            store: gui.simulator.clone().unwrap().ordered_components,
-        }
-        .save_file(&PathBuf::from("file.json")),
+    };
+    if let Err(e) = result {
+        gui.error = Some(format!("Couldn't save model\n\n{}", e));
     }
 }
 pub fn file_save_as_fn(gui: &mut Gui) {
@@ -300,6 +316,49 @@ pub fn file_save_as_fn(gui: &mut Gui) {
         file_save_fn(gui);
     }
 }
+pub fn file_export_vcd_fn(gui: &mut Gui) {
+    // the history is kept by the simulation thread
+    gui.join_simulator_thread();
+    let Some(simulator) = gui.simulator.as_ref() else {
+        return;
+    };
+    let files = FileDialog::new().add_filter("vcd", &["vcd"]).save_file();
+    if let Some(path_buf) = files {
+        if let Err(e) = simulator.save_vcd(&path_buf) {
+            gui.error = Some(format!("Couldn't export VCD\n\n{}", e));
+        }
+    }
+}
+pub fn file_save_state_fn(gui: &mut Gui) {
+    // the signal state and history are kept by the simulation thread
+    gui.join_simulator_thread();
+    let Some(simulator) = gui.simulator.as_ref() else {
+        return;
+    };
+    let files = FileDialog::new().add_filter("json", &["json"]).save_file();
+    if let Some(path_buf) = files {
+        if let Err(e) = simulator.save_state_file(&path_buf) {
+            gui.error = Some(format!("Couldn't save state\n\n{}", e));
+        }
+    }
+}
+pub fn file_load_state_fn(gui: &mut Gui) {
+    if gui.editor_use {
+        return;
+    }
+    let files = FileDialog::new().add_filter("json", &["json"]).pick_file();
+    if let Some(path_buf) = files {
+        gui.join_simulator_thread();
+        match Simulator::load_state_file(&path_buf) {
+            Err(e) => gui.error = Some(format!("Couldn't load state\n\n{}", e)),
+            Ok(s) => {
+                gui.contexts = create_contexts(&s.components);
+                gui.simulator = Some(s);
+                gui.timeline_end = 0;
+            }
+        }
+    }
+}
 pub fn file_editor_toggle_fn(gui: &mut Gui) {
     // Auto-save
     file_save_fn(gui);
@@ -309,7 +368,10 @@ pub fn file_editor_toggle_fn(gui: &mut Gui) {
             if let Some(e) = gui.editor.as_mut() {
                 let components = e.components.clone();
                 gui.contexts = create_contexts(&components);
-                let simulator = Simulator::new(ComponentStore { store: components });
+                let simulator = Simulator::new(ComponentStore {
+                    store: components,
+                    delays: e.delays.clone(),
+                });
                 match simulator {
                     Err(e) => {
                         gui.editor_use = true;
@@ -321,13 +383,17 @@ pub fn file_editor_toggle_fn(gui: &mut Gui) {
         }
         false => {
             let editor_existed: bool = gui.editor.as_mut().is_some();
+            gui.join_simulator_thread();
 
             let simulator = gui.simulator.take().unwrap();
-            let components = simulator.ordered_components;
+            let components = simulator.components;
 
             if !editor_existed {
                 gui.editor = Some(Editor::gui(components, &gui.path, &gui.library));
             }
+            if let Some(e) = gui.editor.as_mut() {
+                e.delays = simulator.delays;
+            }
 
             gui.editor_use = true;
         }
@@ -386,9 +452,12 @@ pub fn control_play_toggle_fn(gui: &mut Gui) {
 pub fn control_play_fn(gui: &mut Gui) {
     if !gui.editor_use {
         gui.pause = false;
-        gui.simulator.as_mut().unwrap().running = true;
-        //gui.simulator.as_mut().unwrap().run();
-        //gui.pause = true;
+        let simulator = gui.simulator.as_mut().unwrap();
+        simulator.running = true;
+        match &gui.simulator_thread {
+            Some(thread) => thread.send(Command::Play),
+            None => gui.simulator_thread = Some(simulator.clone().run_threaded(gui.speed)),
+        }
     }
 }
 //pub fn step(gui: &mut Gui) {
@@ -400,21 +469,30 @@ pub fn control_pause_fn(gui: &mut Gui) {
     if !gui.editor_use {
         gui.pause = true;
         gui.simulator.as_mut().unwrap().running = false;
+        if let Some(thread) = &gui.simulator_thread {
+            thread.send(Command::Pause);
+        }
     }
 }
 pub fn control_reset_fn(gui: &mut Gui) {
     if !gui.editor_use {
+        gui.join_simulator_thread();
         gui.simulator.as_mut().unwrap().reset();
+        gui.timeline_end = 0;
         gui.pause = true;
     }
 }
 pub fn control_step_forward_fn(gui: &mut Gui) {
     if !gui.editor_use {
-        gui.simulator.as_mut().unwrap().clock();
+        match &gui.simulator_thread {
+            Some(thread) => thread.send(Command::Step),
+            None => gui.simulator.as_mut().unwrap().clock(),
+        }
     }
 }
 pub fn control_step_back_fn(gui: &mut Gui) {
     if !gui.editor_use {
+        gui.join_simulator_thread();
         gui.simulator.as_mut().unwrap().un_clock();
     }
 }
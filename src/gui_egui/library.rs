@@ -1,17 +1,19 @@
-use crate::common::{ComponentStore, EguiComponent};
+use crate::common::{Component, ComponentStore, EguiComponent};
 use crate::gui_egui::gui::EguiExtra;
 use crate::gui_egui::{
     editor::{Editor, EditorMode},
     editor_wire_mode::get_grid_snap,
     helper::{id_ports_of_all_components, offset_reverse_helper_pos2, unique_component_name},
 };
+use crate::registry::component_types;
 use egui::{
-    Context, CursorIcon, LayerId, PointerButton, Pos2, Rect, Response, Ui, UiStackInfo, Vec2,
+    Align2, Color32, Context, CursorIcon, FontId, LayerId, PointerButton, Pos2, Rect, Response, Ui,
+    UiStackInfo, Vec2,
 };
-use std::{collections::HashMap, path::PathBuf, rc::Rc};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 pub struct InputMode {
-    pub comp: Option<Rc<dyn EguiComponent>>,
+    pub comp: Option<Arc<dyn EguiComponent>>,
     pub cursor_location: Pos2,
     pub library_contexts: HashMap<crate::common::Id, EguiExtra>,
 }
@@ -97,7 +99,23 @@ pub fn show_library(e: &mut Editor, ui: &mut Ui) {
             y: f32::INFINITY,
         },
     };
+    // library items grouped by category, headed by its name
+    let component_types = component_types();
+    let mut category = "";
     for c in e.library.iter() {
+        let name = Component::typetag_name(c.as_ref());
+        let component_type = component_types.iter().find(|t| t.name == name);
+        if let Some(t) = component_type.filter(|t| t.category != category) {
+            category = t.category;
+            ui.painter().text(
+                Pos2::new(clip_rect.min.x + 10f32, padding.y),
+                Align2::LEFT_TOP,
+                category,
+                FontId::proportional(14f32),
+                Color32::DARK_GRAY,
+            );
+            padding.y += 20f32;
+        }
         let size = c.top_padding();
         padding.y += size;
         let r_vec = c
@@ -118,6 +136,10 @@ pub fn show_library(e: &mut Editor, ui: &mut Ui) {
             .unwrap();
         let rect = r_vec[0].rect;
         for resp in r_vec {
+            let resp = match component_type {
+                Some(t) => resp.on_hover_text(t.description),
+                None => resp,
+            };
             // Create new component
             if resp.drag_started_by(PointerButton::Primary) {
                 e.editor_mode = EditorMode::Input;
@@ -145,7 +167,13 @@ pub fn add_comp_to_editor(e: &mut Editor) {
     let id_ports = id_ports_of_all_components(&e.components);
     let cloned = e.im.comp.clone().unwrap();
     let id = unique_component_name(&id_ports, cloned.get_id_ports().0.as_str());
-    let instance = cloned.dummy(&id, (pos.x, pos.y));
+    let Some(instance) = cloned.dummy(&id, (pos.x, pos.y)) else {
+        log::error!(
+            "No factory for component type {}",
+            Component::typetag_name(cloned.as_ref())
+        );
+        return;
+    };
     e.contexts.insert(
         id.clone(),
         EguiExtra {
@@ -157,8 +185,11 @@ pub fn add_comp_to_editor(e: &mut Editor) {
     );
     e.components.push(*instance);
     let path = PathBuf::from("autosave.json");
-    ComponentStore {
+    let cs = ComponentStore {
         store: e.components.clone(),
+        delays: e.delays.clone(),
+    };
+    if let Err(e) = cs.save_file(&path) {
+        log::warn!("couldn't autosave: {}", e);
     }
-    .save_file(&path);
 }
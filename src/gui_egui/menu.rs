@@ -3,7 +3,8 @@ use crate::gui_egui::{
     gui::Gui,
     keymap,
 };
-use egui::{menu, Button, DragValue, KeyboardShortcut, Response, Ui};
+use crate::speed::{format_hz, Speed};
+use egui::{menu, Button, ComboBox, DragValue, KeyboardShortcut, Response, Slider, Ui};
 
 pub(crate) struct Menu {}
 
@@ -15,8 +16,18 @@ impl Menu {
             shared_buttons_edit(gui, ui);
 
             let mut scale = gui.scale;
-            shared_buttons_view(gui, ui, &mut scale, |_| {});
+            let mut watchpoints_open = gui.watchpoints.open;
+            let mut profile_open = gui.profile.open;
+            let mut critical_path = gui.critical_path;
+            shared_buttons_view(gui, ui, &mut scale, |ui| {
+                ui.checkbox(&mut watchpoints_open, "Watchpoints");
+                ui.checkbox(&mut profile_open, "Profile");
+                ui.checkbox(&mut critical_path, "Critical Path");
+            });
             gui.scale = scale;
+            gui.watchpoints.open = watchpoints_open;
+            gui.profile.open = profile_open;
+            gui.critical_path = critical_path;
 
             shared_buttons_help(gui, ui);
         });
@@ -36,10 +47,43 @@ impl Menu {
             if ui.button("⏸").clicked() {
                 keymap::control_pause_fn(gui);
             }
+            let mut speed = gui.speed;
+            ComboBox::from_id_source("speed")
+                .selected_text(speed.to_string())
+                .show_ui(ui, |ui| {
+                    for preset in Speed::PRESETS {
+                        ui.selectable_value(&mut speed, preset, preset.to_string());
+                    }
+                });
+            if speed != gui.speed {
+                gui.set_speed(speed);
+            }
             if let Some(s) = gui.simulator.as_ref() {
                 ui.label(format!("Cycle #{}", s.cycle));
+                if s.running {
+                    ui.label(format_hz(gui.achieved_hz));
+                }
+                if gui.critical_path {
+                    let critical = s.critical_path();
+                    ui.label(format!(
+                        "Critical path {} ({} levels)",
+                        critical.delay, critical.depth
+                    ));
+                }
             }
         });
+        // timeline, scrubbing through the cycles reached
+        if let Some(current) = gui.simulator.as_ref().map(|s| s.cycle) {
+            gui.timeline_end = gui.timeline_end.max(current);
+            let mut cycle = current;
+            ui.horizontal(|ui| {
+                ui.spacing_mut().slider_width = (ui.available_width() - 80.0).max(100.0);
+                ui.add(Slider::new(&mut cycle, 1..=gui.timeline_end.max(1)).text("cycle"));
+            });
+            if cycle != current {
+                gui.goto_cycle(cycle);
+            }
+        }
     }
 
     #[allow(clippy::new_ret_no_self)]
@@ -129,6 +173,15 @@ fn shared_buttons_file(gui: &mut Gui, ui: &mut Ui) {
         if btn(ui, "Save As", gui.shortcuts.file_save_as).clicked() {
             keymap::file_save_as_fn(gui);
         }
+        if ui.button("Export VCD").clicked() {
+            keymap::file_export_vcd_fn(gui);
+        }
+        if ui.button("Save State").clicked() {
+            keymap::file_save_state_fn(gui);
+        }
+        if ui.button("Load State").clicked() {
+            keymap::file_load_state_fn(gui);
+        }
         ui.separator();
         if btn(ui, "Editor", gui.shortcuts.file_editor_toggle).clicked() {
             keymap::file_editor_toggle_fn(gui);
@@ -34,7 +34,7 @@ impl ViziaComponent for Mem {
         //so as to not trigger unnecessary redraws.
         let data_slice = {
             let mut data_slice = vec![];
-            let mem = self.memory.clone();
+            let mem = self.memory.0.read().unwrap();
             trace!("range {:x?}", self.range);
             for idx in (self.range.start as usize..self.range.end as usize).step_by(4) {
                 trace!("idx {:x?}", idx);
@@ -42,10 +42,10 @@ impl ViziaComponent for Mem {
                 data_slice.push(format!(
                     "0x{:08x}:    {:02x}{:02x}{:02x}{:02x}",
                     self.range.start as usize + idx * 4,
-                    mem.0.borrow().get(&idx).copied().unwrap_or(0u8),
-                    mem.0.borrow().get(&(idx + 1)).copied().unwrap_or(0u8),
-                    mem.0.borrow().get(&(idx + 2)).copied().unwrap_or(0u8),
-                    mem.0.borrow().get(&(idx + 3)).copied().unwrap_or(0u8),
+                    mem.get(&idx).copied().unwrap_or(0u8),
+                    mem.get(&(idx + 1)).copied().unwrap_or(0u8),
+                    mem.get(&(idx + 2)).copied().unwrap_or(0u8),
+                    mem.get(&(idx + 3)).copied().unwrap_or(0u8),
                 ));
             }
             data_slice
@@ -106,32 +106,19 @@ impl View for DataMemView {
         event.map(|event, _| match event {
             DataEvent::UpdateView(range) => {
                 for idx in range.clone() {
+                    let data = self.data.0.read().unwrap();
                     if let Some(data_fmt) = self.data_slice.get_mut(idx) {
                         *data_fmt = format!(
                             "0x{:08x}:    0x{:02x}{:02x}{:02x}{:02x}",
                             idx * 4 + self.start,
-                            self.data
-                                .0
-                                .borrow()
-                                .get(&(self.start + idx * 4))
+                            data.get(&(self.start + idx * 4)).copied().unwrap_or(0u8),
+                            data.get(&(self.start + idx * 4 + 1))
                                 .copied()
                                 .unwrap_or(0u8),
-                            self.data
-                                .0
-                                .borrow()
-                                .get(&(self.start + idx * 4 + 1))
+                            data.get(&(self.start + idx * 4 + 2))
                                 .copied()
                                 .unwrap_or(0u8),
-                            self.data
-                                .0
-                                .borrow()
-                                .get(&(self.start + idx * 4 + 2))
-                                .copied()
-                                .unwrap_or(0u8),
-                            self.data
-                                .0
-                                .borrow()
-                                .get(&(self.start + idx * 4 + 3))
+                            data.get(&(self.start + idx * 4 + 3))
                                 .copied()
                                 .unwrap_or(0u8),
                         );
@@ -8,6 +8,8 @@ mod probe_assert;
 mod probe_edit;
 mod probe_out;
 mod probe_stim;
+mod probe_stim_file;
 mod register;
 mod sext;
+mod sub_circuit;
 mod wire;
@@ -0,0 +1,44 @@
+use crate::{
+    common::{Input, Simulator},
+    components::ProbeStimFile,
+    gui_vizia::{GuiData, ViziaComponent, V},
+};
+use log::*;
+use vizia::prelude::*;
+
+#[typetag::serde]
+impl ViziaComponent for ProbeStimFile {
+    // create view
+    fn view<'a>(&self, cx: &'a mut Context) -> Handle<'a, V> {
+        V::new(cx, self, |cx| {
+            trace!("---- Create ProbeStimFile View");
+            let inputs: Vec<Input> = self
+                .channels
+                .iter()
+                .map(|channel| Input::new(&self.id, channel))
+                .collect();
+            VStack::new(cx, |cx| {
+                Binding::new(
+                    cx,
+                    crate::gui_vizia::GuiData::simulator.then(Simulator::cycle),
+                    move |cx, _| {
+                        let simulator = GuiData::simulator.view(cx.data().unwrap()).unwrap();
+                        let text: Vec<String> = inputs
+                            .iter()
+                            .map(|input| {
+                                format!("{}: {}", input.field, simulator.get_input_signal(input))
+                            })
+                            .collect();
+                        Label::new(cx, &text.join("\n")).hoverable(false);
+                    },
+                );
+            })
+            .size(Auto)
+        })
+        .top(Pixels(self.pos.1 - 10.0))
+        .left(Pixels(self.pos.0 - 10.0))
+        .width(Auto)
+        .height(Auto)
+        .background_color(Color::lightblue())
+    }
+}
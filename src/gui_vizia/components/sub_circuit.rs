@@ -0,0 +1,17 @@
+use crate::{
+    components::SubCircuit,
+    gui_vizia::{ViziaComponent, V},
+};
+use vizia::prelude::*;
+
+#[typetag::serde]
+impl ViziaComponent for SubCircuit {
+    // create view
+    fn view<'a>(&self, cx: &'a mut Context) -> Handle<'a, V> {
+        V::new(cx, self, |cx| Label::new(cx, &self.id))
+            .left(Pixels(self.pos.0 - 30.0))
+            .top(Pixels(self.pos.1 - 10.0))
+            .width(Pixels(60.0))
+            .background_color(Color::lightblue())
+    }
+}
@@ -1,10 +1,12 @@
 use crate::{
     common::{ComponentStore, Simulator},
     gui_vizia::{grid::Grid, keymap::init_keymap, menu::Menu, transport::Transport},
+    speed::{Pacer, Speed, FRAME},
 };
 use rfd::FileDialog;
 use std::collections::HashSet;
 use std::path::PathBuf;
+use std::time::Instant;
 use vizia::{icons, prelude::*};
 
 use log::*;
@@ -18,18 +20,27 @@ pub struct GuiData {
     pub selected_id: usize,
     pub visible: HashSet<usize>,
     pub expanded: HashSet<usize>,
+    // index of the play speed in `Speed::PRESETS`
+    pub speed: usize,
+    pub pacer: Pacer,
+    pub achieved_hz: f64,
 }
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum GuiEvent {
     Open,
     ReOpen,
+    SaveState,
+    LoadState,
     Clock,
     Reset,
     UnClock,
     Play,
     Pause,
     PlayToggle,
+    // clock the cycles due when playing
+    Run,
+    SpeedNext,
     Preferences,
     ShowAbout,
     HideAbout,
@@ -40,7 +51,7 @@ pub enum GuiEvent {
 }
 
 impl Model for GuiData {
-    fn event(&mut self, _cx: &mut EventContext, event: &mut Event) {
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
         #[allow(clippy::single_match)]
         event.map(|window_event, meta| match window_event {
             // Intercept WindowClose event to show a dialog if not 'saved'.
@@ -64,24 +75,48 @@ impl Model for GuiData {
                 }
             }
             GuiEvent::ReOpen => self.open(),
+            GuiEvent::SaveState => {
+                let files = FileDialog::new().add_filter("json", &["json"]).save_file();
+                if let Some(path_buf) = files {
+                    if let Err(e) = self.simulator.save_state_file(&path_buf) {
+                        error!("Couldn't save state: {}", e);
+                    }
+                }
+            }
+            GuiEvent::LoadState => {
+                let files = FileDialog::new().add_filter("json", &["json"]).pick_file();
+                if let Some(path_buf) = files {
+                    match Simulator::load_state_file(&path_buf) {
+                        Ok(s) => self.simulator = s,
+                        Err(e) => error!("Couldn't load state: {}", e),
+                    }
+                }
+            }
             GuiEvent::Clock => self.simulator.clock(),
             GuiEvent::UnClock => self.simulator.un_clock(),
             GuiEvent::Reset => {
                 self.simulator.reset();
             }
             GuiEvent::Play => {
-                self.simulator.running = true;
-                self.simulator.clock();
+                self.play(cx);
             }
             GuiEvent::Pause => {
                 self.simulator.running = false;
+                self.achieved_hz = 0.0;
             }
             GuiEvent::PlayToggle => {
-                self.simulator.running = !self.simulator.running;
                 if self.simulator.running {
-                    self.simulator.clock();
+                    self.simulator.running = false;
+                    self.achieved_hz = 0.0;
+                } else {
+                    self.play(cx);
                 }
             }
+            GuiEvent::Run => self.run(cx),
+            GuiEvent::SpeedNext => {
+                self.speed = (self.speed + 1) % Speed::PRESETS.len();
+                self.pacer = Pacer::new(Speed::PRESETS[self.speed]);
+            }
             GuiEvent::Preferences => trace!("Preferences"),
             GuiEvent::ShowAbout => self.show_about = true,
             GuiEvent::HideAbout => self.show_about = false,
@@ -108,10 +143,50 @@ impl Model for GuiData {
 }
 
 impl GuiData {
+    fn play(&mut self, cx: &mut EventContext) {
+        self.simulator.running = true;
+        self.pacer = Pacer::new(Speed::PRESETS[self.speed]);
+        self.run(cx);
+    }
+
+    // clock the cycles due (for at most a frame), a cycle change triggers the next run
+    fn run(&mut self, cx: &mut EventContext) {
+        if !self.simulator.running {
+            return;
+        }
+        let start = Instant::now();
+        let cycle = self.simulator.cycle;
+        while self.simulator.running && start.elapsed() < FRAME {
+            let now = Instant::now();
+            if self.pacer.due(now, 1) == 0 {
+                break;
+            }
+            self.simulator.clock();
+            self.pacer.tick(now);
+        }
+        self.achieved_hz = self.pacer.hz();
+        if self.simulator.running && self.simulator.cycle == cycle {
+            // nothing due yet, run again when due (at least once a frame)
+            let now = Instant::now();
+            match self.pacer.wait(now) {
+                Some(wait) => {
+                    cx.schedule_emit(GuiEvent::Run, now + wait.min(FRAME));
+                }
+                None => cx.emit(GuiEvent::Run),
+            }
+        }
+    }
+
     fn open(&mut self) {
         // Re-Open model
         trace!("open path {:?}", self.path);
-        let cs = ComponentStore::load_file(&self.path);
+        let cs = match ComponentStore::load_file(&self.path) {
+            Ok(cs) => cs,
+            Err(e) => {
+                error!("{}", e);
+                return;
+            }
+        };
         let simulator = Simulator::new(cs);
         match simulator {
             Ok(s) => {
@@ -143,6 +218,9 @@ pub fn gui(cs: ComponentStore, path: &PathBuf) {
             selected_id: 0,
             visible: HashSet::new(),
             expanded: HashSet::new(),
+            speed: Speed::PRESETS.len() - 1,
+            pacer: Pacer::new(Speed::default()),
+            achieved_hz: 0.0,
         }
         .build(cx);
 
@@ -155,8 +233,8 @@ pub fn gui(cs: ComponentStore, path: &PathBuf) {
             //let simulator = GuiData::simulator.get(cx);
             let simulator = GuiData::simulator.view(cx.data().unwrap()).unwrap();
             if simulator.running {
-                trace!("send clock event");
-                cx.emit(GuiEvent::Clock);
+                trace!("send run event");
+                cx.emit(GuiEvent::Run);
             };
         });
 
@@ -186,7 +264,7 @@ pub fn gui(cs: ComponentStore, path: &PathBuf) {
                     // Left panel
                     Binding::new(
                         cx,
-                        GuiData::simulator.then(Simulator::ordered_components),
+                        GuiData::simulator.then(Simulator::components),
                         |cx, wrapper_oc| {
                             VStack::new(cx, |cx| {
                                 Label::new(cx, "Left").top(Pixels(0.0));
@@ -276,7 +354,7 @@ pub fn gui(cs: ComponentStore, path: &PathBuf) {
                         // (re-)bind all components when simulator changed
                         Binding::new(
                             cx,
-                            GuiData::simulator.then(Simulator::ordered_components),
+                            GuiData::simulator.then(Simulator::components),
                             |cx, wrapper_oc| {
                                 VStack::new(cx, |cx| {
                                     let oc = wrapper_oc.get(cx);
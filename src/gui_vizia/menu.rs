@@ -99,6 +99,16 @@ impl Menu {
                                 |_| trace!("Save As"),
                                 |cx| Label::new(cx, "Save As"),
                             );
+                            MenuButton::new(
+                                cx,
+                                |cx| cx.emit(GuiEvent::SaveState),
+                                |cx| Label::new(cx, "Save State"),
+                            );
+                            MenuButton::new(
+                                cx,
+                                |cx| cx.emit(GuiEvent::LoadState),
+                                |cx| Label::new(cx, "Load State"),
+                            );
                             MenuDivider::new(cx);
                             MenuButton::new(
                                 cx,
@@ -1,5 +1,6 @@
 use crate::common::Simulator;
 use crate::gui_vizia::{GuiData, GuiEvent};
+use crate::speed::{format_hz, Speed};
 use vizia::{icons, prelude::*};
 pub(crate) struct Transport {}
 
@@ -128,6 +129,31 @@ impl Transport {
                         .size(Auto);
                     })
                 });
+
+                // Speed (cycles through the presets)
+                Button::new(cx, |cx| {
+                    Label::new(
+                        cx,
+                        GuiData::speed.map(|speed| Speed::PRESETS[*speed].to_string()),
+                    )
+                    .on_press(|cx| cx.emit(GuiEvent::SpeedNext))
+                })
+                .tooltip(|cx| {
+                    Tooltip::new(cx, |cx| {
+                        Label::new(cx, "Speed");
+                    })
+                });
+
+                // Achieved clock rate
+                Label::new(
+                    cx,
+                    GuiData::achieved_hz.map(|hz| match *hz > 0.0 {
+                        true => format_hz(*hz),
+                        false => String::new(),
+                    }),
+                )
+                .top(Stretch(1.0))
+                .bottom(Stretch(1.0));
             })
             .col_between(Pixels(5.0))
             .size(Auto);
@@ -0,0 +1,230 @@
+// Headless (batch) simulation, without any GUI attached
+//
+// Used by the `syncrim` binary (`--headless`), but equally well by target
+// specific binaries (e.g. `mips`, `riscv`) that link their own components.
+use crate::common::{ComponentStore, Condition, Id, Input, Simulator};
+use crate::dot::DotOptions;
+use log::*;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Default)]
+pub struct Headless {
+    /// Maximum number of cycles to run (unbounded if `None`)
+    pub cycles: Option<usize>,
+    /// Stop on the first `Condition::Halt`
+    pub until_halt: bool,
+    /// Signals to print
+    pub signals: Vec<Input>,
+    /// Print signals each cycle, not only at the end of the run
+    pub trace: bool,
+    /// Number of cycles kept in history (unbounded if `None`)
+    pub history_depth: Option<usize>,
+    /// Export history as VCD at the end of the run
+    pub vcd: Option<PathBuf>,
+    /// Profile the run, exporting component statistics as CSV at the end
+    pub profile: Option<PathBuf>,
+    /// Evaluate incrementally, skipping pure components with unchanged inputs
+    pub incremental: bool,
+    /// Export the model graph as DOT, annotated with the signal values at the end of the run
+    pub dot: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeadlessResult {
+    /// The requested number of cycles was run
+    Done,
+    /// A component raised `Condition::Halt`
+    Halt(Id, String),
+    /// A component raised `Condition::Assert`
+    Assert(Id, String),
+    /// A component raised `Condition::Error`
+    Error(Id, String),
+}
+
+impl HeadlessResult {
+    /// process exit code, non-zero on assertions and errors
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            HeadlessResult::Done | HeadlessResult::Halt(..) => 0,
+            HeadlessResult::Assert(..) => 1,
+            HeadlessResult::Error(..) => 2,
+        }
+    }
+}
+
+impl Headless {
+    /// build simulator from component store and run it
+    pub fn run_store(&self, cs: ComponentStore) -> Result<HeadlessResult, String> {
+        let mut simulator = Simulator::new(cs).map_err(|e| e.to_string())?;
+        simulator.set_history_depth(self.history_depth);
+        self.check_signals(&simulator)?;
+        simulator.set_profiling(self.profile.is_some());
+        simulator.set_incremental(self.incremental);
+        let result = self.run(&mut simulator);
+        if let Some(path) = &self.vcd {
+            simulator
+                .save_vcd(path)
+                .map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+        }
+        if let Some(path) = &self.profile {
+            simulator
+                .save_profile_csv(path)
+                .map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+        }
+        if let Some(path) = &self.dot {
+            let options = DotOptions {
+                values: true,
+                ..Default::default()
+            };
+            simulator
+                .save_dot_with(path, &options)
+                .map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+        }
+        Ok(result)
+    }
+
+    /// check that all signals to print exist in the model
+    pub fn check_signals(&self, simulator: &Simulator) -> Result<(), String> {
+        for signal in &self.signals {
+            if !simulator
+                .id_field_index
+                .contains_key(&(signal.id.clone(), signal.field.clone()))
+            {
+                return Err(format!("Signal {} not found in model", signal));
+            }
+        }
+        Ok(())
+    }
+
+    /// run simulator, the initial (reset) clock is already performed by `Simulator::new`
+    pub fn run(&self, simulator: &mut Simulator) -> HeadlessResult {
+        if self.cycles.is_none() && !self.until_halt {
+            warn!("neither cycles nor until_halt given, running a single cycle");
+        }
+        let cycles = match (self.cycles, self.until_halt) {
+            (Some(cycles), _) => cycles,
+            (None, true) => usize::MAX,
+            (None, false) => 1,
+        };
+
+        let mut result = HeadlessResult::Done;
+        if self.trace {
+            self.print_signals(simulator);
+        }
+        for _ in 0..cycles {
+            simulator.clock();
+            if self.trace {
+                self.print_signals(simulator);
+            }
+            if let Some(r) = self.check_conditions(simulator) {
+                result = r;
+                break;
+            }
+        }
+        if !self.trace {
+            self.print_signals(simulator);
+        }
+        result
+    }
+
+    // assertions and errors take precedence over halt
+    fn check_conditions(&self, simulator: &Simulator) -> Option<HeadlessResult> {
+        let mut result = None;
+        for (id, condition) in &simulator.component_condition {
+            match condition {
+                Condition::Error(e) => {
+                    eprintln!("cycle {}: {} error: {}", simulator.cycle, id, e);
+                    result = Some(HeadlessResult::Error(id.clone(), e.clone()));
+                }
+                Condition::Assert(a) => {
+                    eprintln!("cycle {}: {} assertion failed: {}", simulator.cycle, id, a);
+                    if !matches!(result, Some(HeadlessResult::Error(..))) {
+                        result = Some(HeadlessResult::Assert(id.clone(), a.clone()));
+                    }
+                }
+                Condition::Halt(h) if self.until_halt => {
+                    eprintln!("cycle {}: {} halt: {}", simulator.cycle, id, h);
+                    if result.is_none() {
+                        result = Some(HeadlessResult::Halt(id.clone(), h.clone()));
+                    }
+                }
+                _ => {}
+            }
+        }
+        result
+    }
+
+    fn print_signals(&self, simulator: &Simulator) {
+        if self.signals.is_empty() {
+            return;
+        }
+        let values: Vec<String> = self
+            .signals
+            .iter()
+            .map(|signal| format!("{}={}", signal, simulator.get_input_signal(signal)))
+            .collect();
+        println!("{} {}", simulator.cycle, values.join(" "));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::components::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_headless_cycles() {
+        let cs = ComponentStore::new(vec![ProbeStim::arc_new(
+            "stim",
+            (0.0, 0.0),
+            vec![0, 1, 2, 3],
+        )]);
+        let headless = Headless {
+            cycles: Some(2),
+            ..Default::default()
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+
+        assert_eq!(headless.run(&mut simulator), HeadlessResult::Done);
+        assert_eq!(simulator.cycle, 3);
+        assert_eq!(
+            simulator.get_input_value(&Input::new("stim", "out")),
+            2.into()
+        );
+    }
+
+    #[test]
+    fn test_headless_error() {
+        let cs = ComponentStore::new(vec![
+            ProbeStim::arc_new("select", (0.0, 0.0), vec![0, 0, 5]),
+            Arc::new(Constant::new("c", (0.0, 0.0), 1)),
+            Mux::arc_new(
+                "mux",
+                (0.0, 0.0),
+                Input::new("select", "out"),
+                vec![Input::new("c", "out"), Input::new("c", "out")],
+            ),
+        ]);
+        let headless = Headless {
+            cycles: Some(10),
+            ..Default::default()
+        };
+
+        let result = headless.run_store(cs).unwrap();
+        assert!(matches!(result, HeadlessResult::Error(ref id, _) if id == "mux"));
+        assert_ne!(result.exit_code(), 0);
+    }
+
+    #[test]
+    fn test_headless_missing_signal() {
+        let cs = ComponentStore::new(vec![Arc::new(Constant::new("c", (0.0, 0.0), 1))]);
+        let headless = Headless {
+            cycles: Some(1),
+            signals: vec![Input::new("c", "missing")],
+            ..Default::default()
+        };
+
+        assert!(headless.run_store(cs).is_err());
+    }
+}
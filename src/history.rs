@@ -0,0 +1,307 @@
+// Simulation history, used to reverse the simulation (`un_clock`)
+//
+// The most recent state is kept in full, older states are stored as
+// backward deltas, i.e., the signals that differ from the next (newer) state.
+// Every `keyframe_interval`-th state is stored in full, bounding the number
+// of deltas to apply when reconstructing an arbitrary state.
+//
+// Since each delta only depends on newer states, the oldest states can be
+// dropped at any time. With a `depth` limit, history acts as a ring buffer
+// keeping the last `depth` states.
+use crate::common::Signal;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// default number of states between keyframes
+pub const KEYFRAME_INTERVAL: usize = 64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum Entry {
+    // full state
+    Keyframe(Vec<Signal>),
+    // (index, signal) pairs differing from the next state
+    Delta(Vec<(usize, Signal)>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct History {
+    // number of states between keyframes
+    keyframe_interval: usize,
+    // maximum number of states kept, unbounded if `None`
+    depth: Option<usize>,
+    // absolute index of the oldest entry
+    first: usize,
+    // older states, oldest first
+    entries: VecDeque<Entry>,
+    // the most recent state
+    top: Option<Vec<Signal>>,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        History::new(KEYFRAME_INTERVAL, None)
+    }
+}
+
+impl History {
+    pub fn new(keyframe_interval: usize, depth: Option<usize>) -> Self {
+        History {
+            keyframe_interval: keyframe_interval.max(1),
+            depth,
+            first: 0,
+            entries: VecDeque::new(),
+            top: None,
+        }
+    }
+
+    /// number of states kept
+    pub fn len(&self) -> usize {
+        self.entries.len() + self.top.is_some() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.top.is_none()
+    }
+
+    /// absolute index of the oldest state kept
+    pub fn first(&self) -> usize {
+        self.first
+    }
+
+    /// total number of states pushed (and not popped) since last `clear`,
+    /// including states dropped due to the depth limit
+    pub fn end(&self) -> usize {
+        self.first + self.len()
+    }
+
+    pub fn depth(&self) -> Option<usize> {
+        self.depth
+    }
+
+    /// set maximum number of states kept, dropping the oldest states if needed
+    pub fn set_depth(&mut self, depth: Option<usize>) {
+        self.depth = depth;
+        self.trim();
+    }
+
+    pub fn keyframe_interval(&self) -> usize {
+        self.keyframe_interval
+    }
+
+    /// remove all states
+    pub fn clear(&mut self) {
+        self.first = 0;
+        self.entries.clear();
+        self.top = None;
+    }
+
+    /// push state
+    pub fn push(&mut self, state: &[Signal]) {
+        match self.top.as_mut() {
+            None => self.top = Some(state.to_vec()),
+            Some(top) => {
+                let index = self.first + self.entries.len();
+                let entry =
+                    if index.is_multiple_of(self.keyframe_interval) || top.len() != state.len() {
+                        Entry::Keyframe(std::mem::replace(top, state.to_vec()))
+                    } else {
+                        let delta = top
+                            .iter()
+                            .zip(state)
+                            .enumerate()
+                            .filter(|(_, (old, new))| old != new)
+                            .map(|(i, (old, _))| (i, *old))
+                            .collect();
+                        top.copy_from_slice(state);
+                        Entry::Delta(delta)
+                    };
+                self.entries.push_back(entry);
+            }
+        }
+        self.trim();
+    }
+
+    /// pop the most recent state
+    pub fn pop(&mut self) -> Option<Vec<Signal>> {
+        let top = self.top.take()?;
+        self.top = match self.entries.pop_back() {
+            // history is exhausted, the next push starts over at this position
+            None => None,
+            Some(Entry::Keyframe(state)) => Some(state),
+            Some(Entry::Delta(delta)) => {
+                let mut state = top.clone();
+                for (i, signal) in delta {
+                    state[i] = signal;
+                }
+                Some(state)
+            }
+        };
+        Some(top)
+    }
+
+    /// drop the states at and after absolute index `end`
+    pub fn truncate(&mut self, end: usize) {
+        if end <= self.first {
+            self.first = end;
+            self.entries.clear();
+            self.top = None;
+        } else if end < self.end() {
+            self.top = self.get(end - 1);
+            self.entries.truncate(end - 1 - self.first);
+        }
+    }
+
+    /// reconstruct the state at absolute `index`, if still kept
+    pub fn get(&self, index: usize) -> Option<Vec<Signal>> {
+        if index < self.first || index >= self.end() {
+            return None;
+        }
+        let offset = index - self.first;
+        let (start, mut state) = self.newer_keyframe(offset);
+        for entry in self.entries.range(offset..start).rev() {
+            if let Entry::Delta(delta) = entry {
+                for (i, signal) in delta {
+                    state[*i] = *signal;
+                }
+            }
+        }
+        Some(state)
+    }
+
+    /// iterate over all states kept, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = Vec<Signal>> + '_ {
+        let mut offset = 0;
+        // states of the current segment, newest first
+        let mut segment: Vec<Vec<Signal>> = vec![];
+        std::iter::from_fn(move || {
+            if segment.is_empty() && offset < self.len() {
+                // reconstruct the states of the segment backward
+                let (start, mut state) = self.newer_keyframe(offset);
+                segment.push(state.clone());
+                for entry in self.entries.range(offset..start).rev() {
+                    if let Entry::Delta(delta) = entry {
+                        for (i, signal) in delta {
+                            state[*i] = *signal;
+                        }
+                    }
+                    segment.push(state.clone());
+                }
+            }
+            offset += 1;
+            segment.pop()
+        })
+    }
+
+    // the closest keyframe (or the top) at or after `offset`, and its offset
+    fn newer_keyframe(&self, offset: usize) -> (usize, Vec<Signal>) {
+        self.entries
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .find_map(|(i, entry)| match entry {
+                Entry::Keyframe(state) => Some((i, state.clone())),
+                Entry::Delta(_) => None,
+            })
+            .unwrap_or_else(|| (self.entries.len(), self.top.clone().unwrap()))
+    }
+
+    // drop the oldest states exceeding the depth limit
+    fn trim(&mut self) {
+        if let Some(depth) = self.depth {
+            while self.len() > depth {
+                if self.entries.pop_front().is_none() {
+                    self.top = None;
+                }
+                self.first += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn state(n: usize) -> Vec<Signal> {
+        // a few signals change each step, the rest stays constant
+        (0..16)
+            .map(|i| {
+                if i < 3 {
+                    (n * (i + 1)) as u32
+                } else {
+                    i as u32
+                }
+                .into()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let mut history = History::new(4, None);
+        for n in 0..10 {
+            history.push(&state(n));
+        }
+        assert_eq!(history.len(), 10);
+        for n in (0..10).rev() {
+            assert_eq!(history.pop(), Some(state(n)));
+        }
+        assert!(history.is_empty());
+        assert_eq!(history.pop(), None);
+    }
+
+    #[test]
+    fn test_get() {
+        let mut history = History::new(4, None);
+        for n in 0..10 {
+            history.push(&state(n));
+        }
+        for n in 0..10 {
+            assert_eq!(history.get(n), Some(state(n)));
+        }
+        assert_eq!(history.get(10), None);
+
+        let states: Vec<_> = history.iter().collect();
+        assert_eq!(states, (0..10).map(state).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut history = History::new(4, None);
+        for n in 0..10 {
+            history.push(&state(n));
+        }
+        history.truncate(6);
+        assert_eq!(history.end(), 6);
+        for n in 0..6 {
+            assert_eq!(history.get(n), Some(state(n)));
+        }
+        history.push(&state(6));
+        assert_eq!(history.pop(), Some(state(6)));
+        assert_eq!(history.pop(), Some(state(5)));
+
+        history.truncate(0);
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn test_depth() {
+        let mut history = History::new(4, Some(5));
+        for n in 0..100 {
+            history.push(&state(n));
+        }
+        assert_eq!(history.len(), 5);
+        assert_eq!(history.first(), 95);
+        assert_eq!(history.get(94), None);
+        assert_eq!(history.get(95), Some(state(95)));
+        for n in (95..100).rev() {
+            assert_eq!(history.pop(), Some(state(n)));
+        }
+        assert_eq!(history.pop(), None);
+
+        // history restarts at the current position
+        history.push(&state(95));
+        assert_eq!(history.first(), 95);
+        assert_eq!(history.get(95), Some(state(95)));
+    }
+}
@@ -0,0 +1,173 @@
+// Incremental (event driven) evaluation
+//
+// With incremental evaluation enabled (`Simulator::set_incremental`), the simulator
+// tracks which `sim_state` slots change while clocking. Pure combinational components
+// (`Component::is_pure`) are evaluated, in topological order, only if any of their
+// inputs changed since the previous cycle, otherwise their outputs (and any
+// condition raised) are kept from their last evaluation. Sequential and other
+// components are evaluated every cycle, thus results are identical to evaluating
+// all components.
+//
+// Changes made outside of clocking (e.g., `un_clock`, `reset`, forcing signals, or
+// loading state) cause all components to be evaluated on the next clock.
+use crate::common::{Condition, OutputType, Simulator};
+
+// change tracking state
+#[derive(Debug, Clone)]
+pub(crate) struct Incremental {
+    // `sim_state` indices of the connected inputs, by index in `ordered_components`
+    inputs: Vec<Vec<usize>>,
+    // evaluated only on input changes, by index in `ordered_components`
+    pure: Vec<bool>,
+    // conditions raised when last evaluated, by index in `ordered_components`
+    pub(crate) conditions: Vec<Option<Condition>>,
+    // `sim_state` slots changed in the current cycle
+    pub(crate) changed: Vec<bool>,
+    // evaluate all components on the next clock
+    all: bool,
+    // number of evaluations skipped
+    skipped: u64,
+}
+
+impl Incremental {
+    fn new(simulator: &Simulator) -> Self {
+        let (inputs, pure) = simulator
+            .ordered_components
+            .iter()
+            .zip(&simulator.input_indices)
+            .map(|(c, indices)| {
+                let inputs = indices.iter().map(|index| index.0).collect();
+                let pure = c.is_pure() && c.get_id_ports().1.out_type == OutputType::Combinatorial;
+                (inputs, pure)
+            })
+            .unzip();
+        Incremental {
+            inputs,
+            pure,
+            conditions: vec![None; simulator.ordered_components.len()],
+            changed: vec![false; simulator.sim_state.len()],
+            all: true,
+            skipped: 0,
+        }
+    }
+
+    // if component `index` can be skipped, the condition it raised when last evaluated
+    pub(crate) fn skip(&mut self, index: usize) -> Option<Option<Condition>> {
+        let evaluate = self.all
+            || !self.pure[index]
+            || self.inputs[index].iter().any(|input| self.changed[*input]);
+        match evaluate {
+            true => None,
+            false => {
+                self.skipped += 1;
+                Some(self.conditions[index].clone())
+            }
+        }
+    }
+
+    // end of a cycle, changes are tracked anew
+    pub(crate) fn cycle_done(&mut self) {
+        self.changed.iter_mut().for_each(|changed| *changed = false);
+        self.all = false;
+    }
+}
+
+impl Simulator {
+    /// enable or disable incremental evaluation, skipping pure components with
+    /// unchanged inputs
+    pub fn set_incremental(&mut self, enable: bool) {
+        self.incremental = enable.then(|| Incremental::new(self));
+    }
+
+    /// true if evaluating incrementally
+    pub fn is_incremental(&self) -> bool {
+        self.incremental.is_some()
+    }
+
+    /// number of component evaluations skipped since incremental evaluation was enabled
+    pub fn skipped_evaluations(&self) -> u64 {
+        self.incremental.as_ref().map_or(0, |i| i.skipped)
+    }
+
+    // evaluate all components on the next clock, after changes made outside of clocking
+    pub(crate) fn mark_all_changed(&mut self) {
+        if let Some(incremental) = self.incremental.as_mut() {
+            incremental.all = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::common::{ComponentStore, Input, SignalIndex, Simulator};
+    use crate::components::*;
+    use std::sync::Arc;
+
+    // counter r1, stepping alternately by 1 and 2 (mux selected by bit 0 of counter
+    // r2), with an idle adder of constants
+    fn model() -> Simulator {
+        let cs = ComponentStore::new(vec![
+            Arc::new(Constant::new("c1", (0.0, 0.0), 1)),
+            Arc::new(Constant::new("c2", (0.0, 0.0), 2)),
+            Register::arc_new("r1", (0.0, 0.0), Input::new("add", "out")),
+            Mux::arc_new(
+                "mux",
+                (0.0, 0.0),
+                Input::new("sel", "out"),
+                vec![Input::new("c1", "out"), Input::new("c2", "out")],
+            ),
+            Add::arc_new(
+                "add",
+                (0.0, 0.0),
+                Input::new("r1", "out"),
+                Input::new("mux", "out"),
+            ),
+            Register::arc_new("r2", (0.0, 0.0), Input::new("r2_next", "out")),
+            Add::arc_new(
+                "r2_next",
+                (0.0, 0.0),
+                Input::new("r2", "out"),
+                Input::new("c1", "out"),
+            ),
+            Sext::arc_new("sel", (0.0, 0.0), Input::new("r2", "out"), 1, 1),
+            Add::arc_new(
+                "idle",
+                (0.0, 0.0),
+                Input::new("c1", "out"),
+                Input::new("c2", "out"),
+            ),
+        ]);
+        Simulator::new(cs).unwrap()
+    }
+
+    #[test]
+    fn test_incremental() {
+        let mut full = model();
+        let mut incremental = model();
+        incremental.set_incremental(true);
+        assert!(incremental.is_incremental());
+        for cycle in 0..40 {
+            if cycle == 20 {
+                let index = SignalIndex(full.get_id_start_index("mux"));
+                full.force_signal(index, 5.into());
+                incremental.force_signal(index, 5.into());
+            }
+            if cycle == 25 {
+                let index = SignalIndex(full.get_id_start_index("mux"));
+                full.release_signal(index);
+                incremental.release_signal(index);
+            }
+            if cycle == 30 {
+                full.un_clock();
+                incremental.un_clock();
+            }
+            full.clock();
+            incremental.clock();
+            assert_eq!(incremental.sim_state, full.sim_state, "cycle {}", cycle);
+            assert_eq!(incremental.component_condition, full.component_condition);
+        }
+        // at least the constants and the idle adder are skipped
+        assert!(incremental.skipped_evaluations() >= 3 * 30);
+        assert_eq!(full.skipped_evaluations(), 0);
+    }
+}
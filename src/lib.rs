@@ -1,13 +1,32 @@
 pub mod common;
 pub mod component_store;
+pub mod dot;
 pub mod fern;
+pub mod headless;
+pub mod history;
+pub mod incremental;
+pub mod migration;
+pub mod parameter;
+pub mod profile;
+pub mod registry;
 pub mod signal;
 pub mod simulator;
+pub mod simulator_thread;
+pub mod speed;
+pub mod state;
+pub mod testbench;
+pub mod timing;
+pub mod vcd;
+pub mod watchpoint;
 
 // Default provided components
 #[cfg(feature = "components")]
 pub mod components;
 
+// Models shared by the unit tests
+#[cfg(test)]
+pub(crate) mod fixtures;
+
 // Vizia frontend
 #[cfg(feature = "gui-vizia")]
 pub mod gui_vizia;
@@ -2,7 +2,12 @@ use clap::Parser;
 use std::path::PathBuf;
 #[cfg(feature = "gui-egui")]
 use syncrim::gui_egui::editor::Library;
-use syncrim::{common::ComponentStore, fern::fern_setup};
+use syncrim::{
+    common::{ComponentStore, Input},
+    fern::fern_setup,
+    headless::Headless,
+    testbench::Testbench,
+};
 /// Simple program to greet a person
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -10,6 +15,42 @@ struct Args {
     /// Path to the model to load on startup
     #[arg(short, long, default_value = "empty.json")]
     model: String,
+    /// Run without GUI
+    #[arg(long)]
+    headless: bool,
+    /// Number of cycles to run in headless mode
+    #[arg(long)]
+    cycles: Option<usize>,
+    /// Run until a component halts in headless mode
+    #[arg(long)]
+    until_halt: bool,
+    /// Signal to print in headless mode, on the form `id/field` (may be repeated)
+    #[arg(short, long)]
+    signal: Vec<Input>,
+    /// Print signals each cycle in headless mode
+    #[arg(long)]
+    trace: bool,
+    /// Number of cycles kept in history in headless mode (unbounded if not given)
+    #[arg(long)]
+    history_depth: Option<usize>,
+    /// Export history as VCD file at the end of a headless run
+    #[arg(long)]
+    vcd: Option<PathBuf>,
+    /// Profile a headless run, exporting component statistics as CSV file at the end
+    #[arg(long)]
+    profile: Option<PathBuf>,
+    /// Evaluate incrementally in headless mode, skipping components with unchanged inputs
+    #[arg(long)]
+    incremental: bool,
+    /// Export the model graph as DOT file, with the signal values at the end of a headless run
+    #[arg(long)]
+    dot: Option<PathBuf>,
+    /// Run testbench (the model is given by the testbench)
+    #[arg(long)]
+    testbench: Option<PathBuf>,
+    /// List the component types supported, with their ports
+    #[arg(long)]
+    list_components: bool,
 }
 
 fn main() {
@@ -17,14 +58,61 @@ fn main() {
     let args = Args::parse();
     let path = PathBuf::from(args.model);
 
-    let cs = ComponentStore::load_file(&path);
+    if args.list_components {
+        for component_type in syncrim::registry::component_types() {
+            println!("{}", component_type);
+        }
+        return;
+    }
+
+    if let Some(testbench) = args.testbench {
+        let code = match Testbench::load(&testbench).and_then(|tb| tb.run()) {
+            Ok(report) => {
+                println!("{}", report);
+                !report.passed() as i32
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                3
+            }
+        };
+        std::process::exit(code);
+    }
+
+    // always headless if built without a gui
+    let headless = args.headless || cfg!(not(any(feature = "gui-vizia", feature = "gui-egui")));
+    let cs = match ComponentStore::load_file(&path) {
+        Ok(cs) => cs,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(3);
+        }
+    };
+    if headless {
+        let headless = Headless {
+            cycles: args.cycles,
+            until_halt: args.until_halt,
+            signals: args.signal,
+            trace: args.trace,
+            history_depth: args.history_depth,
+            vcd: args.vcd,
+            profile: args.profile,
+            incremental: args.incremental,
+            dot: args.dot,
+        };
+        let code = match headless.run_store(cs) {
+            Ok(result) => result.exit_code(),
+            Err(e) => {
+                eprintln!("{}", e);
+                3
+            }
+        };
+        std::process::exit(code);
+    }
 
     #[cfg(feature = "gui-egui")]
     syncrim::gui_egui::gui(cs, &path, Library::default()).ok();
 
     #[cfg(feature = "gui-vizia")]
     syncrim::gui_vizia::gui(cs, &path);
-
-    #[cfg(not(any(feature = "gui-vizia", feature = "gui-egui")))]
-    syncrim::common::Simulator::new(cs).unwrap();
 }
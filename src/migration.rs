@@ -0,0 +1,448 @@
+// Versioned model files, upgraded on load
+//
+// A model file starts with a header giving the format version and the component
+// libraries (crates providing the component types) used, e.g.:
+//
+// {
+//   "header": { "version": 1, "libraries": ["riscv", "syncrim"] },
+//   "store": [ ... ]
+// }
+//
+// Files without header are of version 0. Components of a file of an older version
+// are upgraded before deserialization, by the migrations registered for their type
+// (`register_migrations`) by the library providing it, in order of version.
+use crate::common::{ComponentStore, Components, FileError, FileErrorKind, Id};
+use serde::{Deserialize, Serialize};
+use serde_json::{value::RawValue, Map, Value};
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+/// current version of the model file format
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Header of a model file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub version: u32,
+    /// libraries providing the component types used
+    #[serde(default)]
+    pub libraries: Vec<String>,
+}
+
+/// Upgrade of the components of a type to a format version
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// component type, as tagged in the model (e.g., `"Mem"`)
+    pub type_name: &'static str,
+    /// format version upgraded to, applied to files of older versions
+    pub version: u32,
+    /// upgrade component (a JSON object), an error should name the field involved
+    pub migrate: fn(&mut Map<String, Value>) -> Result<(), String>,
+}
+
+// migrations registered by libraries
+static MIGRATIONS: RwLock<Vec<Migration>> = RwLock::new(vec![]);
+
+/// register migrations of a library, needed to load models of older versions
+///
+/// A migration is registered once for its type and version, registering it again
+/// replaces it.
+pub fn register_migrations(migrations: &[Migration]) {
+    let mut registered = MIGRATIONS.write().unwrap();
+    for migration in migrations {
+        registered.retain(|m| (m.type_name, m.version) != (migration.type_name, migration.version));
+        registered.push(*migration);
+    }
+}
+
+/// set field added by a migration to its default value, unless present
+pub fn default_field(component: &mut Map<String, Value>, field: &str, value: impl Into<Value>) {
+    component.entry(field).or_insert_with(|| value.into());
+}
+
+// migrations of component type upgrading from `version`, in order of version
+fn migrations(type_name: &str, version: u32) -> Vec<Migration> {
+    #[cfg(feature = "components")]
+    let builtin = crate::components::MIGRATIONS;
+    #[cfg(not(feature = "components"))]
+    let builtin: &[Migration] = &[];
+    let registered = MIGRATIONS.read().unwrap();
+    let mut migrations: Vec<Migration> = builtin
+        .iter()
+        .chain(registered.iter())
+        .filter(|m| m.type_name == type_name && m.version > version)
+        .copied()
+        .collect();
+    // a builtin migration registered by a library applies once
+    migrations.sort_by_key(|m| m.version);
+    migrations.dedup_by_key(|m| m.version);
+    migrations
+}
+
+// model file as saved, the component store preceded by its header
+#[derive(Serialize)]
+struct ModelFile<'a> {
+    header: Header,
+    #[serde(flatten)]
+    model: &'a ComponentStore,
+}
+
+// model file as read, components kept as text to locate errors
+#[derive(Deserialize)]
+struct RawModelFile<'a> {
+    header: Option<Header>,
+    #[serde(borrow)]
+    store: Vec<&'a RawValue>,
+    #[serde(default)]
+    delays: BTreeMap<Id, f64>,
+}
+
+// upgrade component from `version`, true if any migration applied
+fn migrate_component(component: &mut Map<String, Value>, version: u32) -> Result<bool, String> {
+    let Some(type_name) = component.get("type").and_then(Value::as_str) else {
+        return Err(format!(
+            "Component {} has no type",
+            Value::from(component.clone())
+        ));
+    };
+    let migrations = migrations(type_name, version);
+    for migration in &migrations {
+        (migration.migrate)(component).map_err(|e| {
+            format!(
+                "Component {} ({}), upgrading to version {}: {}",
+                component_id(component),
+                migration.type_name,
+                migration.version,
+                e
+            )
+        })?;
+    }
+    Ok(!migrations.is_empty())
+}
+
+// version of a model file, an error if newer than supported
+fn check_version(header: Option<&Header>) -> Result<u32, String> {
+    let version = header.map_or(0, |header| header.version);
+    match version > FORMAT_VERSION {
+        true => Err(format!(
+            "Model format version {} is newer than supported ({})",
+            version, FORMAT_VERSION
+        )),
+        false => Ok(version),
+    }
+}
+
+// line and column (1-based) of byte `offset` in `text`
+fn position(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    (line, column)
+}
+
+// message of a JSON error, without the position
+fn message(e: &serde_json::Error) -> String {
+    let message = e.to_string();
+    let position = format!(" at line {} column {}", e.line(), e.column());
+    match message.strip_suffix(&position) {
+        Some(message) => message.to_string(),
+        None => message,
+    }
+}
+
+// edit of a component (JSON object) when loading, e.g., renaming for a subcircuit
+pub(crate) type EditComponent<'a> = &'a dyn Fn(&mut Map<String, Value>);
+
+impl ComponentStore {
+    /// header of the current format version, listing the libraries used
+    pub fn header(&self) -> Header {
+        let mut libraries: Vec<String> =
+            self.store.iter().map(|c| c.library().to_string()).collect();
+        libraries.sort();
+        libraries.dedup();
+        Header {
+            version: FORMAT_VERSION,
+            libraries,
+        }
+    }
+
+    /// model as JSON, with header
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(&ModelFile {
+            header: self.header(),
+            model: self,
+        })
+    }
+
+    /// upgrade model (as JSON) to the current format version
+    pub fn migrate(model: &mut Value) -> Result<(), String> {
+        let header: Option<Header> = match model.get("header") {
+            Some(header) => Some(
+                serde_json::from_value(header.clone())
+                    .map_err(|e| format!("Invalid header: {}", e))?,
+            ),
+            None => None,
+        };
+        let version = check_version(header.as_ref())?;
+        let components = model
+            .get_mut("store")
+            .and_then(Value::as_array_mut)
+            .ok_or("Model has no component store")?;
+        for component in components {
+            let Some(component) = component.as_object_mut() else {
+                return Err(format!("Component {} is not an object", component));
+            };
+            migrate_component(component, version)?;
+        }
+        model["header"] = serde_json::to_value(Header {
+            version: FORMAT_VERSION,
+            libraries: header.map(|header| header.libraries).unwrap_or_default(),
+        })
+        .unwrap();
+        Ok(())
+    }
+
+    /// model from JSON, upgraded to the current format version, errors located by
+    /// line and column and naming the component failing to load
+    pub fn from_json(json: &str) -> Result<Self, FileError> {
+        ComponentStore::from_json_with(json, None)
+    }
+
+    // model from JSON, components (JSON objects) edited by `edit` before deserialization
+    pub(crate) fn from_json_with(
+        json: &str,
+        edit: Option<EditComponent>,
+    ) -> Result<Self, FileError> {
+        let error = |position, id, kind| FileError {
+            path: None,
+            position,
+            id,
+            kind,
+        };
+        let model: RawModelFile = serde_json::from_str(json).map_err(|e| {
+            let position = (e.line() > 0).then(|| (e.line(), e.column()));
+            error(position, None, FileErrorKind::Json(message(&e)))
+        })?;
+        let version = check_version(model.header.as_ref())
+            .map_err(|e| error(None, None, FileErrorKind::Migration(e)))?;
+
+        // deserialize components one by one, naming the one failing
+        let mut store: Components = vec![];
+        for raw in model.store {
+            let offset = raw.get().as_ptr() as usize - json.as_ptr() as usize;
+            let (line, column) = position(json, offset);
+            let mut component: Value = serde_json::from_str(raw.get()).unwrap();
+            let Some(object) = component.as_object_mut() else {
+                let kind = FileErrorKind::Json("component is not an object".to_string());
+                return Err(error(Some((line, column)), None, kind));
+            };
+            let id = object.get("id").and_then(Value::as_str).map(str::to_string);
+            let migrated = migrate_component(object, version).map_err(|e| {
+                error(
+                    Some((line, column)),
+                    id.clone(),
+                    FileErrorKind::Migration(e),
+                )
+            })?;
+            if let Some(edit) = edit {
+                edit(object);
+            }
+            let rewritten = migrated || edit.is_some();
+            let type_name = object
+                .get("type")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+            // upgraded or edited components are deserialized from their new text, as read
+            // from the file, errors are located at the component
+            let text = match rewritten {
+                true => tagged_text(object),
+                false => raw.get().to_string(),
+            };
+            match serde_json::from_str(&text) {
+                Ok(component) => store.push(component),
+                Err(e) => {
+                    // position in the file, of the error within the component if known
+                    let position = match (rewritten, e.line()) {
+                        (true, _) | (_, 0) => (line, column),
+                        (_, 1) => (line, column + e.column() - 1),
+                        (_, l) => (line + l - 1, e.column()),
+                    };
+                    let known = ComponentStore::known_types();
+                    let kind = match known.contains(&type_name) {
+                        true => FileErrorKind::Component(message(&e)),
+                        false => FileErrorKind::UnknownType { type_name, known },
+                    };
+                    return Err(error(Some(position), id, kind));
+                }
+            }
+        }
+        Ok(ComponentStore {
+            store,
+            delays: model.delays,
+        })
+    }
+}
+
+// component (a JSON object) as text with its type first, as saved
+//
+// `typetag` buffers the fields of components not having their type first, failing
+// on some types, e.g., maps with integer keys.
+fn tagged_text(component: &mut Map<String, Value>) -> String {
+    #[derive(Serialize)]
+    struct Tagged<'a> {
+        #[serde(rename = "type")]
+        type_name: Value,
+        #[serde(flatten)]
+        fields: &'a Map<String, Value>,
+    }
+    let type_name = component.remove("type").unwrap_or_default();
+    serde_json::to_string(&Tagged {
+        type_name,
+        fields: component,
+    })
+    .unwrap()
+}
+
+// identifier of a component (a JSON object), for messages
+fn component_id(component: &Map<String, Value>) -> String {
+    match component.get("id") {
+        Some(id) => id.to_string(),
+        None => "without id".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::Input;
+    use crate::components::*;
+    use std::sync::Arc;
+
+    // test migration, version 1 renamed the `endian` field of memories to `big_endian`
+    fn rename_endian(mem: &mut Map<String, Value>) -> Result<(), String> {
+        if let Some(endian) = mem.remove("endian") {
+            mem.insert("big_endian".to_string(), endian);
+        }
+        Ok(())
+    }
+
+    const TEST_MIGRATIONS: &[Migration] = &[Migration {
+        type_name: "Mem",
+        version: 1,
+        migrate: rename_endian,
+    }];
+
+    // model of version 0, without header, with memory contents
+    fn old() -> Value {
+        let mem = Mem::arc_new(
+            "mem",
+            (0.0, 0.0),
+            120.0,
+            80.0,
+            true,
+            Input::new("c", "out"),
+            Input::new("c", "out"),
+            Input::new("c", "out"),
+            Input::new("c", "out"),
+            Input::new("c", "out"),
+            0..4,
+        );
+        mem.memory.0.write().unwrap().insert(2, 0xab);
+        let cs = ComponentStore::new(vec![Arc::new(Constant::new("c", (0.0, 0.0), 0)), mem]);
+        let mut model = serde_json::to_value(&cs).unwrap();
+        let mem = model["store"][1].as_object_mut().unwrap();
+        let endian = mem.remove("big_endian").unwrap();
+        mem.insert("endian".to_string(), endian);
+        model
+    }
+
+    // contents of the memory of a model
+    fn contents(cs: &ComponentStore) -> BTreeMap<usize, u8> {
+        let mem = cs
+            .store
+            .iter()
+            .find_map(|c| c.as_any().downcast_ref::<Mem>())
+            .unwrap();
+        mem.memory.0.read().unwrap().clone()
+    }
+
+    #[test]
+    fn test_migrate() {
+        // registering again has no effect
+        register_migrations(TEST_MIGRATIONS);
+        register_migrations(TEST_MIGRATIONS);
+        assert_eq!(migrations("Mem", 0).len(), 1);
+
+        let mut model = old();
+        ComponentStore::migrate(&mut model).unwrap();
+        assert_eq!(model["header"]["version"], FORMAT_VERSION);
+        assert_eq!(model["store"][1]["big_endian"], true);
+
+        let cs = ComponentStore::from_json(&old().to_string()).unwrap();
+        assert_eq!(cs.store.len(), 2);
+        assert_eq!(contents(&cs).get(&2), Some(&0xab));
+        assert_eq!(
+            cs.header(),
+            Header {
+                version: FORMAT_VERSION,
+                libraries: vec!["syncrim".to_string()]
+            }
+        );
+
+        // saved with header, loaded as is
+        let json = cs.to_json().unwrap();
+        let model: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(model["header"]["libraries"][0], "syncrim");
+        let cs = ComponentStore::from_json(&json).unwrap();
+        assert_eq!(contents(&cs).get(&2), Some(&0xab));
+    }
+
+    #[test]
+    fn test_migrate_errors() {
+        let newer = r#"{"header": {"version": 1000, "libraries": []}, "store": []}"#;
+        let e = ComponentStore::from_json(newer).err().unwrap();
+        assert!(e.to_string().contains("version 1000"), "{}", e);
+
+        // a missing field names the component and the field
+        register_migrations(TEST_MIGRATIONS);
+        let mut model = old();
+        model["store"][1].as_object_mut().unwrap().remove("data");
+        let e = ComponentStore::from_json(&model.to_string()).err().unwrap();
+        assert_eq!(e.id.as_deref(), Some("mem"));
+        assert!(e.to_string().contains("`data`"), "{}", e);
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let model = r#"{
+  "header": { "version": 1, "libraries": ["syncrim"] },
+  "store": [
+    { "type": "Addd", "id": "add", "pos": [0.0, 0.0] }
+  ]
+}"#;
+        let e = ComponentStore::from_json(model).err().unwrap();
+        assert_eq!(e.id.as_deref(), Some("add"));
+        assert_eq!(e.position.map(|(line, _)| line), Some(4));
+        match &e.kind {
+            FileErrorKind::UnknownType { type_name, known } => {
+                assert_eq!(type_name, "Addd");
+                assert!(known.iter().any(|t| t == "Add"), "{:?}", known);
+            }
+            kind => panic!("{:?}", kind),
+        }
+        assert!(
+            e.to_string()
+                .starts_with("4:20: component \"add\": unknown"),
+            "{}",
+            e
+        );
+
+        // invalid JSON, located in the file
+        let e = ComponentStore::from_json("{\n  \"store\": [,]\n}")
+            .err()
+            .unwrap();
+        assert_eq!(e.position, Some((2, 13)));
+        assert!(matches!(e.kind, FileErrorKind::Json(_)));
+    }
+}
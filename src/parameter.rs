@@ -0,0 +1,319 @@
+// Editable parameters of components
+//
+// A component lists its editable parameters (`Component::parameters`), each a name
+// and a typed value carrying its constraints (bounds, options, the input port
+// connected), and applies an edited value by `Component::set_parameter`.
+//
+// Front-ends edit parameters generically, e.g., the properties window of the egui
+// editor (`gui_egui::component_ui::parameters_editor`). Edited values are applied by
+// `set_parameter`, checking them against the declared constraints first, so a
+// component only checks constraints between its parameters (e.g., `in_size` not
+// exceeding `out_size` of `Sext`).
+use crate::common::{Component, Id, Input};
+use std::fmt;
+use std::ops::Range;
+
+/// Value of a parameter, with its constraints
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParameterValue {
+    /// integer within `min..=max`
+    Int {
+        value: i64,
+        min: i64,
+        max: i64,
+    },
+    Bool(bool),
+    /// one of `options`
+    Enum {
+        value: String,
+        options: Vec<String>,
+    },
+    /// range within `min..=max`, possibly empty
+    Range {
+        value: Range<u64>,
+        min: u64,
+        max: u64,
+    },
+    /// input connected to the input port `port_id`
+    Input {
+        value: Input,
+        port_id: Id,
+    },
+}
+
+/// Editable parameter of a component
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub name: String,
+    pub value: ParameterValue,
+}
+
+impl Parameter {
+    pub fn int(name: &str, value: i64, min: i64, max: i64) -> Self {
+        Parameter {
+            name: name.to_string(),
+            value: ParameterValue::Int { value, min, max },
+        }
+    }
+
+    pub fn bool(name: &str, value: bool) -> Self {
+        Parameter {
+            name: name.to_string(),
+            value: ParameterValue::Bool(value),
+        }
+    }
+
+    pub fn choice(name: &str, value: &str, options: &[&str]) -> Self {
+        Parameter {
+            name: name.to_string(),
+            value: ParameterValue::Enum {
+                value: value.to_string(),
+                options: options.iter().map(|o| o.to_string()).collect(),
+            },
+        }
+    }
+
+    pub fn range(name: &str, value: Range<u64>, min: u64, max: u64) -> Self {
+        Parameter {
+            name: name.to_string(),
+            value: ParameterValue::Range { value, min, max },
+        }
+    }
+
+    /// input connected to port `port_id`, named by the port
+    pub fn input(port_id: &str, value: &Input) -> Self {
+        Parameter {
+            name: port_id.to_string(),
+            value: ParameterValue::Input {
+                value: value.clone(),
+                port_id: port_id.to_string(),
+            },
+        }
+    }
+
+    /// check the value against its constraints
+    pub fn check(&self) -> Result<(), String> {
+        let valid = match &self.value {
+            ParameterValue::Int { value, min, max } => (min..=max).contains(&value),
+            ParameterValue::Bool(_) | ParameterValue::Input { .. } => true,
+            ParameterValue::Enum { value, options } => options.contains(value),
+            ParameterValue::Range { value, min, max } => {
+                value.start <= value.end && *min <= value.start && value.end <= *max
+            }
+        };
+        match valid {
+            true => Ok(()),
+            false => Err(format!(
+                "{}: {} is not {}",
+                self.name,
+                self.value,
+                self.allowed()
+            )),
+        }
+    }
+
+    // description of the values allowed, for messages
+    fn allowed(&self) -> String {
+        match &self.value {
+            ParameterValue::Int { min, max, .. } => format!("within {}..={}", min, max),
+            ParameterValue::Enum { options, .. } => format!("one of {}", options.join(", ")),
+            ParameterValue::Range { min, max, .. } => format!("a range within {}..={}", min, max),
+            ParameterValue::Bool(_) | ParameterValue::Input { .. } => "allowed".to_string(),
+        }
+    }
+}
+
+impl ParameterValue {
+    /// kind of parameter, for messages
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ParameterValue::Int { .. } => "integer",
+            ParameterValue::Bool(_) => "boolean",
+            ParameterValue::Enum { .. } => "option",
+            ParameterValue::Range { .. } => "range",
+            ParameterValue::Input { .. } => "input",
+        }
+    }
+
+    pub fn as_int(&self) -> Result<i64, String> {
+        match self {
+            ParameterValue::Int { value, .. } => Ok(*value),
+            _ => Err(self.expected("integer")),
+        }
+    }
+
+    pub fn as_bool(&self) -> Result<bool, String> {
+        match self {
+            ParameterValue::Bool(value) => Ok(*value),
+            _ => Err(self.expected("boolean")),
+        }
+    }
+
+    /// option selected
+    pub fn as_str(&self) -> Result<&str, String> {
+        match self {
+            ParameterValue::Enum { value, .. } => Ok(value),
+            _ => Err(self.expected("option")),
+        }
+    }
+
+    pub fn as_range(&self) -> Result<Range<u64>, String> {
+        match self {
+            ParameterValue::Range { value, .. } => Ok(value.clone()),
+            _ => Err(self.expected("range")),
+        }
+    }
+
+    pub fn as_input(&self) -> Result<Input, String> {
+        match self {
+            ParameterValue::Input { value, .. } => Ok(value.clone()),
+            _ => Err(self.expected("input")),
+        }
+    }
+
+    fn expected(&self, kind: &str) -> String {
+        format!("Expected {}, got {}", kind, self.kind())
+    }
+
+    // `value` with the constraints of self, an error if of another kind
+    fn constrained(&self, value: ParameterValue) -> Result<ParameterValue, String> {
+        Ok(match (self, value) {
+            (ParameterValue::Int { min, max, .. }, ParameterValue::Int { value, .. }) => {
+                ParameterValue::Int {
+                    value,
+                    min: *min,
+                    max: *max,
+                }
+            }
+            (ParameterValue::Bool(_), ParameterValue::Bool(value)) => ParameterValue::Bool(value),
+            (ParameterValue::Enum { options, .. }, ParameterValue::Enum { value, .. }) => {
+                ParameterValue::Enum {
+                    value,
+                    options: options.clone(),
+                }
+            }
+            (ParameterValue::Range { min, max, .. }, ParameterValue::Range { value, .. }) => {
+                ParameterValue::Range {
+                    value,
+                    min: *min,
+                    max: *max,
+                }
+            }
+            (ParameterValue::Input { port_id, .. }, ParameterValue::Input { value, .. }) => {
+                ParameterValue::Input {
+                    value,
+                    port_id: port_id.clone(),
+                }
+            }
+            (declared, value) => return Err(value.expected(declared.kind())),
+        })
+    }
+}
+
+impl fmt::Display for ParameterValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParameterValue::Int { value, .. } => write!(f, "{}", value),
+            ParameterValue::Bool(value) => write!(f, "{}", value),
+            ParameterValue::Enum { value, .. } => write!(f, "{:?}", value),
+            ParameterValue::Range { value, .. } => write!(f, "{:?}", value),
+            ParameterValue::Input { value, .. } => write!(f, "{}.{}", value.id, value.field),
+        }
+    }
+}
+
+/// error of `Component::set_parameter` for parameters not listed by the component
+pub fn unknown_parameter(name: &str) -> String {
+    format!("No parameter {:?}", name)
+}
+
+/// input parameters of a component, one for each of its input ports
+pub fn input_parameters(component: &dyn Component) -> Vec<Parameter> {
+    let ports = component.get_id_ports().1;
+    ports
+        .inputs
+        .iter()
+        .map(|port| Parameter::input(&port.port_id, &port.input))
+        .collect()
+}
+
+/// set input parameter listed by `input_parameters`, by `Component::set_id_port`
+pub fn set_input_parameter(
+    component: &mut dyn Component,
+    name: &str,
+    value: ParameterValue,
+) -> Result<(), String> {
+    let ports = component.get_id_ports().1;
+    if !ports.inputs.iter().any(|port| port.port_id == name) {
+        return Err(unknown_parameter(name));
+    }
+    component.set_id_port(name.to_string(), value.as_input()?);
+    Ok(())
+}
+
+/// set parameter of a component, checked against the constraints it declares
+pub fn set_parameter(
+    component: &mut dyn Component,
+    name: &str,
+    value: ParameterValue,
+) -> Result<(), String> {
+    let declared = component
+        .parameters()
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| unknown_parameter(name))?;
+    let parameter = Parameter {
+        name: name.to_string(),
+        value: declared.value.constrained(value)?,
+    };
+    parameter.check()?;
+    component.set_parameter(name, parameter.value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::components::*;
+
+    #[test]
+    fn test_parameters() {
+        let mut sext = Sext::new("sext", (0.0, 0.0), Input::new("c", "out"), 4, 16);
+        let names: Vec<String> = sext.parameters().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec![SEXT_IN_ID, "in_size", "out_size"]);
+
+        let int = |value| ParameterValue::Int {
+            value,
+            min: 0,
+            max: 0,
+        };
+        // constraints are those declared, not of the value given
+        set_parameter(&mut sext, "out_size", int(64)).unwrap();
+        let e = set_parameter(&mut sext, "out_size", int(65)).unwrap_err();
+        assert_eq!(e, "out_size: 65 is not within 1..=64");
+        // constraints between parameters are checked by the component
+        assert!(set_parameter(&mut sext, "in_size", int(24)).is_ok());
+        assert!(set_parameter(&mut sext, "out_size", int(16)).is_err());
+        let e = set_parameter(&mut sext, "in_size", ParameterValue::Bool(true)).unwrap_err();
+        assert_eq!(e, "Expected integer, got boolean");
+        assert_eq!(
+            set_parameter(&mut sext, "size", int(1)),
+            Err(unknown_parameter("size"))
+        );
+
+        let input = Parameter::input(SEXT_IN_ID, &Input::new("r", "out")).value;
+        set_parameter(&mut sext, SEXT_IN_ID, input).unwrap();
+        assert_eq!(
+            sext.get_id_ports().1.inputs[0].input,
+            Input::new("r", "out")
+        );
+        assert!(sext.parameters().iter().all(|p| p.check().is_ok()));
+
+        let range = Parameter::range("range", Range { start: 4, end: 2 }, 0, 8);
+        assert!(range.check().is_err());
+        let choice = Parameter::choice("endianness", "middle", &["little", "big"]);
+        assert_eq!(
+            choice.check(),
+            Err("endianness: \"middle\" is not one of little, big".to_string())
+        );
+    }
+}
@@ -0,0 +1,340 @@
+// Profiling, per component activity statistics collected while simulating
+//
+// With profiling enabled (`Simulator::set_profiling`), each clock records for every
+// component the number of evaluations and the wall time spent in its `clock`, the
+// number of output value changes and output bit toggles (switching activity, a
+// rough estimate of dynamic power), and the memory accesses reported by the
+// component itself (`Simulator::record_access`).
+use crate::common::{Id, Signal, SignalWide, Simulator};
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Memory access, reported by memory components while clocked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+/// Statistics of a single component
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ComponentStats {
+    pub id: Id,
+    /// number of `clock` calls
+    pub evaluations: u64,
+    /// total wall time spent in `clock`
+    pub time: Duration,
+    /// number of output value changes
+    pub changes: u64,
+    /// number of output bits toggled between known values
+    pub toggles: u64,
+    /// number of cycles with a memory read
+    pub reads: u64,
+    /// number of cycles with a memory write
+    pub writes: u64,
+}
+
+impl ComponentStats {
+    /// mean wall time per evaluation
+    pub fn time_per_evaluation(&self) -> Duration {
+        match self.evaluations {
+            0 => Duration::ZERO,
+            n => Duration::from_nanos((self.time.as_nanos() / n as u128) as u64),
+        }
+    }
+}
+
+/// Column of the statistics, for sorting and export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Column {
+    #[default]
+    Id,
+    Evaluations,
+    Time,
+    TimePerEvaluation,
+    Changes,
+    Toggles,
+    Reads,
+    Writes,
+}
+
+impl Column {
+    pub const ALL: [Column; 8] = [
+        Column::Id,
+        Column::Evaluations,
+        Column::Time,
+        Column::TimePerEvaluation,
+        Column::Changes,
+        Column::Toggles,
+        Column::Reads,
+        Column::Writes,
+    ];
+
+    // order of two components by this column
+    fn cmp(&self, a: &ComponentStats, b: &ComponentStats) -> Ordering {
+        match self {
+            Column::Id => a.id.cmp(&b.id),
+            Column::Evaluations => a.evaluations.cmp(&b.evaluations),
+            Column::Time => a.time.cmp(&b.time),
+            Column::TimePerEvaluation => a.time_per_evaluation().cmp(&b.time_per_evaluation()),
+            Column::Changes => a.changes.cmp(&b.changes),
+            Column::Toggles => a.toggles.cmp(&b.toggles),
+            Column::Reads => a.reads.cmp(&b.reads),
+            Column::Writes => a.writes.cmp(&b.writes),
+        }
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Column::Id => "id",
+            Column::Evaluations => "evaluations",
+            Column::Time => "time (us)",
+            Column::TimePerEvaluation => "time/evaluation (ns)",
+            Column::Changes => "changes",
+            Column::Toggles => "toggles",
+            Column::Reads => "reads",
+            Column::Writes => "writes",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Statistics of all components, since profiling was enabled
+#[derive(Debug, Clone)]
+pub struct Profile {
+    /// number of cycles profiled
+    pub cycles: u64,
+    /// statistics, in evaluation order
+    pub stats: Vec<ComponentStats>,
+    // output indices in `sim_state` of each component
+    outputs: Vec<Range<usize>>,
+    // component being clocked
+    current: Option<usize>,
+}
+
+impl Profile {
+    pub(crate) fn new(simulator: &Simulator) -> Self {
+        let ids = simulator
+            .ordered_components
+            .iter()
+            .map(|c| c.get_id_ports().0);
+        let (stats, outputs) = ids
+            .map(|id| {
+                let start = simulator.id_start_index[&id];
+                let end = start + simulator.id_nr_outputs[&id];
+                let stats = ComponentStats {
+                    id,
+                    ..Default::default()
+                };
+                (stats, start..end)
+            })
+            .unzip();
+        Profile {
+            cycles: 0,
+            stats,
+            outputs,
+            current: None,
+        }
+    }
+
+    /// fraction of cycles with a memory read by the component
+    pub fn read_fraction(&self, stats: &ComponentStats) -> f64 {
+        stats.reads as f64 / self.cycles.max(1) as f64
+    }
+
+    /// fraction of cycles with a memory write by the component
+    pub fn write_fraction(&self, stats: &ComponentStats) -> f64 {
+        stats.writes as f64 / self.cycles.max(1) as f64
+    }
+
+    /// statistics sorted by column
+    pub fn sorted(&self, column: Column, descending: bool) -> Vec<&ComponentStats> {
+        let mut stats: Vec<&ComponentStats> = self.stats.iter().collect();
+        stats.sort_by(|a, b| match descending {
+            true => column.cmp(b, a),
+            false => column.cmp(a, b),
+        });
+        stats
+    }
+
+    /// write statistics as CSV, one line per component
+    pub fn write_csv(&self, w: &mut impl Write) -> io::Result<()> {
+        let header: Vec<String> = Column::ALL.iter().map(|c| c.to_string()).collect();
+        writeln!(w, "{},read fraction,write fraction", header.join(","))?;
+        for stats in &self.stats {
+            writeln!(
+                w,
+                "{},{},{:.3},{:.1},{},{},{},{},{:.4},{:.4}",
+                stats.id,
+                stats.evaluations,
+                stats.time.as_secs_f64() * 1e6,
+                stats.time_per_evaluation().as_secs_f64() * 1e9,
+                stats.changes,
+                stats.toggles,
+                stats.reads,
+                stats.writes,
+                self.read_fraction(stats),
+                self.write_fraction(stats),
+            )?;
+        }
+        Ok(())
+    }
+
+    // start of the evaluation of component `index`
+    pub(crate) fn start(&mut self, index: usize) -> Instant {
+        self.current = Some(index);
+        Instant::now()
+    }
+
+    // end of the evaluation of the current component
+    pub(crate) fn end(&mut self, start: Instant) {
+        if let Some(index) = self.current.take() {
+            let stats = &mut self.stats[index];
+            stats.evaluations += 1;
+            stats.time += start.elapsed();
+        }
+    }
+
+    // account for the output changes of a cycle
+    pub(crate) fn cycle(&mut self, previous: &[Signal], current: &[Signal]) {
+        self.cycles += 1;
+        for (stats, outputs) in self.stats.iter_mut().zip(&self.outputs) {
+            for i in outputs.clone() {
+                let (old, new) = (previous[i].get_value(), current[i].get_value());
+                if old != new {
+                    stats.changes += 1;
+                    if let (Ok(old), Ok(new)) =
+                        (SignalWide::try_from(old), SignalWide::try_from(new))
+                    {
+                        stats.toggles += (old ^ new).count_ones() as u64;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Simulator {
+    /// enable (restarting the statistics) or disable profiling
+    pub fn set_profiling(&mut self, enable: bool) {
+        self.profile = enable.then(|| Profile::new(self));
+    }
+
+    /// statistics collected, if profiling
+    pub fn profile(&self) -> Option<&Profile> {
+        self.profile.as_ref()
+    }
+
+    /// report a memory access by the component being clocked, ignored unless profiling
+    pub fn record_access(&mut self, access: Access) {
+        if let Some(profile) = self.profile.as_mut() {
+            if let Some(index) = profile.current {
+                match access {
+                    Access::Read => profile.stats[index].reads += 1,
+                    Access::Write => profile.stats[index].writes += 1,
+                }
+            }
+        }
+    }
+
+    /// save statistics as CSV file
+    pub fn save_profile_csv(&self, path: &Path) -> io::Result<()> {
+        let profile = self
+            .profile
+            .as_ref()
+            .ok_or(io::Error::other("profiling not enabled"))?;
+        let mut file = io::BufWriter::new(std::fs::File::create(path)?);
+        profile.write_csv(&mut file)?;
+        file.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::{ComponentStore, Components, Input};
+    use crate::components::*;
+    use crate::fixtures::counter_components;
+    use std::sync::Arc;
+
+    // counter, stored to one memory and loaded from another
+    fn counter() -> Simulator {
+        let mem = |id: &str, ctrl: &str| {
+            Mem::arc_new(
+                id,
+                (0.0, 0.0),
+                0.0,
+                0.0,
+                false,
+                Input::new("r1", "out"),
+                Input::new("size", "out"),
+                Input::new(ctrl, "out"),
+                Input::new("c1", "out"),
+                Input::new("size", "out"),
+                0..16,
+            )
+        };
+        let mut store = counter_components();
+        store.extend::<Components>(vec![
+            Arc::new(Constant::new("size", (0.0, 0.0), 4)),
+            Arc::new(Constant::new("read", (0.0, 0.0), MemCtrl::Read as u32)),
+            Arc::new(Constant::new("write", (0.0, 0.0), MemCtrl::Write as u32)),
+            mem("store", "write"),
+            mem("load", "read"),
+        ]);
+        Simulator::new(ComponentStore::new(store)).unwrap()
+    }
+
+    #[test]
+    fn test_profile() {
+        let mut simulator = counter();
+        assert!(simulator.profile().is_none());
+        simulator.set_profiling(true);
+        for _ in 0..8 {
+            simulator.clock();
+        }
+        let profile = simulator.profile().unwrap();
+        assert_eq!(profile.cycles, 8);
+        let stats = |id: &str| profile.stats.iter().find(|s| s.id == id).unwrap();
+
+        assert_eq!(stats("r1").evaluations, 8);
+        assert_eq!(stats("c1").changes, 0);
+        // r1 counts from 1 to 9, toggling 2, 1, 3, 1, 2, 1, 4, 1 bits
+        assert_eq!(stats("r1").changes, 8);
+        assert_eq!(stats("r1").toggles, 15);
+
+        assert_eq!((stats("store").reads, stats("store").writes), (0, 8));
+        assert_eq!((stats("load").reads, stats("load").writes), (8, 0));
+        assert_eq!(profile.write_fraction(stats("store")), 1.0);
+
+        let sorted = profile.sorted(Column::Toggles, true);
+        assert!(["r1", "add"].contains(&sorted[0].id.as_str()));
+        assert_eq!(sorted.last().unwrap().toggles, 0);
+
+        let mut csv = vec![];
+        profile.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), profile.stats.len() + 1);
+        assert!(csv.starts_with("id,evaluations,"));
+    }
+
+    #[test]
+    fn test_time_per_evaluation() {
+        let stats = ComponentStats {
+            evaluations: 1 << 33,
+            time: Duration::from_secs(1 << 33),
+            ..Default::default()
+        };
+        assert_eq!(stats.time_per_evaluation(), Duration::from_secs(1));
+        assert_eq!(
+            ComponentStats::default().time_per_evaluation(),
+            Duration::ZERO
+        );
+    }
+}
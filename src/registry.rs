@@ -0,0 +1,163 @@
+// Registry of the component types supported
+//
+// Each component type declares its name (as tagged in the model), a category, a short
+// description, and a factory creating a default instance. The ports of a type are
+// those of its default instance. The editor library, `--list-components` and the port
+// validation of `Simulator::new` are generated from the registry.
+//
+// The components provided (`crate::components::COMPONENTS`) are always registered, a
+// library registers its own by `register_components`, e.g.:
+//
+// syncrim::registry::register_components(riscv::components::COMPONENTS);
+//
+// Components of a model must have (at least) the ports of the default instance of
+// their type, components with ports given by the model (e.g., the inputs of a mux or
+// the ports of a subcircuit) may have more.
+use crate::common::{Component, ComponentRc, Input, ModelError, Ports};
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::RwLock;
+
+/// Component type, with the metadata of its instances
+#[derive(Debug, Clone, Copy)]
+pub struct ComponentType {
+    /// type name, as tagged in the model (e.g., `"Add"`)
+    pub name: &'static str,
+    /// category, groups component types in the editor library
+    pub category: &'static str,
+    pub description: &'static str,
+    /// shown in the editor library
+    pub library: bool,
+    /// default instance of given id and position, with its inputs unconnected
+    pub new: fn(&str, (f32, f32)) -> ComponentRc,
+}
+
+impl ComponentType {
+    /// ports of the default instance
+    pub fn ports(&self) -> Ports {
+        (self.new)("dummy", (0.0, 0.0)).get_id_ports().1
+    }
+
+    /// check that a component of this type has the ports of the type
+    pub fn check(&self, component: &dyn Component) -> Result<(), ModelError> {
+        let (id, ports) = component.get_id_ports();
+        let declared = self.ports();
+        let inputs = declared.inputs.iter().map(|port| &port.port_id);
+        let missing = inputs
+            .filter(|port| !ports.inputs.iter().any(|p| &p.port_id == *port))
+            .chain(
+                declared
+                    .outputs
+                    .iter()
+                    .filter(|o| !ports.outputs.contains(o)),
+            )
+            .next();
+        match missing {
+            Some(port) => Err(ModelError::MissingPort {
+                id,
+                type_name: self.name.to_string(),
+                port: port.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for ComponentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ports = self.ports();
+        let inputs: Vec<&str> = ports.inputs.iter().map(|p| p.port_id.as_str()).collect();
+        writeln!(f, "{} ({}): {}", self.name, self.category, self.description)?;
+        writeln!(f, "    inputs: {}", inputs.join(", "))?;
+        write!(f, "    outputs: {}", ports.outputs.join(", "))
+    }
+}
+
+// component types registered by libraries
+static COMPONENTS: RwLock<Vec<ComponentType>> = RwLock::new(vec![]);
+
+/// register the component types of a library
+pub fn register_components(component_types: &[ComponentType]) {
+    COMPONENTS
+        .write()
+        .unwrap()
+        .extend_from_slice(component_types);
+}
+
+/// component types supported, by category and name
+pub fn component_types() -> Vec<ComponentType> {
+    #[cfg(feature = "components")]
+    let builtin = crate::components::COMPONENTS;
+    #[cfg(not(feature = "components"))]
+    let builtin: &[ComponentType] = &[];
+    let mut component_types: Vec<ComponentType> = builtin
+        .iter()
+        .chain(COMPONENTS.read().unwrap().iter())
+        .copied()
+        .collect();
+    // listed once if registered more than once, as first registered
+    let mut names = HashSet::new();
+    component_types.retain(|t| names.insert(t.name));
+    component_types.sort_by_key(|t| (t.category, t.name));
+    component_types
+}
+
+/// component type by name
+pub fn component_type(name: &str) -> Option<ComponentType> {
+    component_types().into_iter().find(|t| t.name == name)
+}
+
+/// input of default instances, to be connected in the editor
+pub fn dummy_input() -> Input {
+    Input::new("dummy", "out")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::components::Mux;
+
+    #[test]
+    fn test_registry() {
+        let component_types = component_types();
+        for component_type in &component_types {
+            let component = (component_type.new)("c", (0.0, 0.0));
+            assert_eq!(
+                Component::typetag_name(component.as_ref()),
+                component_type.name
+            );
+            assert!(component.parameters().iter().all(|p| p.check().is_ok()));
+        }
+        assert_eq!(component_type("Add").unwrap().category, "Arithmetic");
+        assert!(component_type("Addd").is_none());
+
+        // registered again, in another category
+        register_components(&[ComponentType {
+            category: "Other",
+            ..component_type("Add").unwrap()
+        }]);
+        let adds = super::component_types()
+            .iter()
+            .filter(|t| t.name == "Add")
+            .count();
+        assert_eq!(adds, 1);
+        assert_eq!(component_type("Add").unwrap().category, "Arithmetic");
+    }
+
+    #[test]
+    fn test_check() {
+        let mux = component_type("Mux").unwrap();
+        let inputs = vec![dummy_input(); 4];
+        let wide = Mux::new("mux", (0.0, 0.0), dummy_input(), inputs);
+        assert_eq!(mux.check(&wide), Ok(()));
+        let narrow = Mux::new("mux", (0.0, 0.0), dummy_input(), vec![dummy_input()]);
+        assert_eq!(
+            mux.check(&narrow),
+            Err(ModelError::MissingPort {
+                id: "mux".to_string(),
+                type_name: "Mux".to_string(),
+                port: "in1".to_string(),
+            })
+        );
+    }
+}
@@ -10,8 +10,12 @@ pub type Id = String;
 
 pub type SignalUnsigned = u32;
 pub type SignalSigned = i32;
+pub type SignalWide = u64;
 pub type SignalBool = bool;
 
+/// Maximum width (in bits) of a signal
+pub const MAX_WIDTH: u8 = SignalWide::BITS as u8;
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Copy, Clone)]
 pub struct Signal {
     data: SignalValue,
@@ -37,12 +41,44 @@ impl Signal {
     }
 }
 
+/// Value of a signal
+///
+/// `Uninitialized`, `Unknown` and `DontCare` carry no data (X), and propagate as
+/// follows in the core components:
+/// - an output depending on an X input is `Unknown` (e.g., `Add`, `Sext`, `Mem` reads),
+/// - a `Mux` with an X select outputs `Unknown`, unless all its inputs agree,
+/// - a `Register` passes its input as is,
+/// - a `Mem` skips writes to an X address or of X data, raising a warning.
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum SignalValue {
+    /// not yet driven (Z)
     Uninitialized,
     Unknown,
     DontCare,
     Data(SignalUnsigned), // Maybe we should have something even more generic here
+    /// Data wider than `SignalUnsigned`, with its width in bits
+    Wide {
+        value: SignalWide,
+        width: u8,
+    },
+}
+
+impl SignalValue {
+    /// data of the given width (at most `MAX_WIDTH`), bits beyond the width cleared
+    ///
+    /// Data fitting `SignalUnsigned` is stored as `Data`, wider data as `Wide`.
+    pub fn from_bits(value: SignalWide, width: u8) -> Self {
+        let width = width.min(MAX_WIDTH);
+        let value = value
+            & SignalWide::MAX
+                .checked_shr((MAX_WIDTH - width) as u32)
+                .unwrap_or(0);
+        if width as u32 <= SignalUnsigned::BITS {
+            SignalValue::Data(value as SignalUnsigned)
+        } else {
+            SignalValue::Wide { value, width }
+        }
+    }
 }
 
 impl TryFrom<Signal> for bool {
@@ -73,10 +109,11 @@ impl TryFrom<SignalValue> for usize {
     type Error = String;
 
     fn try_from(data: SignalValue) -> Result<Self, Self::Error> {
-        if let SignalValue::Data(data) = data {
-            Ok(data as usize)
-        } else {
-            Err(format!("Could not convert {:?} into usize", data))
+        match data {
+            SignalValue::Data(data) => Ok(data as usize),
+            SignalValue::Wide { value, .. } => usize::try_from(value)
+                .map_err(|_| format!("Could not convert {:?} into usize", data)),
+            _ => Err(format!("Could not convert {:?} into usize", data)),
         }
     }
 }
@@ -108,11 +145,35 @@ impl TryFrom<SignalValue> for SignalUnsigned {
     }
 }
 
+impl TryFrom<Signal> for SignalWide {
+    type Error = String;
+
+    fn try_from(signal: Signal) -> Result<Self, Self::Error> {
+        SignalWide::try_from(signal.data)
+    }
+}
+
+impl TryFrom<SignalValue> for SignalWide {
+    type Error = String;
+
+    fn try_from(data: SignalValue) -> Result<Self, Self::Error> {
+        match data {
+            SignalValue::Data(data) => Ok(data as SignalWide),
+            SignalValue::Wide { value, .. } => Ok(value),
+            _ => Err(format!("Could not convert {:?} into SignalWide", data)),
+        }
+    }
+}
+
 impl From<SignalValue> for Signal {
     fn from(data: SignalValue) -> Signal {
+        let size = match data {
+            SignalValue::Wide { .. } => SignalSize::_64,
+            _ => SignalSize::_32,
+        };
         Signal {
             data,
-            fmt: SignalFmt::Hex(SignalSize::_32, false),
+            fmt: SignalFmt::Hex(size, false),
         }
     }
 }
@@ -166,79 +227,116 @@ pub enum SignalFmt {
     Bool,                  // treats it as true/false
 }
 
+impl SignalFmt {
+    /// number of bits covered by the format
+    pub fn bits(&self) -> u8 {
+        match self {
+            SignalFmt::Ascii(size)
+            | SignalFmt::Unsigned(size)
+            | SignalFmt::Signed(size)
+            | SignalFmt::Hex(size, _) => u8::from(*size) * 8,
+            SignalFmt::Binary(bits) => *bits,
+            SignalFmt::Bool => 1,
+        }
+    }
+
+    // format covering at least `width` bits
+    fn widened(self, width: u8) -> Self {
+        if self.bits() >= width {
+            return self;
+        }
+        match self {
+            SignalFmt::Ascii(_) => SignalFmt::Ascii(SignalSize::_64),
+            SignalFmt::Unsigned(_) => SignalFmt::Unsigned(SignalSize::_64),
+            SignalFmt::Signed(_) => SignalFmt::Signed(SignalSize::_64),
+            SignalFmt::Hex(_, pad) => SignalFmt::Hex(SignalSize::_64, pad),
+            SignalFmt::Binary(_) => SignalFmt::Binary(width),
+            SignalFmt::Bool => SignalFmt::Bool,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Copy, Clone, IntoPrimitive)]
 #[repr(u8)]
 pub enum SignalSize {
     _8 = 1,
     _16 = 2,
     _32 = 4,
+    _64 = 8,
 }
 
 impl fmt::Display for Signal {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.data {
-            SignalValue::Data(value) => match self.fmt {
-                SignalFmt::Ascii(signal_size) => {
-                    let s: u8 = signal_size.into();
-
-                    let bytes = &value.to_ne_bytes()[0..s as usize];
-                    let s: String = bytes
-                        .iter()
-                        .map(|b| {
-                            let c = *b as char;
-                            if c.is_ascii_graphic() || c == ' ' {
-                                c
-                            } else {
-                                '¤'
-                            }
-                        })
-                        .rev()
-                        .collect();
-
-                    write!(f, "{}", s)
+        // wide data is never truncated by the format
+        let (value, fmt) = match self.data {
+            SignalValue::Data(value) => (value as SignalWide, self.fmt),
+            SignalValue::Wide { value, width } => (value, self.fmt.widened(width)),
+            _ => return write!(f, "{:?}", self.data),
+        };
+        match fmt {
+            SignalFmt::Ascii(signal_size) => {
+                let s: u8 = signal_size.into();
+
+                let bytes = &value.to_le_bytes()[0..s as usize];
+                let s: String = bytes
+                    .iter()
+                    .map(|b| {
+                        let c = *b as char;
+                        if c.is_ascii_graphic() || c == ' ' {
+                            c
+                        } else {
+                            '¤'
+                        }
+                    })
+                    .rev()
+                    .collect();
+
+                write!(f, "{}", s)
+            }
+            SignalFmt::Binary(size) => {
+                write!(f, "0b{}", &format!("{:064b}", value)[64 - size as usize..])
+            }
+            SignalFmt::Unsigned(size) => write!(
+                f,
+                "{}",
+                match size {
+                    SignalSize::_8 => format!("{}", value as u8),
+                    SignalSize::_16 => format!("{}", value as u16),
+                    SignalSize::_32 => format!("{}", value as u32),
+                    SignalSize::_64 => format!("{}", value),
+                }
+            ),
+            SignalFmt::Signed(size) => write!(
+                f,
+                "{}",
+                match size {
+                    SignalSize::_8 => format!("{}", value as i8),
+                    SignalSize::_16 => format!("{}", value as i16),
+                    SignalSize::_32 => format!("{}", value as i32),
+                    SignalSize::_64 => format!("{}", value as i64),
+                }
+            ),
+            SignalFmt::Hex(size, true) => write!(
+                f,
+                "{}",
+                match size {
+                    SignalSize::_8 => format!("{:#04x}", value as u8),
+                    SignalSize::_16 => format!("{:#06x}", value as u16),
+                    SignalSize::_32 => format!("{:#010x}", value as u32),
+                    SignalSize::_64 => format!("{:#018x}", value),
                 }
-                SignalFmt::Binary(size) => {
-                    write!(f, "0b{}", &format!("{:032b}", value)[32 - size as usize..])
+            ),
+            SignalFmt::Hex(size, false) => write!(
+                f,
+                "{}",
+                match size {
+                    SignalSize::_8 => format!("{:#x}", value as u8),
+                    SignalSize::_16 => format!("{:#x}", value as u16),
+                    SignalSize::_32 => format!("{:#x}", value as u32),
+                    SignalSize::_64 => format!("{:#x}", value),
                 }
-                SignalFmt::Unsigned(size) => write!(
-                    f,
-                    "{}",
-                    match size {
-                        SignalSize::_8 => format!("{}", value as u8),
-                        SignalSize::_16 => format!("{}", value as u16),
-                        SignalSize::_32 => format!("{}", value),
-                    }
-                ),
-                SignalFmt::Signed(size) => write!(
-                    f,
-                    "{}",
-                    match size {
-                        SignalSize::_8 => format!("{}", value as i8),
-                        SignalSize::_16 => format!("{}", value as i16),
-                        SignalSize::_32 => format!("{}", value as i32),
-                    }
-                ),
-                SignalFmt::Hex(size, true) => write!(
-                    f,
-                    "{}",
-                    match size {
-                        SignalSize::_8 => format!("{:#04x}", value as u8),
-                        SignalSize::_16 => format!("{:#06x}", value as u16),
-                        SignalSize::_32 => format!("{:#010x}", value),
-                    }
-                ),
-                SignalFmt::Hex(size, false) => write!(
-                    f,
-                    "{}",
-                    match size {
-                        SignalSize::_8 => format!("{:#x}", value as u8),
-                        SignalSize::_16 => format!("{:#x}", value as u16),
-                        SignalSize::_32 => format!("{:#x}", value),
-                    }
-                ),
-                SignalFmt::Bool => write!(f, "{}", value != 0),
-            },
-            _ => write!(f, "{:?}", self.data),
+            ),
+            SignalFmt::Bool => write!(f, "{}", value != 0),
         }
     }
 }
@@ -398,4 +496,42 @@ mod test {
         println!("{}", s);
         assert_eq!(s, "¤");
     }
+
+    #[test]
+    fn test_wide() {
+        let value = SignalValue::from_bits(0x1_2345_6789, 40);
+        assert_eq!(
+            value,
+            SignalValue::Wide {
+                value: 0x1_2345_6789,
+                width: 40
+            }
+        );
+        assert_eq!(SignalWide::try_from(value), Ok(0x1_2345_6789));
+        assert!(SignalUnsigned::try_from(value).is_err());
+
+        // narrow data is stored as `Data`, truncated to its width
+        assert_eq!(SignalValue::from_bits(0x1_2345_6789, 16), 0x6789.into());
+        assert_eq!(
+            SignalValue::from_bits(u64::MAX, 64),
+            SignalValue::Wide {
+                value: u64::MAX,
+                width: 64
+            }
+        );
+
+        // wide data is not truncated by a narrower format
+        let mut signal: Signal = value.into();
+        assert_eq!(format!("{}", signal), "0x123456789");
+        signal.set_fmt(SignalFmt::Hex(SignalSize::_32, true));
+        assert_eq!(format!("{}", signal), "0x0000000123456789");
+        signal.set_fmt(SignalFmt::Binary(8));
+        assert_eq!(
+            format!("{}", signal),
+            format!("0b{:040b}", 0x1_2345_6789u64)
+        );
+        signal.set_fmt(SignalFmt::Signed(SignalSize::_64));
+        signal.set_value(SignalValue::from_bits(u64::MAX, 64));
+        assert_eq!(format!("{}", signal), "-1");
+    }
 }
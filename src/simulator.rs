@@ -1,39 +1,77 @@
 use crate::common::{
-    Component, ComponentStore, Condition, Id, Input, OutputType, Signal, SignalFmt, SignalValue,
-    Simulator,
+    Component, ComponentStore, Components, Condition, Id, IdFieldSimIndex, Input, ModelError,
+    ModelErrors, OutputType, Signal, SignalFmt, SignalIndex, SignalUnsigned, SignalValue,
+    SignalWrite, Simulator,
 };
+use crate::dot::DotOptions;
+use crate::history::History;
+use crate::simulator_thread::SimulatorThread;
+use crate::speed::Speed;
+use crate::state::CHECKPOINT_INTERVAL;
+use crate::timing::CriticalPath;
+use crate::watchpoint::Watchpoint;
 use log::*;
 use petgraph::{
-    algo::toposort,
-    dot::{Config, Dot},
+    algo::{tarjan_scc, toposort},
     Graph,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::{fs::File, io::prelude::*, path::PathBuf};
 
 pub struct IdComponent(pub HashMap<String, Box<dyn Component>>);
 
-// Notice:
-// The topological order does not enforce any specific order of registers
-// Thus registers cannot point to other registers in a cyclic fashion
-// This is (likely) not occurring in practice.
-//
-// A solution is to evaluate register updates separately from other components
-// ... but not currently implemented ...
+// maximum nesting of subcircuits, exceeded for recursive models
+const MAX_DEPTH: usize = 32;
+
+// flatten hierarchical components recursively
+fn flatten(components: Components, depth: usize, errors: &mut Vec<ModelError>) -> Components {
+    let mut flat = vec![];
+    for c in components {
+        match c.flatten() {
+            None => flat.push(c),
+            Some(Ok(inner)) if depth < MAX_DEPTH => flat.extend(flatten(inner, depth + 1, errors)),
+            Some(Ok(_)) => errors.push(ModelError::SubCircuit {
+                id: c.get_id_ports().0,
+                error: format!("nested more than {} levels deep", MAX_DEPTH),
+            }),
+            Some(Err(error)) => errors.push(ModelError::SubCircuit {
+                id: c.get_id_ports().0,
+                error,
+            }),
+        }
+    }
+    flat
+}
+
+// Clocking is done in two phases:
+// First all sequential components sample their inputs from the previous state,
+// their output writes are deferred and committed at once when all have been clocked.
+// Thus sequential components may be chained (e.g., shift registers, pipeline stages)
+// regardless of their relative order.
+// Then the combinatorial components are evaluated in topological order.
 impl Simulator {
-    pub fn new(component_store: ComponentStore) -> Result<Self, &'static str> {
+    pub fn new(component_store: ComponentStore) -> Result<Self, ModelErrors> {
+        // all errors in the model are collected and reported at once
+        let mut errors = vec![];
+
+        // flatten hierarchical components, keeping the model as given for the gui
+        let components = component_store.store;
+        let delays = component_store.delays;
+        let component_store = ComponentStore::new(flatten(components.clone(), 0, &mut errors));
+
         for component in &component_store.store {
             component.reset();
         }
         let mut lens_values = vec![];
+        let mut widths = vec![];
 
         let mut id_start_index = HashMap::new();
         let mut id_component = HashMap::new(); // IdComponent(HashMap::new());
 
         let mut id_nr_outputs = HashMap::new();
         let mut id_field_index = HashMap::new();
-        // allocate storage for lensed outputs
 
+        // allocate storage for lensed outputs
         trace!("-- allocate storage for lensed outputs");
         for c in &component_store.store {
             trace!("{:?}", c.get_id_ports().0);
@@ -41,12 +79,11 @@ impl Simulator {
 
             trace!("id {}, ports {:?}", id, ports);
             // start index for outputs related to component
-            if id_start_index
-                .insert(id.clone(), lens_values.len())
-                .is_some()
-            {
-                panic!("Component identifier {:?} is defined twice", id);
+            if id_start_index.contains_key(&id) {
+                errors.push(ModelError::DuplicateId(id));
+                continue;
             }
+            id_start_index.insert(id.clone(), lens_values.len());
 
             id_component.insert(id.clone(), c);
 
@@ -55,16 +92,72 @@ impl Simulator {
             for (index, field_id) in ports.outputs.iter().enumerate() {
                 // create the value with a default to 0
                 lens_values.push(0.into());
+                widths.push(ports.output_width(field_id));
                 if id_field_index
                     .insert((id.clone(), field_id.into()), index)
                     .is_some()
                 {
-                    panic!("Component {:?} field {:?} is defined twice", id, field_id)
+                    errors.push(ModelError::DuplicateField {
+                        id: id.clone(),
+                        field: field_id.clone(),
+                    });
                 };
             }
             id_nr_outputs.insert(id.clone(), ports.outputs.len());
         }
 
+        // check that components have the ports of their types, see `registry`
+        let component_types = crate::registry::component_types();
+        for c in &component_store.store {
+            let name = Component::typetag_name(c.as_ref());
+            if let Some(component_type) = component_types.iter().find(|t| t.name == name) {
+                if let Err(e) = component_type.check(c.as_ref()) {
+                    errors.push(e);
+                }
+            }
+        }
+
+        // check that all inputs refer to existing outputs of the declared width
+        for c in &component_store.store {
+            let (id, ports) = c.get_id_ports();
+            for in_port in &ports.inputs {
+                let input = &in_port.input;
+                let Some(start_index) = id_start_index.get(&input.id) else {
+                    errors.push(ModelError::UnknownComponent {
+                        id: id.clone(),
+                        port: in_port.port_id.clone(),
+                        input: input.clone(),
+                    });
+                    continue;
+                };
+                let Some(index) = id_field_index.get(&(input.id.clone(), input.field.clone()))
+                else {
+                    errors.push(ModelError::UnknownField {
+                        id: id.clone(),
+                        port: in_port.port_id.clone(),
+                        input: input.clone(),
+                    });
+                    continue;
+                };
+                if let (Some(width), Some(input_width)) = (
+                    ports.input_width(&in_port.port_id),
+                    widths[start_index + index],
+                ) {
+                    // up to 32 bits, signals are `Data` and narrower ones zero extended
+                    let data = |width| width <= SignalUnsigned::BITS as u8;
+                    if width != input_width && !(data(width) && data(input_width)) {
+                        errors.push(ModelError::WidthMismatch {
+                            id: id.clone(),
+                            port: in_port.port_id.clone(),
+                            width,
+                            input: input.clone(),
+                            input_width,
+                        });
+                    }
+                }
+            }
+        }
+
         let mut graph = Graph::<_, (), petgraph::Directed>::new();
         let mut id_node = HashMap::new();
         let mut node_comp = HashMap::new();
@@ -95,12 +188,10 @@ impl Simulator {
                 let (_, ports) = c.get_id_ports();
                 for in_port in &ports.inputs {
                     let from_id = &in_port.input.id;
-                    let from_node = id_node.get(from_id);
-                    if from_node.is_none() {
-                        println!("to id: {} from port {} is not connected", to_id, from_id);
-                        return Err("A port left unconnected");
-                    }
-                    let from_node = from_node.unwrap();
+                    // unconnected ports are already reported
+                    let Some(from_node) = id_node.get(from_id) else {
+                        continue;
+                    };
 
                     graph.add_edge(*from_node, *to_node, ());
                     trace!(
@@ -115,8 +206,29 @@ impl Simulator {
         }
 
         // topological order
-        let top =
-            toposort(&graph, None).expect("Topological sort failed, your model contains loops.");
+        let top = match toposort(&graph, None) {
+            Ok(top) => top,
+            Err(_) => {
+                // report each strongly connected component forming a loop
+                for scc in tarjan_scc(&graph) {
+                    let is_loop = scc.len() > 1 || graph.contains_edge(scc[0], scc[0]);
+                    if is_loop {
+                        let mut ids: Vec<Id> =
+                            scc.iter().map(|node| graph[*node].clone()).collect();
+                        ids.sort();
+                        errors.push(ModelError::CombinationalLoop(ids));
+                    }
+                }
+                vec![]
+            }
+        };
+
+        if !errors.is_empty() {
+            for e in &errors {
+                error!("{}", e);
+            }
+            return Err(ModelErrors(errors));
+        }
         trace!("--- topologically ordered graph \n{:?}", top);
         //two passes, first add all sequential roots
         let mut ordered_components = vec![];
@@ -129,6 +241,7 @@ impl Simulator {
                 ordered_components.push(c);
             }
         }
+        let nr_sequential = ordered_components.len();
         //then the rest...
         for node in &top {
             #[allow(suspicious_double_ref_op)]
@@ -148,19 +261,63 @@ impl Simulator {
             component_ids
         );
 
+        // resolve (id, field) to flat `sim_state` indices once and for all
+        let mut id_field_sim_index: IdFieldSimIndex = HashMap::new();
+        for ((id, field), index) in &id_field_index {
+            id_field_sim_index
+                .entry(id.clone())
+                .or_default()
+                .insert(field.clone(), id_start_index[id] + index);
+        }
+
+        // resolve the inputs of each component, so clocking needs no lookups
+        let input_indices = ordered_components
+            .iter()
+            .map(|c| {
+                c.get_id_ports()
+                    .1
+                    .inputs
+                    .iter()
+                    .map(|port| {
+                        let input = &port.input;
+                        SignalIndex(id_field_sim_index[&input.id][&input.field])
+                    })
+                    .collect()
+            })
+            .collect();
+
         let mut simulator = Simulator {
             cycle: 0,
             id_start_index,
+            components,
             ordered_components,
             id_nr_outputs,
             id_field_index,
             sim_state: lens_values,
-            history: vec![],
+            widths,
+            history: History::default(),
             component_ids,
             graph,
+            id_field_sim_index,
+            input_indices,
+            clocking: 0,
+            nr_sequential,
+            pending_writes: None,
+            forced: HashMap::new(),
+            drives: vec![],
+            watchpoints: vec![],
+            watchpoint_hits: vec![],
+            checkpoints: BTreeMap::new(),
+            checkpoint_interval: CHECKPOINT_INTERVAL,
+            profile: None,
+            delays,
+            critical_path: CriticalPath::default(),
+            incremental: None,
             running: false,
+            component_condition: vec![],
         };
 
+        simulator.critical_path = simulator.find_critical_path();
         trace!("sim_state {:?}", simulator.sim_state);
         simulator.clock();
         Ok(simulator)
@@ -171,33 +328,59 @@ impl Simulator {
         self.sim_state[index]
     }
 
+    /// resolve input to its index in `sim_state`
+    ///
+    /// Components may keep the returned index and read through `get_signal`,
+    /// the index stays valid as long as the simulator is not rebuilt.
+    pub fn resolve_input(&self, input: &Input) -> Option<SignalIndex> {
+        self.id_field_sim_index
+            .get(&input.id)?
+            .get(&input.field)
+            .map(|index| SignalIndex(*index))
+    }
+
+    /// get signal by resolved index
+    pub fn get_signal(&self, index: SignalIndex) -> Signal {
+        self.sim_state[index.0]
+    }
+
+    /// get declared width of signal by resolved index
+    pub fn get_width(&self, index: SignalIndex) -> Option<u8> {
+        self.widths[index.0]
+    }
+
     /// get input signal
+    ///
+    /// The `(id, field)` of the input is looked up (by `&str`, without allocation) in
+    /// the indices resolved when the simulator was built, see `resolve_input`.
     pub fn get_input_signal(&self, input: &Input) -> Signal {
-        #[allow(unreachable_code)]
-        let nr_out = *self
-            .id_nr_outputs
-            .get(&input.id)
-            .unwrap_or_else(|| panic!("\n{:?} not found in \n{:?}", input, self.id_nr_outputs));
-        let index = *self
-            .id_field_index
-            .get(&(input.id.clone(), input.field.clone()))
-            .unwrap_or_else(|| {
+        match self.resolve_input(input) {
+            Some(index) => self.get(index.0),
+            None => {
+                if !self.id_nr_outputs.contains_key(&input.id) {
+                    panic!("\n{:?} not found in \n{:?}", input, self.id_nr_outputs)
+                }
                 panic!(
                     "Component {:?}, field {:?} not found.",
                     input.id, input.field
                 )
-            });
-        if index < nr_out {
-            let start_index = *self.id_start_index.get(&input.id).unwrap();
-            self.get(start_index + index)
-        } else {
-            panic!(
-                "ICE: Attempt to read {:?} at index {}, where {:?} has only {} outputs.",
-                input.id, index, input.id, nr_out
-            )
+            }
         }
     }
 
+    /// get signal of input `port` of the component being clocked
+    ///
+    /// The `port` is the position of the input in `Ports::inputs`, resolved to its
+    /// index in `sim_state` when the simulator was built. Only valid from `clock`.
+    pub fn get_port_signal(&self, port: usize) -> Signal {
+        self.get(self.input_indices[self.clocking][port].0)
+    }
+
+    /// get value of input `port` of the component being clocked, see `get_port_signal`
+    pub fn get_port_value(&self, port: usize) -> SignalValue {
+        self.get_port_signal(port).get_value()
+    }
+
     /// get input value
     pub fn get_input_value(&self, input: &Input) -> SignalValue {
         self.get_input_signal(input).get_value()
@@ -209,68 +392,215 @@ impl Simulator {
     }
 
     /// get start index by id
-    pub(crate) fn get_id_start_index(&self, id: &str) -> usize {
+    pub fn get_id_start_index(&self, id: &str) -> usize {
         *self.id_start_index.get(id).unwrap()
     }
 
-    // set value by index
+    // set value by index, deferred while sequential components are sampling
     fn set_value(&mut self, index: usize, value: SignalValue) {
-        self.sim_state[index].set_value(value);
+        let value = match self.forced.is_empty() {
+            true => value,
+            false => *self.forced.get(&index).unwrap_or(&value),
+        };
+        match &mut self.pending_writes {
+            Some(writes) => writes.push((index, SignalWrite::Value(value))),
+            None => self.write(index, SignalWrite::Value(value)),
+        }
     }
 
-    // set fmt by index
+    // set fmt by index, deferred while sequential components are sampling
     fn set_fmt(&mut self, index: usize, fmt: SignalFmt) {
-        self.sim_state[index].set_fmt(fmt);
+        match &mut self.pending_writes {
+            Some(writes) => writes.push((index, SignalWrite::Fmt(fmt))),
+            None => self.write(index, SignalWrite::Fmt(fmt)),
+        }
+    }
+
+    // write to `sim_state`, tracking changes if evaluating incrementally
+    fn write(&mut self, index: usize, write: SignalWrite) {
+        let old = self.sim_state[index];
+        match write {
+            SignalWrite::Value(value) => self.sim_state[index].set_value(value),
+            SignalWrite::Fmt(fmt) => self.sim_state[index].set_fmt(fmt),
+        }
+        if let Some(incremental) = self.incremental.as_mut() {
+            if self.sim_state[index] != old {
+                incremental.changed[index] = true;
+            }
+        }
+    }
+
+    // commit writes deferred during sampling
+    fn commit_writes(&mut self) {
+        let Some(writes) = self.pending_writes.take() else {
+            return;
+        };
+        for (index, write) in writes {
+            self.write(index, write);
+        }
+        // driven outputs, seen by combinational components only
+        for (index, value) in std::mem::take(&mut self.drives) {
+            self.forced.insert(index, value);
+            self.write(index, SignalWrite::Value(value));
+        }
+    }
+
+    // get index by Id (instance) and Id (field)
+    fn get_out_index(&self, id: &str, field: &str) -> usize {
+        *self
+            .id_field_sim_index
+            .get(id)
+            .and_then(|fields| fields.get(field))
+            .unwrap_or_else(|| panic!("Component {}, field {} not found.", id, field))
     }
 
     /// set value by Id (instance) and Id (field)
     pub fn set_out_value(&mut self, id: &str, field: &str, value: impl Into<SignalValue>) {
-        let index = *self
-            .id_field_index
-            .get(&(id.into(), field.into()))
-            .unwrap_or_else(|| panic!("Component {}, field {} not found.", id, field));
-        let start_index = self.get_id_start_index(id);
+        let index = self.get_out_index(id, field);
         let val: SignalValue = value.into();
         //trace!("id:{}, field:{}, value:{:?}", id,field, SignalValue::try_from(val).unwrap());
-        self.set_value(start_index + index, val);
+        self.set_value(index, val);
     }
 
     /// set fmt by Id (instance) and Id (field)
     pub fn set_out_fmt(&mut self, id: &str, field: &str, fmt: SignalFmt) {
-        let index = *self
-            .id_field_index
-            .get(&(id.into(), field.into()))
-            .unwrap_or_else(|| panic!("Component {}, field {} not found.", id, field));
-        let start_index = self.get_id_start_index(id);
-        self.set_fmt(start_index + index, fmt);
+        let index = self.get_out_index(id, field);
+        self.set_fmt(index, fmt);
     }
 
     /// iterate over the evaluators and increase clock by one
     pub fn clock(&mut self) {
+        if self.cycle.is_multiple_of(self.checkpoint_interval) {
+            self.trim_checkpoints();
+            self.checkpoint();
+        }
         // push current state
-        self.history.push(self.sim_state.clone());
+        self.history.push(&self.sim_state);
+        let previous = self.profile.is_some().then(|| self.sim_state.clone());
+        self.component_condition.clear();
+        // watched signals and the conditions holding before the clock
+        let watched: Vec<(Option<SignalValue>, bool)> = self
+            .watchpoints
+            .iter()
+            .map(|w| {
+                let previous = w.signal().map(|input| self.get_input_value(input));
+                (previous, w.holds(self))
+            })
+            .collect();
         trace!("cycle:{}", self.cycle);
-        for component in self.ordered_components.clone() {
+        // sequential components sample the previous state
+        self.pending_writes = Some(vec![]);
+        for (i, component) in self.ordered_components.clone().iter().enumerate() {
+            if i == self.nr_sequential {
+                self.commit_writes();
+            }
             //trace!("evaling component:{}", component.get_id_ports().0);
-            match component.clock(self) {
-                Ok(_) => {}
-                Err(cond) => match cond {
-                    Condition::Warning(warn) => {
-                        trace!("warning {}", warn)
+            if let Some(incremental) = self.incremental.as_mut() {
+                if let Some(condition) = incremental.skip(i) {
+                    if let Some(cond) = condition {
+                        self.component_condition
+                            .push((component.get_id_ports().0, cond));
                     }
-                    Condition::Error(err) => panic!("err {}", err),
-                    Condition::Assert(assert) => {
-                        error!("assertion failed {}", assert);
-                        self.running = false;
-                    }
-                    Condition::Halt(halt) => {
-                        self.running = false;
-                        info!("halt {}", halt)
+                    continue;
+                }
+            }
+            let start = self.profile.as_mut().map(|profile| profile.start(i));
+            self.clocking = i;
+            let result = component.clock(self);
+            if let (Some(profile), Some(start)) = (self.profile.as_mut(), start) {
+                profile.end(start);
+            }
+            if let Some(incremental) = self.incremental.as_mut() {
+                incremental.conditions[i] = result.clone().err();
+            }
+            match result {
+                Ok(_) => {}
+                Err(cond) => {
+                    match &cond {
+                        Condition::Warning(warn) => {
+                            trace!("warning {}", warn)
+                        }
+                        Condition::Error(err) => {
+                            error!("err {}", err);
+                            self.running = false;
+                        }
+                        Condition::Assert(assert) => {
+                            error!("assertion failed {}", assert);
+                            self.running = false;
+                        }
+                        Condition::Halt(halt) => {
+                            self.running = false;
+                            info!("halt {}", halt)
+                        }
                     }
-                },
+                    self.component_condition
+                        .push((component.get_id_ports().0, cond));
+                }
+            }
+        }
+        // in case there are only sequential components
+        self.commit_writes();
+        if let Some(incremental) = self.incremental.as_mut() {
+            incremental.cycle_done();
+        }
+        if let (Some(profile), Some(previous)) = (self.profile.as_mut(), previous) {
+            profile.cycle(&previous, &self.sim_state);
+        }
+        self.cycle = self.history.end();
+
+        self.watchpoint_hits = (0..self.watchpoints.len())
+            .filter(|i| {
+                let (previous, held) = watched[*i];
+                self.watchpoints[*i].hit(self, previous, held)
+            })
+            .collect();
+        if !self.watchpoint_hits.is_empty() {
+            info!("watchpoint hit {:?}", self.watchpoint_hits);
+            self.running = false;
+        }
+    }
+
+    /// clock until `until` holds, a watchpoint is hit, or a component raises a
+    /// condition other than a warning, returns the number of cycles run
+    pub fn run_until(&mut self, mut until: impl FnMut(&Simulator) -> bool) -> usize {
+        let start = self.cycle;
+        loop {
+            self.clock();
+            let stopped = self
+                .component_condition
+                .iter()
+                .any(|(_, condition)| !matches!(condition, Condition::Warning(_)));
+            if stopped || !self.watchpoint_hits.is_empty() || until(self) {
+                return self.cycle - start;
             }
         }
-        self.cycle = self.history.len();
+    }
+
+    /// add watchpoint, stopping the simulation when hit
+    pub fn add_watchpoint(&mut self, watchpoint: Watchpoint) -> Result<(), String> {
+        if let Some(input) = watchpoint.signal() {
+            if self.resolve_input(input).is_none() {
+                return Err(format!("Signal {} not found in model", input));
+            }
+        }
+        self.watchpoints.push(watchpoint);
+        Ok(())
+    }
+
+    /// remove watchpoint by index
+    pub fn remove_watchpoint(&mut self, index: usize) -> Watchpoint {
+        self.watchpoint_hits.clear();
+        self.watchpoints.remove(index)
+    }
+
+    /// watchpoints, in the order added
+    pub fn watchpoints(&self) -> &[Watchpoint] {
+        &self.watchpoints
+    }
+
+    /// indices of the watchpoints hit by the last clock
+    pub fn watchpoint_hits(&self) -> &[usize] {
+        &self.watchpoint_hits
     }
 
     /// free running mode until Halt condition
@@ -285,7 +615,11 @@ impl Simulator {
         }
     }
 
-    pub fn run_threaded(&mut self) {}
+    /// free running mode in a background thread, see `SimulatorThread`
+    pub fn run_threaded(mut self, speed: Speed) -> SimulatorThread {
+        self.running = true;
+        SimulatorThread::spawn(self, speed)
+    }
 
     /// stop the simulator from gui or other external reason
     pub fn stop(&mut self) {
@@ -293,13 +627,20 @@ impl Simulator {
     }
 
     /// reverse simulation using history if clock > 1
+    ///
+    /// Only the last `history.depth()` cycles can be reversed.
     pub fn un_clock(&mut self) {
         if self.cycle > 1 {
-            let state = self.history.pop().unwrap();
+            let Some(state) = self.history.pop() else {
+                warn!("cycle {} is beyond history depth", self.cycle - 1);
+                return;
+            };
             // set old state
             self.sim_state = state;
+            self.mark_all_changed();
             // to ensure that history length and cycle count complies
-            self.cycle = self.history.len();
+            self.cycle = self.history.end();
+            self.checkpoints.split_off(&(self.cycle + 1));
 
             for component in self.ordered_components.clone() {
                 component.un_clock();
@@ -309,31 +650,71 @@ impl Simulator {
 
     /// reset simulator
     pub fn reset(&mut self) {
-        self.history = vec![];
+        self.history.clear();
+        self.checkpoints.clear();
+        if self.profile.is_some() {
+            self.set_profiling(true);
+        }
+        self.component_condition.clear();
         self.cycle = 0;
         self.sim_state.iter_mut().for_each(|val| *val = 0.into());
+        for (index, value) in &self.forced {
+            self.sim_state[*index].set_value(*value);
+        }
         self.stop();
-        self.clock();
+        self.mark_all_changed();
 
+        // reset internal state before the initial clock, which may update it
         for component in self.ordered_components.clone() {
             component.reset();
         }
+        self.clock();
+    }
+
+    /// drive output to value in the current state, overriding the value set by its
+    /// component until released
+    pub fn force_signal(&mut self, index: SignalIndex, value: SignalValue) {
+        self.forced.insert(index.0, value);
+        self.sim_state[index.0].set_value(value);
+        self.mark_all_changed();
+    }
+
+    /// force output from the next clock on
+    ///
+    /// Unlike `force_signal`, the current state (and thus its history) is kept, and
+    /// sequential components sample the current value on the next clock, i.e., the
+    /// driven value is seen from the next cycle, as if set by its component.
+    pub fn drive_signal(&mut self, index: SignalIndex, value: SignalValue) {
+        self.drives.push((index.0, value));
+    }
+
+    /// release forced output, the value set by its component applies from next clock
+    pub fn release_signal(&mut self, index: SignalIndex) {
+        self.forced.remove(&index.0);
+        self.mark_all_changed();
+    }
+
+    /// limit the number of cycles kept in history, unbounded if `None`
+    pub fn set_history_depth(&mut self, depth: Option<usize>) {
+        self.history.set_depth(depth);
     }
 
     pub fn get_state(&self) -> bool {
         self.running
     }
 
-    /// save as `dot` file with `.gv` extension
+    /// save history as `vcd` (Value Change Dump) file
+    pub fn save_vcd(&self, path: &PathBuf) -> std::io::Result<()> {
+        let mut file = std::io::BufWriter::new(File::create(path)?);
+        crate::vcd::write_vcd(self, &mut file)?;
+        file.flush()
+    }
+
+    /// save model graph as `dot` file with `.gv` extension, see `DotOptions`
     pub fn save_dot(&self, path: &PathBuf) {
         let mut path = path.to_owned();
         path.set_extension("gv");
-        let mut file = File::create(path).unwrap();
-        let dot_string = format!(
-            "{:?}",
-            Dot::with_config(&self.graph, &[Config::EdgeNoLabel])
-        );
-        file.write_all(dot_string.as_bytes()).unwrap();
+        self.save_dot_with(&path, &DotOptions::default()).unwrap();
     }
 }
 
@@ -341,13 +722,11 @@ impl Simulator {
 mod test {
     use super::*;
     use crate::components::*;
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     #[test]
     fn test_define() {
-        let cs = ComponentStore {
-            store: vec![Rc::new(ProbeOut::new("po1"))],
-        };
+        let cs = ComponentStore::new(vec![Arc::new(ProbeOut::new("po1"))]);
 
         let simulator = Simulator::new(cs).unwrap();
 
@@ -355,22 +734,140 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Component identifier \"po1\" is defined twice")]
     fn test_redefined() {
-        let cs = ComponentStore {
-            store: vec![Rc::new(ProbeOut::new("po1")), Rc::new(ProbeOut::new("po1"))],
-        };
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("po1")),
+            Arc::new(ProbeOut::new("po1")),
+        ]);
 
-        let simulator = Simulator::new(cs).unwrap();
+        let errors = Simulator::new(cs).err().unwrap();
 
-        assert_eq!(simulator.cycle, 1);
+        assert_eq!(errors.0, vec![ModelError::DuplicateId("po1".into())]);
+        assert!(errors
+            .to_string()
+            .contains("Component identifier \"po1\" is defined twice"));
+    }
+
+    #[test]
+    fn test_unknown_input() {
+        let cs = ComponentStore::new(vec![
+            Arc::new(ProbeOut::new("po1")),
+            Arc::new(Probe::new("p1", (0.0, 0.0), Input::new("missing", "out"))),
+            Arc::new(Probe::new("p2", (0.0, 0.0), Input::new("po1", "missing"))),
+            Register::arc_new("r", (0.0, 0.0), Input::new("missing", "out")),
+        ]);
+
+        let errors = Simulator::new(cs).err().unwrap().0;
+
+        // all errors are reported, in store order
+        assert_eq!(
+            errors,
+            vec![
+                ModelError::UnknownComponent {
+                    id: "p1".into(),
+                    port: PROBE_IN_ID.into(),
+                    input: Input::new("missing", "out")
+                },
+                ModelError::UnknownField {
+                    id: "p2".into(),
+                    port: PROBE_IN_ID.into(),
+                    input: Input::new("po1", "missing")
+                },
+                ModelError::UnknownComponent {
+                    id: "r".into(),
+                    port: REGISTER_R_IN_ID.into(),
+                    input: Input::new("missing", "out")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_combinational_loop() {
+        let cs = ComponentStore::new(vec![
+            Arc::new(Constant::new("c", (0.0, 0.0), 1)),
+            Add::arc_new(
+                "add1",
+                (0.0, 0.0),
+                Input::new("c", "out"),
+                Input::new("add2", "out"),
+            ),
+            Add::arc_new(
+                "add2",
+                (0.0, 0.0),
+                Input::new("c", "out"),
+                Input::new("add1", "out"),
+            ),
+            // loops through registers are fine
+            Add::arc_new(
+                "add3",
+                (0.0, 0.0),
+                Input::new("c", "out"),
+                Input::new("r", "out"),
+            ),
+            Register::arc_new("r", (0.0, 0.0), Input::new("add3", "out")),
+        ]);
+
+        let errors = Simulator::new(cs).err().unwrap().0;
+
+        assert_eq!(
+            errors,
+            vec![ModelError::CombinationalLoop(vec![
+                "add1".into(),
+                "add2".into()
+            ])]
+        );
+    }
+
+    #[test]
+    fn test_width_mismatch() {
+        let cs = ComponentStore::new(vec![
+            Arc::new(Constant::new("c", (0.0, 0.0), 1)),
+            Sext::arc_new("sext", (0.0, 0.0), Input::new("c", "out"), 8, 16),
+            Sext::arc_new("sext64", (0.0, 0.0), Input::new("c", "out"), 8, 64),
+            // narrower data is accepted, as is any width by undeclared ports
+            Add::arc_new(
+                "add0",
+                (0.0, 0.0),
+                Input::new("sext", "out"),
+                Input::new("c", "out"),
+            ),
+            Add::arc_new(
+                "add1",
+                (0.0, 0.0),
+                Input::new("add0", "out"),
+                Input::new("add0", "overflow"),
+            ),
+            Register::arc_new("r", (0.0, 0.0), Input::new("sext64", "out")),
+            Add::arc_new(
+                "add2",
+                (0.0, 0.0),
+                Input::new("sext64", "out"),
+                Input::new("add1", "out"),
+            ),
+        ]);
+
+        let errors = Simulator::new(cs).err().unwrap().0;
+
+        assert_eq!(
+            errors,
+            vec![ModelError::WidthMismatch {
+                id: "add2".into(),
+                port: ADD_A_IN_ID.into(),
+                width: 32,
+                input: Input::new("sext64", "out"),
+                input_width: 64,
+            }]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "Component \"add2\" port \"a_in\" (32 bits) is connected to sext64/out (64 bits)"
+        );
     }
 
     #[test]
     fn test_get_input_val() {
-        let cs = ComponentStore {
-            store: vec![Rc::new(ProbeOut::new("po1"))],
-        };
+        let cs = ComponentStore::new(vec![Arc::new(ProbeOut::new("po1"))]);
 
         let simulator = Simulator::new(cs).unwrap();
 
@@ -381,9 +878,7 @@ mod test {
     #[test]
     #[should_panic(expected = "Component \"po1\", field \"missing\" not found.")]
     fn test_get_input_out_of_range() {
-        let cs = ComponentStore {
-            store: vec![Rc::new(ProbeOut::new("po1"))],
-        };
+        let cs = ComponentStore::new(vec![Arc::new(ProbeOut::new("po1"))]);
 
         let simulator = Simulator::new(cs).unwrap();
 
@@ -391,11 +886,169 @@ mod test {
         let _ = simulator.get_input_value(&Input::new("po1", "missing"));
     }
 
+    #[test]
+    fn test_resolve_input() {
+        let cs1 = ComponentStore::new(vec![
+            Constant::arc_new("c1", (0.0, 0.0), 1),
+            Constant::arc_new("c2", (0.0, 0.0), 2),
+        ]);
+        let cs2 = ComponentStore::new(vec![
+            Constant::arc_new("c2", (0.0, 0.0), 3),
+            Constant::arc_new("c1", (0.0, 0.0), 4),
+        ]);
+        let simulator1 = Simulator::new(cs1).unwrap();
+        let simulator2 = Simulator::new(cs2).unwrap();
+
+        // the same input read from simulators with different layouts
+        let input = Input::new("c2", "out");
+        assert_eq!(simulator1.get_input_value(&input), 2.into());
+        assert_eq!(simulator2.get_input_value(&input), 3.into());
+        assert_eq!(simulator1.get_input_value(&input), 2.into());
+
+        let index = simulator1.resolve_input(&input).unwrap();
+        assert_eq!(simulator1.get_signal(index).get_value(), 2.into());
+        assert!(simulator1
+            .resolve_input(&Input::new("c2", "missing"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_input_indices() {
+        let simulator = crate::fixtures::counter();
+
+        // the inputs of each component are resolved in the order of its ports
+        for (c, indices) in simulator
+            .ordered_components
+            .iter()
+            .zip(&simulator.input_indices)
+        {
+            let resolved: Vec<SignalIndex> = c
+                .get_id_ports()
+                .1
+                .inputs
+                .iter()
+                .map(|port| simulator.resolve_input(&port.input).unwrap())
+                .collect();
+            assert_eq!(indices, &resolved);
+        }
+    }
+
+    #[test]
+    fn test_history_depth() {
+        let cs = ComponentStore::new(vec![ProbeStim::arc_new(
+            "stim",
+            (0.0, 0.0),
+            (0..1000).collect(),
+        )]);
+        let input = Input::new("stim", "out");
+
+        let mut simulator = Simulator::new(cs).unwrap();
+        simulator.set_history_depth(Some(10));
+        for _ in 0..100 {
+            simulator.clock();
+        }
+        assert_eq!(simulator.cycle, 101);
+        assert_eq!(simulator.history.len(), 10);
+        assert_eq!(simulator.get_input_value(&input), 100.into());
+
+        // the last 10 cycles are reversible
+        for cycle in (91..101).rev() {
+            simulator.un_clock();
+            assert_eq!(simulator.cycle, cycle);
+            assert_eq!(simulator.get_input_value(&input), (cycle as u32 - 1).into());
+        }
+        simulator.un_clock();
+        assert_eq!(simulator.cycle, 91);
+
+        // clocking on from the oldest state kept
+        simulator.clock();
+        assert_eq!(simulator.cycle, 92);
+        assert_eq!(simulator.get_input_value(&input), 91.into());
+
+        simulator.reset();
+        assert_eq!(simulator.cycle, 1);
+        assert_eq!(simulator.history.len(), 1);
+    }
+
+    #[test]
+    fn test_shift_register() {
+        // registers are deliberately listed in reverse order
+        let cs = ComponentStore::new(vec![
+            Register::arc_new("r3", (0.0, 0.0), Input::new("r2", "out")),
+            Register::arc_new("r2", (0.0, 0.0), Input::new("r1", "out")),
+            Register::arc_new("r1", (0.0, 0.0), Input::new("stim", "out")),
+            ProbeStim::arc_new("stim", (0.0, 0.0), vec![1, 2, 3, 4, 5]),
+        ]);
+
+        let mut simulator = Simulator::new(cs).unwrap();
+        for _ in 0..3 {
+            simulator.clock();
+        }
+        // each register delays its input by one cycle
+        assert_eq!(
+            simulator.get_input_value(&Input::new("stim", "out")),
+            4.into()
+        );
+        assert_eq!(
+            simulator.get_input_value(&Input::new("r1", "out")),
+            3.into()
+        );
+        assert_eq!(
+            simulator.get_input_value(&Input::new("r2", "out")),
+            2.into()
+        );
+        assert_eq!(
+            simulator.get_input_value(&Input::new("r3", "out")),
+            1.into()
+        );
+
+        simulator.un_clock();
+        assert_eq!(
+            simulator.get_input_value(&Input::new("r1", "out")),
+            2.into()
+        );
+        assert_eq!(
+            simulator.get_input_value(&Input::new("r3", "out")),
+            0.into()
+        );
+    }
+
+    #[test]
+    fn test_register_swap() {
+        // two registers exchanging their values each cycle
+        let cs = ComponentStore::new(vec![
+            ProbeStim::arc_new("init", (0.0, 0.0), vec![1, 0, 0, 0, 0, 0]),
+            Constant::arc_new("c", (0.0, 0.0), 2),
+            Mux::arc_new(
+                "mux",
+                (0.0, 0.0),
+                Input::new("init", "out"),
+                vec![Input::new("r2", "out"), Input::new("c", "out")],
+            ),
+            Register::arc_new("r1", (0.0, 0.0), Input::new("mux", "out")),
+            Register::arc_new("r2", (0.0, 0.0), Input::new("r1", "out")),
+        ]);
+
+        let mut simulator = Simulator::new(cs).unwrap();
+        // r1 is loaded with 2
+        simulator.clock();
+        let r1 = Input::new("r1", "out");
+        let r2 = Input::new("r2", "out");
+        assert_eq!(simulator.get_input_value(&r1), 2.into());
+        assert_eq!(simulator.get_input_value(&r2), 0.into());
+        for _ in 0..2 {
+            simulator.clock();
+            assert_eq!(simulator.get_input_value(&r1), 0.into());
+            assert_eq!(simulator.get_input_value(&r2), 2.into());
+            simulator.clock();
+            assert_eq!(simulator.get_input_value(&r1), 2.into());
+            assert_eq!(simulator.get_input_value(&r2), 0.into());
+        }
+    }
+
     #[test]
     fn test_get_input_fmt() {
-        let cs = ComponentStore {
-            store: vec![Rc::new(Constant::new("c", (0.0, 0.0), 0))],
-        };
+        let cs = ComponentStore::new(vec![Arc::new(Constant::new("c", (0.0, 0.0), 0))]);
 
         let simulator = Simulator::new(cs).unwrap();
 
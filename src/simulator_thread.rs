@@ -0,0 +1,253 @@
+// Simulation in a background thread
+//
+// The simulator is moved to a worker thread, controlled by commands over a channel.
+// While running, the worker sends snapshots of the signal state at a fixed rate,
+// allowing a GUI to refresh independently of the simulation speed. The components
+// (and thus their internal state, e.g., memories) are shared with the GUI copy of
+// the simulator, while signal state, cycle and history are kept by the worker until
+// the thread is joined.
+use crate::common::{Condition, Id, Signal, Simulator};
+use crate::profile::Profile;
+use crate::speed::{Pacer, Speed, FRAME};
+use crate::watchpoint::Watchpoint;
+use log::*;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Period between snapshots sent while running
+pub const SNAPSHOT_PERIOD: Duration = FRAME;
+
+/// Command to the simulation thread
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// run freely, until paused or a watchpoint or condition stops the simulation
+    Play,
+    Pause,
+    /// clock once, pausing the simulation
+    Step,
+    SetSpeed(Speed),
+    AddWatchpoint(Watchpoint),
+    RemoveWatchpoint(usize),
+    /// enable (restarting the statistics) or disable profiling
+    SetProfiling(bool),
+    /// terminate the thread, handing back the simulator
+    Stop,
+}
+
+/// State of the simulation, as sent by the simulation thread
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub cycle: usize,
+    pub sim_state: Vec<Signal>,
+    pub running: bool,
+    pub component_condition: Vec<(Id, Condition)>,
+    pub watchpoint_hits: Vec<usize>,
+    /// achieved clock rate (Hz) while running in a simulation thread
+    pub hz: f64,
+    pub profile: Option<Profile>,
+}
+
+/// Handle to a simulator running in a background thread
+pub struct SimulatorThread {
+    commands: Sender<Command>,
+    snapshots: Receiver<Snapshot>,
+    handle: JoinHandle<Simulator>,
+}
+
+impl SimulatorThread {
+    /// move simulator to a new thread, running (at `speed`) if the simulator is
+    pub fn spawn(simulator: Simulator, speed: Speed) -> Self {
+        let (commands, command_rx) = channel();
+        let (snapshot_tx, snapshots) = channel();
+        let handle = thread::spawn(move || work(simulator, speed, command_rx, snapshot_tx));
+        SimulatorThread {
+            commands,
+            snapshots,
+            handle,
+        }
+    }
+
+    /// send command, ignored if the thread has terminated
+    pub fn send(&self, command: Command) {
+        if self.commands.send(command).is_err() {
+            warn!("simulation thread has terminated");
+        }
+    }
+
+    /// most recent snapshot, if any received since last call
+    pub fn latest(&self) -> Option<Snapshot> {
+        self.snapshots.try_iter().last()
+    }
+
+    /// stop the thread, returning the simulator
+    pub fn join(self) -> Simulator {
+        self.send(Command::Stop);
+        self.handle.join().expect("simulation thread panicked")
+    }
+}
+
+// the simulation thread, serving commands until stopped
+fn work(
+    mut simulator: Simulator,
+    speed: Speed,
+    commands: Receiver<Command>,
+    snapshots: Sender<Snapshot>,
+) -> Simulator {
+    let mut pacer = Pacer::new(speed);
+    let mut sent = Instant::now();
+    loop {
+        // block while paused, poll (or wait for the next cycle) while running
+        let command = match (simulator.running, pacer.wait(Instant::now())) {
+            (true, None) => match commands.try_recv() {
+                Ok(command) => Some(command),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => Some(Command::Stop),
+            },
+            (true, Some(wait)) => match commands.recv_timeout(wait.min(SNAPSHOT_PERIOD)) {
+                Ok(command) => Some(command),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => Some(Command::Stop),
+            },
+            (false, _) => Some(commands.recv().unwrap_or(Command::Stop)),
+        };
+        match command {
+            Some(Command::Play) => {
+                simulator.running = true;
+                pacer = Pacer::new(pacer.speed());
+            }
+            Some(Command::Pause) => simulator.running = false,
+            Some(Command::Step) => {
+                simulator.running = false;
+                simulator.clock();
+            }
+            Some(Command::SetSpeed(speed)) => pacer = Pacer::new(speed),
+            Some(Command::AddWatchpoint(watchpoint)) => {
+                if let Err(e) = simulator.add_watchpoint(watchpoint) {
+                    warn!("{}", e);
+                }
+            }
+            Some(Command::RemoveWatchpoint(index)) => {
+                if index < simulator.watchpoints.len() {
+                    simulator.remove_watchpoint(index);
+                }
+            }
+            Some(Command::SetProfiling(enable)) => simulator.set_profiling(enable),
+            Some(Command::Stop) => {
+                simulator.running = false;
+                return simulator;
+            }
+            None => {
+                let now = Instant::now();
+                if pacer.wait(now).is_none() {
+                    simulator.clock();
+                    pacer.tick(now);
+                }
+            }
+        }
+
+        // snapshot on any change while paused, periodically while running
+        if !simulator.running || sent.elapsed() >= SNAPSHOT_PERIOD {
+            sent = Instant::now();
+            let snapshot = Snapshot {
+                hz: pacer.hz(),
+                ..simulator.snapshot()
+            };
+            if snapshots.send(snapshot).is_err() {
+                simulator.running = false;
+                return simulator;
+            }
+        }
+    }
+}
+
+impl Simulator {
+    /// current state of the simulation
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            cycle: self.cycle,
+            sim_state: self.sim_state.clone(),
+            running: self.running,
+            component_condition: self.component_condition.clone(),
+            watchpoint_hits: self.watchpoint_hits.clone(),
+            hz: 0.0,
+            profile: self.profile.clone(),
+        }
+    }
+
+    /// show the state of a simulation running in another thread
+    ///
+    /// Only the signal state is updated, the history is kept by the running simulator.
+    pub fn apply_snapshot(&mut self, snapshot: Snapshot) {
+        self.cycle = snapshot.cycle;
+        self.sim_state = snapshot.sim_state;
+        self.mark_all_changed();
+        self.running = snapshot.running;
+        self.component_condition = snapshot.component_condition;
+        self.watchpoint_hits = snapshot.watchpoint_hits;
+        self.profile = snapshot.profile;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::Input;
+    use crate::fixtures::counter;
+
+    // wait for the first snapshot with the simulation paused
+    fn paused(thread: &SimulatorThread) -> Snapshot {
+        loop {
+            let snapshot = thread.snapshots.recv().unwrap();
+            if !snapshot.running {
+                return snapshot;
+            }
+        }
+    }
+
+    #[test]
+    fn test_step() {
+        let thread = SimulatorThread::spawn(counter(), Speed::Max);
+        thread.send(Command::Step);
+        thread.send(Command::Step);
+        paused(&thread);
+        let snapshot = paused(&thread);
+        assert_eq!(snapshot.cycle, 3);
+
+        let mut simulator = counter();
+        simulator.apply_snapshot(snapshot);
+        assert_eq!(
+            simulator.get_input_value(&Input::new("r1", "out")),
+            2.into()
+        );
+
+        // the history is handed back
+        let mut simulator = thread.join();
+        assert_eq!(simulator.cycle, 3);
+        simulator.un_clock();
+        assert_eq!(simulator.cycle, 2);
+    }
+
+    #[test]
+    fn test_play_watchpoint() {
+        let thread = SimulatorThread::spawn(counter(), Speed::Max);
+        thread.send(Command::AddWatchpoint("r1/out == 1000".parse().unwrap()));
+        paused(&thread);
+        thread.send(Command::Play);
+        let snapshot = paused(&thread);
+        assert_eq!(snapshot.watchpoint_hits, vec![0]);
+        assert_eq!(snapshot.cycle, 1001);
+        assert_eq!(thread.join().cycle, 1001);
+    }
+
+    #[test]
+    fn test_speed() {
+        let thread = SimulatorThread::spawn(counter(), Speed::Hz(100.0));
+        thread.send(Command::AddWatchpoint("cycle >= 11".parse().unwrap()));
+        paused(&thread);
+        // paced by `Pacer`, see `speed`
+        thread.send(Command::Play);
+        assert_eq!(paused(&thread).cycle, 11);
+        thread.join();
+    }
+}
@@ -0,0 +1,218 @@
+// Simulation speed when playing
+//
+// A `Pacer` decides when the next cycle is due for a given `Speed`, and measures
+// the achieved clock rate.
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Period of a (GUI) frame
+pub const FRAME: Duration = Duration::from_millis(1000 / 60);
+
+// period over which the achieved clock rate is measured
+const METER_PERIOD: Duration = Duration::from_millis(500);
+
+/// Simulation speed
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Speed {
+    /// target clock rate (Hz)
+    Hz(f64),
+    /// cycles per frame
+    CyclesPerFrame(usize),
+    /// as fast as possible
+    #[default]
+    Max,
+}
+
+impl Speed {
+    /// speeds offered by the GUIs
+    pub const PRESETS: [Speed; 9] = [
+        Speed::Hz(1.0),
+        Speed::Hz(10.0),
+        Speed::Hz(100.0),
+        Speed::Hz(1000.0),
+        Speed::Hz(10000.0),
+        Speed::CyclesPerFrame(1),
+        Speed::CyclesPerFrame(10),
+        Speed::CyclesPerFrame(1000),
+        Speed::Max,
+    ];
+}
+
+impl fmt::Display for Speed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Speed::Hz(hz) => write!(f, "{}", format_hz(*hz)),
+            Speed::CyclesPerFrame(1) => write!(f, "1 cycle/frame"),
+            Speed::CyclesPerFrame(cycles) => write!(f, "{} cycles/frame", cycles),
+            Speed::Max => write!(f, "Max"),
+        }
+    }
+}
+
+/// clock rate with unit, e.g., `1.5 kHz`
+pub fn format_hz(hz: f64) -> String {
+    match hz {
+        hz if hz >= 1e6 => format!("{:.3} MHz", hz / 1e6),
+        hz if hz >= 1e3 => format!("{:.3} kHz", hz / 1e3),
+        hz => format!("{:.1} Hz", hz),
+    }
+    .replace(".000 ", " ")
+    .replace(".0 ", " ")
+}
+
+/// Pacing of cycles at a given speed
+#[derive(Debug, Clone)]
+pub struct Pacer {
+    speed: Speed,
+    // when the next cycle is due (`Hz`), or the current frame started (`CyclesPerFrame`)
+    next: Instant,
+    // cycles in the current frame
+    frame_cycles: usize,
+    // start of the current measurement, and cycles since
+    meter: (Instant, usize),
+    hz: f64,
+}
+
+impl Pacer {
+    pub fn new(speed: Speed) -> Self {
+        Pacer::new_at(speed, Instant::now())
+    }
+
+    /// pacer starting at `now`, e.g., a simulated time
+    pub fn new_at(speed: Speed, now: Instant) -> Self {
+        Pacer {
+            speed,
+            next: now,
+            frame_cycles: 0,
+            meter: (now, 0),
+            hz: 0.0,
+        }
+    }
+
+    pub fn speed(&self) -> Speed {
+        self.speed
+    }
+
+    /// time to wait for the next cycle, `None` if due
+    pub fn wait(&self, now: Instant) -> Option<Duration> {
+        match self.speed {
+            Speed::Max => None,
+            Speed::Hz(_) => self.next.checked_duration_since(now),
+            Speed::CyclesPerFrame(cycles) => match self.frame_cycles < cycles {
+                true => None,
+                false => (self.next + FRAME).checked_duration_since(now),
+            },
+        }
+        .filter(|wait| !wait.is_zero())
+    }
+
+    /// number of cycles due, at most `max`
+    pub fn due(&self, now: Instant, max: usize) -> usize {
+        match self.speed {
+            Speed::Max => max,
+            Speed::Hz(hz) if now >= self.next => {
+                let behind = now.duration_since(self.next).as_secs_f64() * hz;
+                (behind as usize + 1).min(max)
+            }
+            Speed::CyclesPerFrame(cycles) if now >= self.next + FRAME => cycles.min(max),
+            Speed::CyclesPerFrame(cycles) => (cycles - self.frame_cycles.min(cycles)).min(max),
+            _ => 0,
+        }
+    }
+
+    /// account for a cycle clocked at `now`
+    pub fn tick(&mut self, now: Instant) {
+        match self.speed {
+            Speed::Max => {}
+            Speed::Hz(hz) => {
+                // don't catch up on more than a frame
+                self.next = (self.next + Duration::from_secs_f64(1.0 / hz))
+                    .max(now.checked_sub(FRAME).unwrap_or(now));
+            }
+            Speed::CyclesPerFrame(_) => {
+                if now >= self.next + FRAME {
+                    self.next = now;
+                    self.frame_cycles = 0;
+                }
+                self.frame_cycles += 1;
+            }
+        }
+        self.meter.1 += 1;
+        let elapsed = now.duration_since(self.meter.0);
+        if elapsed >= METER_PERIOD {
+            self.hz = self.meter.1 as f64 / elapsed.as_secs_f64();
+            self.meter = (now, 0);
+        }
+    }
+
+    /// achieved clock rate (Hz), as last measured
+    pub fn hz(&self) -> f64 {
+        self.hz
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        assert_eq!(Speed::Hz(1.0).to_string(), "1 Hz");
+        assert_eq!(Speed::Hz(1000.0).to_string(), "1 kHz");
+        assert_eq!(format_hz(1500.0), "1.500 kHz");
+        assert_eq!(format_hz(2.5e6), "2.500 MHz");
+        assert_eq!(Speed::CyclesPerFrame(10).to_string(), "10 cycles/frame");
+    }
+
+    // time to clock `cycles` cycles, clocking when due and waiting otherwise
+    fn run(speed: Speed, cycles: usize) -> (Duration, Pacer) {
+        let start = Instant::now();
+        let mut pacer = Pacer::new_at(speed, start);
+        let mut now = start;
+        let mut clocked = 0;
+        while clocked < cycles {
+            match pacer.wait(now) {
+                Some(wait) => now += wait,
+                None => {
+                    pacer.tick(now);
+                    clocked += 1;
+                }
+            }
+        }
+        (now - start, pacer)
+    }
+
+    #[test]
+    fn test_speed() {
+        // the first cycle is due at once
+        let (elapsed, pacer) = run(Speed::Hz(100.0), 101);
+        assert_eq!(elapsed.as_millis(), 1000);
+        assert!((pacer.hz() - 100.0).abs() < 1.0, "{}", pacer.hz());
+
+        let (elapsed, _) = run(Speed::CyclesPerFrame(10), 31);
+        assert_eq!(elapsed, 3 * FRAME);
+
+        let (elapsed, _) = run(Speed::Max, 1000);
+        assert!(elapsed.is_zero());
+    }
+
+    #[test]
+    fn test_pacer() {
+        let start = Instant::now();
+        let mut pacer = Pacer::new_at(Speed::Hz(10.0), start);
+        assert_eq!(pacer.wait(start), None);
+        pacer.tick(start);
+        // next cycle due after 100 ms
+        let wait = pacer.wait(start).unwrap();
+        assert!(wait > Duration::from_millis(90) && wait <= Duration::from_millis(100));
+        assert_eq!(pacer.due(start, 10), 0);
+        assert_eq!(pacer.due(start + Duration::from_millis(250), 10), 2);
+
+        let mut pacer = Pacer::new_at(Speed::CyclesPerFrame(2), start);
+        assert_eq!(pacer.due(start, 10), 2);
+        pacer.tick(start);
+        pacer.tick(start);
+        assert!(pacer.wait(start).is_some());
+        assert_eq!(pacer.due(start + FRAME, 10), 2);
+    }
+}
@@ -0,0 +1,387 @@
+// Full simulation state, allowing a simulation to be saved and continued later
+//
+// While simulating, checkpoints of the signal state and the internal state of the
+// components (without undo logs, see `Component::save_checkpoint`) are taken
+// periodically, allowing `goto_cycle` to move to any cycle in history by
+// re-simulating from the closest checkpoint.
+//
+// A state file holds the model together with the cycle, the signal state and its
+// history, forced outputs, and the internal state of each component (as given by
+// `Component::save_state`), e.g.:
+//
+// {
+//   "model": { "store": [ ... ] },
+//   "state": {
+//     "cycle": 42,
+//     "sim_state": [ ... ],
+//     "history": { ... },
+//     "forced": [],
+//     "components": { "mem": { "memory": { "0": 1, "1": 2 } } }
+//   }
+// }
+use crate::common::{ComponentStore, Id, Signal, SignalValue, Simulator};
+use crate::history::History;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// default number of cycles between checkpoints
+pub const CHECKPOINT_INTERVAL: usize = 256;
+
+/// State of a simulation, for the model it was saved from
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SimulatorState {
+    pub cycle: usize,
+    pub sim_state: Vec<Signal>,
+    pub history: History,
+    /// forced outputs (index into `sim_state`, value)
+    #[serde(default)]
+    pub forced: Vec<(usize, SignalValue)>,
+    /// internal state of components, by (flattened) identifier
+    #[serde(default)]
+    pub components: BTreeMap<Id, serde_json::Value>,
+}
+
+/// Model with the state of a simulation
+#[derive(Serialize, Deserialize)]
+pub struct StateFile {
+    pub model: ComponentStore,
+    pub state: SimulatorState,
+}
+
+// signal state and internal state of components at a given cycle
+#[derive(Debug, Clone)]
+pub(crate) struct Checkpoint {
+    sim_state: Vec<Signal>,
+    components: BTreeMap<Id, serde_json::Value>,
+}
+
+impl Simulator {
+    /// full state of the simulation
+    pub fn save_state(&self) -> SimulatorState {
+        let mut forced: Vec<(usize, SignalValue)> =
+            self.forced.iter().map(|(i, v)| (*i, *v)).collect();
+        forced.sort_by_key(|(i, _)| *i);
+        SimulatorState {
+            cycle: self.cycle,
+            sim_state: self.sim_state.clone(),
+            history: self.history.clone(),
+            forced,
+            components: self
+                .ordered_components
+                .iter()
+                .filter_map(|c| Some((c.get_id_ports().0, c.save_state()?)))
+                .collect(),
+        }
+    }
+
+    /// restore the state of the simulation, saved from the same model
+    ///
+    /// The simulation is paused.
+    pub fn load_state(&mut self, state: SimulatorState) -> Result<(), String> {
+        if state.sim_state.len() != self.sim_state.len() {
+            return Err(format!(
+                "State has {} signals, the model {}",
+                state.sim_state.len(),
+                self.sim_state.len()
+            ));
+        }
+        if let Some((index, _)) = state
+            .forced
+            .iter()
+            .find(|(i, _)| *i >= self.sim_state.len())
+        {
+            return Err(format!("Forced signal {} not in model", index));
+        }
+        // all or none of the component states are applied, components failing to load
+        // their state are restored to the state before
+        let mut components = vec![];
+        for (id, component_state) in state.components {
+            let component = self
+                .ordered_components
+                .iter()
+                .find(|c| c.get_id_ports().0 == id)
+                .ok_or(format!("Component {:?} not in model", id))?;
+            components.push((id, component, component_state));
+        }
+        let before: Vec<_> = components.iter().map(|(_, c, _)| c.save_state()).collect();
+        for (i, (id, component, component_state)) in components.iter().enumerate() {
+            if let Err(e) = component.load_state(component_state.clone()) {
+                for ((_, component, _), state) in components.iter().zip(&before).take(i + 1) {
+                    if let Some(state) = state {
+                        component.load_state(state.clone())?;
+                    }
+                }
+                return Err(format!("Component {:?}: {}", id, e));
+            }
+        }
+        self.cycle = state.cycle;
+        self.sim_state = state.sim_state;
+        self.history = state.history;
+        self.forced = state.forced.into_iter().collect();
+        self.mark_all_changed();
+        self.component_condition.clear();
+        self.watchpoint_hits.clear();
+        self.running = false;
+        self.checkpoints.clear();
+        self.checkpoint();
+        Ok(())
+    }
+
+    /// move to `cycle`, clocking forward or re-simulating from the closest checkpoint
+    ///
+    /// The simulation is paused. Cycles after `cycle` are dropped from history, as by
+    /// `un_clock`. Re-simulation uses the current values of forced signals and editable
+    /// probes, thus changes made to those after `cycle` are not reproduced.
+    pub fn goto_cycle(&mut self, cycle: usize) -> Result<(), String> {
+        self.running = false;
+        if cycle == 0 {
+            return Err("Cycle 0 precedes the initial evaluation".to_string());
+        }
+        if cycle < self.cycle {
+            let (&start, checkpoint) = self
+                .checkpoints
+                .range(..=cycle)
+                .next_back()
+                .ok_or(format!("Cycle {} is beyond history depth", cycle))?;
+            let checkpoint = checkpoint.clone();
+            for (id, component_state) in checkpoint.components {
+                if let Some(component) = self
+                    .ordered_components
+                    .iter()
+                    .find(|c| c.get_id_ports().0 == id)
+                {
+                    component.load_state(component_state)?;
+                }
+            }
+            self.sim_state = checkpoint.sim_state;
+            self.mark_all_changed();
+            self.history.truncate(start);
+            self.checkpoints.split_off(&(start + 1));
+            self.cycle = start;
+        }
+        while self.cycle < cycle {
+            self.clock();
+        }
+        Ok(())
+    }
+
+    /// set number of cycles between checkpoints, trading memory for `goto_cycle` speed
+    pub fn set_checkpoint_interval(&mut self, interval: usize) {
+        self.checkpoint_interval = interval.max(1);
+    }
+
+    // checkpoint at the current cycle
+    pub(crate) fn checkpoint(&mut self) {
+        let checkpoint = Checkpoint {
+            sim_state: self.sim_state.clone(),
+            components: self
+                .ordered_components
+                .iter()
+                .filter_map(|c| Some((c.get_id_ports().0, c.save_checkpoint()?)))
+                .collect(),
+        };
+        self.checkpoints.insert(self.cycle, checkpoint);
+    }
+
+    // drop checkpoints not needed to reach the oldest state in history
+    pub(crate) fn trim_checkpoints(&mut self) {
+        let first = self.history.first();
+        if let Some(&oldest) = self.checkpoints.range(..=first).next_back().map(|(c, _)| c) {
+            self.checkpoints = self.checkpoints.split_off(&oldest);
+        }
+    }
+
+    /// save model and state, see `StateFile`
+    pub fn save_state_file(&self, path: &Path) -> Result<(), String> {
+        let file = StateFile {
+            model: ComponentStore {
+                store: self.components.clone(),
+                delays: self.delays.clone(),
+            },
+            state: self.save_state(),
+        };
+        let json = serde_json::to_string(&file).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// build simulator from a state file, continuing the saved simulation
+    pub fn load_state_file(path: &Path) -> Result<Simulator, String> {
+        let json =
+            std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let mut file: StateFile =
+            serde_json::from_str(&json).map_err(|e| format!("{}: {}", path.display(), e))?;
+        file.model
+            .set_model_dir(path.parent().unwrap_or(Path::new("")));
+        let mut simulator = Simulator::new(file.model).map_err(|e| e.to_string())?;
+        simulator.load_state(file.state)?;
+        Ok(simulator)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::{Components, Input};
+    use crate::components::*;
+    use crate::fixtures::counter_components;
+    use std::sync::Arc;
+
+    // counter, storing its value in memory at address 0
+    fn counter() -> Simulator {
+        let mut store = counter_components();
+        store.extend::<Components>(vec![
+            Arc::new(Constant::new("zero", (0.0, 0.0), 0)),
+            Arc::new(Constant::new("size", (0.0, 0.0), 4)),
+            Arc::new(Constant::new("write", (0.0, 0.0), MemCtrl::Write as u32)),
+            Mem::arc_new(
+                "mem",
+                (0.0, 0.0),
+                100.0,
+                100.0,
+                false,
+                Input::new("r1", "out"),
+                Input::new("zero", "out"),
+                Input::new("write", "out"),
+                Input::new("zero", "out"),
+                Input::new("size", "out"),
+                0..16,
+            ),
+        ]);
+        Simulator::new(ComponentStore::new(store)).unwrap()
+    }
+
+    #[test]
+    fn test_state() {
+        let mut simulator = counter();
+        for _ in 0..4 {
+            simulator.clock();
+        }
+        let state = simulator.save_state();
+        assert!(state.components.contains_key("mem"));
+
+        // continue from the saved state
+        let mut restored = counter();
+        restored.load_state(state.clone()).unwrap();
+        simulator.clock();
+        restored.clock();
+        assert_eq!(restored.cycle, 6);
+        assert_eq!(restored.sim_state, simulator.sim_state);
+        assert_eq!(
+            restored.save_state().components,
+            simulator.save_state().components
+        );
+
+        // history is restored
+        restored.un_clock();
+        assert_eq!(restored.sim_state, state.sim_state);
+
+        let mut state = state;
+        state.sim_state.pop();
+        assert!(restored.load_state(state).is_err());
+    }
+
+    #[test]
+    fn test_load_state_errors() {
+        // counter with a second memory, written by another state
+        let model = ComponentStore::new(counter().components);
+        let mut cs = ComponentStore::load(&model.to_json().unwrap()).unwrap();
+        cs.store.push(Mem::arc_new(
+            "mem2",
+            (0.0, 0.0),
+            100.0,
+            100.0,
+            false,
+            Input::new("r1", "out"),
+            Input::new("zero", "out"),
+            Input::new("write", "out"),
+            Input::new("zero", "out"),
+            Input::new("size", "out"),
+            0..16,
+        ));
+        let mut simulator = Simulator::new(cs).unwrap();
+        let before = simulator.save_state();
+        for _ in 0..4 {
+            simulator.clock();
+        }
+        let state = simulator.save_state();
+        simulator.load_state(before.clone()).unwrap();
+
+        // nothing is applied if any component state fails to load
+        let mut unknown = state.clone();
+        unknown.components.insert("zzz".to_string(), 0.into());
+        let mut invalid = state;
+        invalid.components.insert("mem2".to_string(), 0.into());
+        for state in [unknown, invalid] {
+            assert!(simulator.load_state(state).is_err());
+            assert_eq!(simulator.cycle, before.cycle);
+            assert_eq!(simulator.save_state().components, before.components);
+        }
+    }
+
+    #[test]
+    fn test_goto_cycle() {
+        let mut simulator = counter();
+        simulator.set_checkpoint_interval(16);
+        // state at each cycle
+        let mut states = vec![None];
+        while simulator.cycle <= 100 {
+            states.push(Some(simulator.save_state()));
+            simulator.clock();
+        }
+
+        for cycle in [37, 90, 5, 1, 64, 100] {
+            simulator.goto_cycle(cycle).unwrap();
+            let state = states[cycle].as_ref().unwrap();
+            assert_eq!(simulator.cycle, cycle);
+            assert_eq!(simulator.sim_state, state.sim_state);
+            assert_eq!(simulator.save_state().components, state.components);
+        }
+        assert!(simulator.goto_cycle(0).is_err());
+
+        // with limited history, only recent cycles are reachable
+        simulator.set_history_depth(Some(8));
+        simulator.goto_cycle(200).unwrap();
+        assert!(simulator.goto_cycle(100).is_err());
+        simulator.goto_cycle(195).unwrap();
+        assert_eq!(simulator.cycle, 195);
+    }
+
+    #[test]
+    fn test_checkpoint_memory() {
+        let mut simulator = counter();
+        simulator.set_history_depth(Some(1000));
+        // size of the checkpoints and of the memory undo log
+        let size = |simulator: &Simulator| {
+            let checkpoints: usize = simulator
+                .checkpoints
+                .values()
+                .map(|c| serde_json::to_string(&c.components).unwrap().len())
+                .sum();
+            let state = simulator.save_state().components["mem"].to_string();
+            (checkpoints, state.len())
+        };
+        for _ in 0..5000 {
+            simulator.clock();
+        }
+        let (checkpoints, state) = size(&simulator);
+        for _ in 0..20000 {
+            simulator.clock();
+        }
+        let (checkpoints_after, state_after) = size(&simulator);
+        assert!(checkpoints_after < 2 * checkpoints, "{}", checkpoints_after);
+        assert!(state_after < 2 * state, "{}", state_after);
+    }
+
+    #[test]
+    fn test_state_file() {
+        let path = std::env::temp_dir().join(format!("state_{}.json", std::process::id()));
+        let mut simulator = counter();
+        simulator.clock();
+        simulator.save_state_file(&path).unwrap();
+        let restored = Simulator::load_state_file(&path).unwrap();
+        assert_eq!(restored.cycle, 2);
+        assert_eq!(restored.sim_state, simulator.sim_state);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,472 @@
+// Declarative testbenches, kept separate from the model under test
+//
+// A testbench (JSON) names a model file, outputs to drive at given cycles,
+// and values to check at given cycles, e.g.:
+//
+// {
+//   "model": "add_reg.json",
+//   "drive": [{ "cycle": 3, "signal": "c1/out", "value": 2 }],
+//   "release": [{ "cycle": 5, "signal": "c1/out" }],
+//   "check": [
+//     { "cycle": 2, "signal": "add/out", "expect": 2 },
+//     { "cycle": 3, "signal": "add/out", "expect": { "value": 4, "mask": 254 } },
+//     { "cycle": 4, "signal": "add/out", "expect": { "min": 4, "max": 8 } },
+//     { "cycle": 4, "signal": "add/overflow", "expect": "x" }
+//   ]
+// }
+//
+// Cycles are numbered as by `Simulator::cycle`, the state after reset being cycle 1.
+// A driven output (a value, or `"x"` for unknown) takes its value from the given cycle,
+// as if set by its component (i.e., registers sample it on the next clock), and keeps
+// it until released, its component then setting it again from the cycle released.
+// Values (up to 64 bits) take the declared width of the driven output.
+use crate::common::{
+    ComponentStore, Input, Signal, SignalIndex, SignalUnsigned, SignalValue, SignalWide, Simulator,
+    MAX_WIDTH,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Testbench {
+    /// Model file, relative to the testbench file
+    pub model: PathBuf,
+    /// Number of cycles to run, at least up to the last drive or check
+    #[serde(default)]
+    pub cycles: Option<usize>,
+    #[serde(default)]
+    pub drive: Vec<Drive>,
+    #[serde(default)]
+    pub release: Vec<Release>,
+    #[serde(default)]
+    pub check: Vec<Check>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Drive {
+    pub cycle: usize,
+    #[serde(with = "signal_path")]
+    pub signal: Input,
+    pub value: DriveValue,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum DriveValue {
+    /// Value, of the width of the driven output
+    Value(SignalWide),
+    /// Unknown value, given as `"x"` (or `"-"`)
+    Unknown(DontCare),
+}
+
+impl DriveValue {
+    /// value driven to an output of the given width (if declared)
+    pub fn to_signal_value(self, width: Option<u8>) -> SignalValue {
+        match self {
+            DriveValue::Value(value) => {
+                // undeclared widths are up to 32 bits, unless the value does not fit
+                let width = width.unwrap_or(match SignalUnsigned::try_from(value) {
+                    Ok(_) => SignalUnsigned::BITS as u8,
+                    Err(_) => MAX_WIDTH,
+                });
+                SignalValue::from_bits(value, width)
+            }
+            DriveValue::Unknown(_) => SignalValue::Unknown,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Release {
+    pub cycle: usize,
+    #[serde(with = "signal_path")]
+    pub signal: Input,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Check {
+    pub cycle: usize,
+    #[serde(with = "signal_path")]
+    pub signal: Input,
+    pub expect: Expect,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Expect {
+    /// Exact value
+    Value(SignalWide),
+    /// Value of the bits set in `mask`
+    Masked { value: SignalWide, mask: SignalWide },
+    /// Value in range `min..=max`
+    Range { min: SignalWide, max: SignalWide },
+    /// Any value, given as `"x"` (or `"-"`)
+    DontCare(DontCare),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DontCare {
+    #[serde(rename = "x", alias = "-")]
+    X,
+}
+
+impl Expect {
+    pub fn matches(&self, value: SignalValue) -> bool {
+        match (self, SignalWide::try_from(value)) {
+            (Expect::DontCare(_), _) => true,
+            (Expect::Value(expected), Ok(value)) => value == *expected,
+            (
+                Expect::Masked {
+                    value: expected,
+                    mask,
+                },
+                Ok(value),
+            ) => value & mask == expected & mask,
+            (Expect::Range { min, max }, Ok(value)) => (*min..=*max).contains(&value),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Expect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expect::Value(value) => write!(f, "{:#x}", value),
+            Expect::Masked { value, mask } => write!(f, "{:#x} (mask {:#x})", value, mask),
+            Expect::Range { min, max } => write!(f, "{:#x}..={:#x}", min, max),
+            Expect::DontCare(_) => write!(f, "x"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub cycle: usize,
+    pub signal: Input,
+    pub expect: Expect,
+    pub actual: Signal,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cycle {}: {} = {}, expected {}",
+            self.cycle, self.signal, self.actual, self.expect
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TestReport {
+    /// Number of checks performed
+    pub checks: usize,
+    pub failures: Vec<Failure>,
+}
+
+impl TestReport {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl fmt::Display for TestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for failure in &self.failures {
+            writeln!(f, "{}", failure)?;
+        }
+        write!(f, "{} checks, {} failed", self.checks, self.failures.len())
+    }
+}
+
+impl Testbench {
+    /// load testbench, resolving the model path relative to the testbench file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        let mut testbench: Testbench =
+            serde_json::from_str(&json).map_err(|e| format!("{}: {}", path.display(), e))?;
+        if let Some(dir) = path.parent() {
+            testbench.model = dir.join(&testbench.model);
+        }
+        Ok(testbench)
+    }
+
+    /// load model and run testbench
+    pub fn run(&self) -> Result<TestReport, String> {
+        if !self.model.exists() {
+            return Err(format!("Model {} not found", self.model.display()));
+        }
+        self.run_store(ComponentStore::load_file(&self.model).map_err(|e| e.to_string())?)
+    }
+
+    /// run testbench on model
+    pub fn run_store(&self, cs: ComponentStore) -> Result<TestReport, String> {
+        let mut simulator = Simulator::new(cs).map_err(|e| e.to_string())?;
+
+        let resolve = |input: &Input, cycle: usize| {
+            if cycle == 0 {
+                return Err(format!("{}: cycles start at 1", input));
+            }
+            simulator
+                .resolve_input(input)
+                .ok_or(format!("Signal {} not found in model", input))
+        };
+        let mut drives = self
+            .drive
+            .iter()
+            .map(|d| Ok((d.cycle, resolve(&d.signal, d.cycle)?, d.value)))
+            .collect::<Result<Vec<(usize, SignalIndex, DriveValue)>, String>>()?;
+        let mut releases = self
+            .release
+            .iter()
+            .map(|r| Ok((r.cycle, resolve(&r.signal, r.cycle)?)))
+            .collect::<Result<Vec<(usize, SignalIndex)>, String>>()?;
+        let mut checks = self
+            .check
+            .iter()
+            .map(|c| Ok((c.cycle, resolve(&c.signal, c.cycle)?, c)))
+            .collect::<Result<Vec<(usize, SignalIndex, &Check)>, String>>()?;
+        drives.sort_by_key(|(cycle, ..)| *cycle);
+        releases.sort_by_key(|(cycle, ..)| *cycle);
+        checks.sort_by_key(|(cycle, ..)| *cycle);
+
+        let last = drives
+            .iter()
+            .map(|(cycle, ..)| *cycle)
+            .chain(releases.iter().map(|(cycle, ..)| *cycle))
+            .chain(checks.iter().map(|(cycle, ..)| *cycle))
+            .chain(self.cycles)
+            .max()
+            .unwrap_or(1);
+
+        let mut drives = drives.into_iter().peekable();
+        let mut releases = releases.into_iter().peekable();
+        let mut checks = checks.into_iter().peekable();
+        let mut report = TestReport::default();
+        for cycle in 1..=last {
+            while let Some((_, index)) = releases.next_if(|(c, ..)| *c == cycle) {
+                simulator.release_signal(index);
+            }
+            let mut driven = false;
+            while let Some((_, index, value)) = drives.next_if(|(c, ..)| *c == cycle) {
+                let value = value.to_signal_value(simulator.get_width(index));
+                // the state of cycle 1 is computed on reset, other cycles by the clock
+                match cycle {
+                    1 => simulator.force_signal(index, value),
+                    _ => simulator.drive_signal(index, value),
+                }
+                driven = true;
+            }
+            match cycle {
+                1 if driven => simulator.reset(),
+                1 => {}
+                _ => simulator.clock(),
+            }
+            while let Some((_, index, check)) = checks.next_if(|(c, ..)| *c == cycle) {
+                let actual = simulator.get_signal(index);
+                report.checks += 1;
+                if !check.expect.matches(actual.get_value()) {
+                    report.failures.push(Failure {
+                        cycle,
+                        signal: check.signal.clone(),
+                        expect: check.expect,
+                        actual,
+                    });
+                }
+            }
+        }
+        Ok(report)
+    }
+}
+
+// signals given as `id/field`
+mod signal_path {
+    use crate::common::Input;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(input: &Input, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(input)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Input, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::components::*;
+    use crate::fixtures::counter_components;
+    use std::sync::Arc;
+
+    fn testbench(json: &str) -> Testbench {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_expect() {
+        let tb = testbench(
+            r#"{ "model": "m.json", "check": [
+                { "cycle": 1, "signal": "a/out", "expect": 3 },
+                { "cycle": 1, "signal": "a/out", "expect": { "value": 19, "mask": 15 } },
+                { "cycle": 1, "signal": "a/out", "expect": { "min": 1, "max": 3 } },
+                { "cycle": 1, "signal": "a/out", "expect": "x" },
+                { "cycle": 1, "signal": "a/out", "expect": "-" }
+            ] }"#,
+        );
+        let expects: Vec<Expect> = tb.check.iter().map(|c| c.expect).collect();
+        assert_eq!(
+            expects,
+            vec![
+                Expect::Value(3),
+                Expect::Masked {
+                    value: 19,
+                    mask: 15
+                },
+                Expect::Range { min: 1, max: 3 },
+                Expect::DontCare(DontCare::X),
+                Expect::DontCare(DontCare::X),
+            ]
+        );
+        assert!(expects.iter().all(|e| e.matches(3.into())));
+        assert!(expects[3].matches(SignalValue::Unknown));
+        assert!(!expects[0].matches(4.into()));
+        assert!(expects[1].matches(0x23.into()));
+        assert!(!expects[1].matches(0x42.into()));
+        assert!(!expects[2].matches(0.into()));
+        assert!(!expects[0].matches(SignalValue::from_bits(3 << 32, 64)));
+        assert!(expects[1].matches(SignalValue::from_bits(3 << 32 | 3, 64)));
+    }
+
+    #[test]
+    fn test_testbench() {
+        let tb = testbench(
+            r#"{ "model": "m.json",
+                "drive": [ { "cycle": 3, "signal": "c1/out", "value": 2 } ],
+                "check": [
+                    { "cycle": 1, "signal": "add/out", "expect": 1 },
+                    { "cycle": 2, "signal": "add/out", "expect": 2 },
+                    { "cycle": 3, "signal": "add/out", "expect": 4 },
+                    { "cycle": 4, "signal": "add/out", "expect": 5 },
+                    { "cycle": 4, "signal": "r1/out", "expect": { "min": 4, "max": 8 } }
+                ] }"#,
+        );
+        let report = tb
+            .run_store(ComponentStore::new(counter_components()))
+            .unwrap();
+        assert_eq!(report.checks, 5);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].cycle, 4);
+        assert_eq!(report.failures[0].signal, Input::new("add", "out"));
+        assert_eq!(report.failures[0].actual.get_value(), 6.into());
+        assert_eq!(
+            report.failures[0].to_string(),
+            "cycle 4: add/out = 0x6, expected 0x5"
+        );
+    }
+
+    #[test]
+    fn test_testbench_drive_reset() {
+        let tb = testbench(
+            r#"{ "model": "m.json",
+                "drive": [ { "cycle": 1, "signal": "c1/out", "value": 3 } ],
+                "check": [
+                    { "cycle": 1, "signal": "add/out", "expect": 3 },
+                    { "cycle": 2, "signal": "add/out", "expect": 6 }
+                ] }"#,
+        );
+        assert!(tb
+            .run_store(ComponentStore::new(counter_components()))
+            .unwrap()
+            .passed());
+    }
+
+    #[test]
+    fn test_testbench_drive_timing() {
+        let tb = testbench(
+            r#"{ "model": "m.json",
+                "drive": [
+                    { "cycle": 3, "signal": "add/out", "value": 10 },
+                    { "cycle": 6, "signal": "c1/out", "value": "x" }
+                ],
+                "release": [ { "cycle": 5, "signal": "add/out" } ],
+                "check": [
+                    { "cycle": 2, "signal": "add/out", "expect": 2 },
+                    { "cycle": 3, "signal": "add/out", "expect": 10 },
+                    { "cycle": 3, "signal": "r1/out", "expect": 2 },
+                    { "cycle": 4, "signal": "r1/out", "expect": 10 },
+                    { "cycle": 4, "signal": "add/out", "expect": 10 },
+                    { "cycle": 5, "signal": "r1/out", "expect": 10 },
+                    { "cycle": 5, "signal": "add/out", "expect": 11 },
+                    { "cycle": 6, "signal": "r1/out", "expect": 11 },
+                    { "cycle": 6, "signal": "add/out", "expect": 12 }
+                ] }"#,
+        );
+        let report = tb
+            .run_store(ComponentStore::new(counter_components()))
+            .unwrap();
+        assert_eq!(report.checks, 9);
+        assert_eq!(report.failures.len(), 1, "{}", report);
+        assert_eq!(report.failures[0].cycle, 6);
+        assert_eq!(report.failures[0].actual.get_value(), SignalValue::Unknown);
+    }
+
+    #[test]
+    fn test_testbench_drive_wide() {
+        let cs = ComponentStore::new(vec![
+            Arc::new(Constant::new("c", (0.0, 0.0), 1)),
+            Sext::arc_new("sext64", (0.0, 0.0), Input::new("c", "out"), 8, 64),
+            Register::arc_new("r", (0.0, 0.0), Input::new("sext64", "out")),
+            Add::arc_new(
+                "add",
+                (0.0, 0.0),
+                Input::new("c", "out"),
+                Input::new("c", "out"),
+            ),
+        ]);
+        let tb = testbench(
+            r#"{ "model": "m.json",
+                "drive": [
+                    { "cycle": 2, "signal": "sext64/out", "value": 4294967297 },
+                    { "cycle": 2, "signal": "add/out", "value": 4294967298 }
+                ],
+                "check": [
+                    { "cycle": 2, "signal": "sext64/out", "expect": 4294967297 },
+                    { "cycle": 3, "signal": "r/out", "expect": 4294967297 },
+                    { "cycle": 2, "signal": "add/out", "expect": 2 }
+                ] }"#,
+        );
+        let report = tb.run_store(cs).unwrap();
+        assert!(report.passed(), "{}", report);
+    }
+
+    #[test]
+    fn test_testbench_model_error() {
+        let dir = std::env::temp_dir().join(format!("testbench_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("m.json"), "{ not a model").unwrap();
+        std::fs::write(dir.join("tb.json"), r#"{ "model": "m.json" }"#).unwrap();
+
+        let tb = Testbench::load(&dir.join("tb.json")).unwrap();
+        assert!(tb.run().is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_testbench_missing_signal() {
+        let tb = testbench(
+            r#"{ "model": "m.json", "check": [ { "cycle": 1, "signal": "add/missing", "expect": 1 } ] }"#,
+        );
+        assert!(tb
+            .run_store(ComponentStore::new(counter_components()))
+            .is_err());
+    }
+}
@@ -0,0 +1,227 @@
+// Timing analysis, the longest combinational path between sequential elements
+//
+// Each component has a propagation delay (in arbitrary units), given by
+// `Component::delay` unless annotated in the model, by component id or type, e.g.:
+//
+// {
+//   "store": [ ... ],
+//   "delays": { "Add": 3.0, "alu": 5.0 }
+// }
+//
+// The delay of a sequential component is its clock to output delay, the delay of a
+// path is the sum of delays from a sequential element (or a combinational source) up
+// to the element capturing the value. The longest such path limits the clock rate.
+use crate::common::{Component, Id, OutputType, Simulator};
+use std::collections::HashMap;
+
+/// Longest combinational path of a model
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CriticalPath {
+    /// total delay
+    pub delay: f64,
+    /// components on the path, from the launching to the capturing element (if any)
+    pub path: Vec<Id>,
+    /// number of combinational components with a delay on the path
+    pub depth: usize,
+}
+
+impl CriticalPath {
+    pub fn contains(&self, id: &str) -> bool {
+        self.path.iter().any(|p| p == id)
+    }
+
+    /// true if `from` directly drives `to` on the path
+    pub fn contains_edge(&self, from: &str, to: &str) -> bool {
+        self.path.windows(2).any(|w| w[0] == from && w[1] == to)
+    }
+}
+
+// arrival time at the outputs of a component
+#[derive(Debug, Clone)]
+struct Arrival {
+    time: f64,
+    // input on the longest path
+    from: Option<Id>,
+    // if the path is launched by a sequential element, preferred on equal time
+    sequential: bool,
+}
+
+impl Arrival {
+    fn later(&self, other: &Arrival) -> bool {
+        (self.time, self.sequential) > (other.time, other.sequential)
+    }
+}
+
+impl Simulator {
+    /// delay of a component, as annotated in the model by id or by type, or its default
+    pub fn component_delay(&self, component: &dyn Component) -> f64 {
+        let id = component.get_id_ports().0;
+        self.delays
+            .get(&id)
+            .or_else(|| self.delays.get(component.typetag_name()))
+            .copied()
+            .unwrap_or_else(|| component.delay())
+    }
+
+    /// longest combinational path between sequential elements, found when building
+    /// the simulator
+    pub fn critical_path(&self) -> &CriticalPath {
+        &self.critical_path
+    }
+
+    pub(crate) fn find_critical_path(&self) -> CriticalPath {
+        let mut arrival: HashMap<Id, Arrival> = HashMap::new();
+        let mut delays: HashMap<Id, f64> = HashMap::new();
+        for c in &self.ordered_components {
+            let (id, ports) = c.get_id_ports();
+            let delay = self.component_delay(c.as_ref());
+            delays.insert(id.clone(), delay);
+            let latest = match ports.out_type {
+                OutputType::Sequential => None,
+                OutputType::Combinatorial => Self::latest_input(&arrival, c.as_ref()),
+            };
+            let output = match latest {
+                Some(input) => Arrival {
+                    time: input.time + delay,
+                    ..input
+                },
+                None => Arrival {
+                    time: delay,
+                    from: None,
+                    sequential: ports.out_type == OutputType::Sequential,
+                },
+            };
+            arrival.insert(id, output);
+        }
+
+        // endpoints, the inputs of sequential elements and any combinational output,
+        // preferring the capturing sequential element on equal delay
+        let mut end: Option<(Arrival, Option<Id>)> = None;
+        let (sequential, combinatorial) = self.ordered_components.split_at(self.nr_sequential);
+        for c in sequential {
+            if let Some(input) = Self::latest_input(&arrival, c.as_ref()) {
+                if end.as_ref().is_none_or(|(e, _)| input.later(e)) {
+                    end = Some((input, Some(c.get_id_ports().0)));
+                }
+            }
+        }
+        for c in sequential.iter().chain(combinatorial) {
+            let id = c.get_id_ports().0;
+            let output = Arrival {
+                from: Some(id.clone()),
+                ..arrival[&id].clone()
+            };
+            if end.as_ref().is_none_or(|(e, _)| output.later(e)) {
+                end = Some((output, None));
+            }
+        }
+        let Some((end, capture)) = end else {
+            return CriticalPath::default();
+        };
+
+        let mut path: Vec<Id> = capture.into_iter().collect();
+        let mut current = end.from;
+        while let Some(id) = current {
+            current = arrival[&id].from.clone();
+            path.push(id);
+        }
+        path.reverse();
+        let sequential: Vec<Id> = sequential.iter().map(|c| c.get_id_ports().0).collect();
+        let depth = path
+            .iter()
+            .filter(|id| !sequential.contains(id) && delays[*id] > 0.0)
+            .count();
+        CriticalPath {
+            delay: end.time,
+            path,
+            depth,
+        }
+    }
+
+    // latest arriving input of a component, if any connected, `from` the driving component
+    fn latest_input(arrival: &HashMap<Id, Arrival>, component: &dyn Component) -> Option<Arrival> {
+        let mut latest: Option<Arrival> = None;
+        for port in component.get_id_ports().1.inputs {
+            if let Some(input) = arrival.get(&port.input.id) {
+                if latest.as_ref().is_none_or(|l| input.later(l)) {
+                    latest = Some(Arrival {
+                        from: Some(port.input.id.clone()),
+                        ..input.clone()
+                    });
+                }
+            }
+        }
+        latest
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::{ComponentStore, Input};
+    use crate::components::*;
+    use std::sync::Arc;
+
+    // r1 -> add1 -> add2 -> add3 -> r1, optionally with r2 between add2 and add3
+    fn chain(pipelined: bool) -> ComponentStore {
+        let add = |id: &str, from: &str| {
+            Add::arc_new(
+                id,
+                (0.0, 0.0),
+                Input::new(from, "out"),
+                Input::new("c1", "out"),
+            )
+        };
+        let mut store: crate::common::Components = vec![
+            Arc::new(Constant::new("c1", (0.0, 0.0), 1)),
+            Register::arc_new("r1", (0.0, 0.0), Input::new("add3", "out")),
+            add("add1", "r1"),
+            add("add2", "add1"),
+            Arc::new(Probe::new("p", (0.0, 0.0), Input::new("add2", "out"))),
+        ];
+        if pipelined {
+            store.push(Register::arc_new(
+                "r2",
+                (0.0, 0.0),
+                Input::new("add2", "out"),
+            ));
+            store.push(add("add3", "r2"));
+        } else {
+            store.push(add("add3", "add2"));
+        }
+        ComponentStore::new(store)
+    }
+
+    #[test]
+    fn test_critical_path() {
+        let simulator = Simulator::new(chain(false)).unwrap();
+        let critical = simulator.critical_path();
+        assert_eq!(critical.delay, 3.0);
+        assert_eq!(critical.depth, 3);
+        assert_eq!(critical.path, vec!["r1", "add1", "add2", "add3", "r1"]);
+        assert!(critical.contains_edge("add1", "add2"));
+        assert!(!critical.contains("p"));
+
+        let simulator = Simulator::new(chain(true)).unwrap();
+        let critical = simulator.critical_path();
+        assert_eq!(critical.delay, 2.0);
+        assert_eq!(critical.path, vec!["r1", "add1", "add2", "r2"]);
+    }
+
+    #[test]
+    fn test_delay_annotation() {
+        let mut cs = chain(true);
+        cs.delays.insert("Add".to_string(), 2.0);
+        cs.delays.insert("add3".to_string(), 5.0);
+        cs.delays.insert("Register".to_string(), 0.5);
+        // annotations are kept in the model file
+        let json = serde_json::to_string(&cs).unwrap();
+        let cs: ComponentStore = serde_json::from_str(&json).unwrap();
+
+        let simulator = Simulator::new(cs).unwrap();
+        let critical = simulator.critical_path();
+        assert_eq!(critical.delay, 5.5);
+        assert_eq!(critical.path, vec!["r2", "add3", "r1"]);
+        assert_eq!(critical.depth, 1);
+    }
+}
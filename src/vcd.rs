@@ -0,0 +1,188 @@
+// Export of simulation history as Value Change Dump (IEEE 1364)
+//
+// Each output field is a variable in a scope named by its component,
+// its width as declared by the component, else taken from the current
+// signal (format). One cycle amounts to one time unit.
+use crate::common::{Id, Signal, SignalIndex, SignalValue, SignalWide, Simulator};
+use std::io::{self, Write};
+
+struct Var<'a> {
+    id: &'a Id,
+    field: &'a Id,
+    // index in `sim_state`
+    index: usize,
+    bits: u8,
+    code: String,
+}
+
+// printable identifier code, `!`, `"`, ... `~`, `!!`, ...
+fn code(mut n: usize) -> String {
+    let mut code = String::new();
+    loop {
+        code.push((b'!' + (n % 94) as u8) as char);
+        n /= 94;
+        if n == 0 {
+            return code;
+        }
+        n -= 1;
+    }
+}
+
+fn write_value(w: &mut impl Write, var: &Var, signal: &Signal) -> io::Result<()> {
+    match (SignalWide::try_from(signal.get_value()), var.bits) {
+        (Ok(value), 1) => writeln!(w, "{}{}", value & 1, var.code),
+        (Ok(value), bits) => {
+            let mask = SignalWide::MAX
+                .checked_shr(SignalWide::BITS - bits as u32)
+                .unwrap_or(0);
+            writeln!(w, "b{:b} {}", value & mask, var.code)
+        }
+        (_, 1) => writeln!(w, "x{}", var.code),
+        _ => writeln!(w, "bx {}", var.code),
+    }
+}
+
+/// write the history and current state of the simulator as VCD
+pub fn write_vcd(simulator: &Simulator, w: &mut impl Write) -> io::Result<()> {
+    let mut fields: Vec<_> = simulator
+        .id_field_index
+        .iter()
+        .map(|((id, field), index)| (id, *index, field))
+        .collect();
+    fields.sort();
+    let vars: Vec<Var> = fields
+        .into_iter()
+        .enumerate()
+        .map(|(n, (id, index, field))| {
+            let index = simulator.id_start_index[id] + index;
+            let signal = simulator.sim_state[index];
+            let bits = match (simulator.get_width(SignalIndex(index)), signal.get_value()) {
+                (Some(width), _) => width,
+                (None, SignalValue::Wide { width, .. }) => width,
+                (None, _) => signal.get_fmt().bits(),
+            };
+            Var {
+                id,
+                field,
+                index,
+                bits: bits.max(1),
+                code: code(n),
+            }
+        })
+        .collect();
+
+    writeln!(w, "$version SyncRim {} $end", env!("CARGO_PKG_VERSION"))?;
+    writeln!(w, "$timescale 1ns $end")?;
+    writeln!(w, "$scope module top $end")?;
+    let mut scope: Option<&Id> = None;
+    for var in &vars {
+        if scope != Some(var.id) {
+            if scope.is_some() {
+                writeln!(w, "$upscope $end")?;
+            }
+            writeln!(w, "$scope module {} $end", var.id.replace(' ', "_"))?;
+            scope = Some(var.id);
+        }
+        writeln!(
+            w,
+            "$var wire {} {} {} $end",
+            var.bits,
+            var.code,
+            var.field.replace(' ', "_")
+        )?;
+    }
+    if scope.is_some() {
+        writeln!(w, "$upscope $end")?;
+    }
+    writeln!(w, "$upscope $end")?;
+    writeln!(w, "$enddefinitions $end")?;
+
+    let states = simulator
+        .history
+        .iter()
+        .chain(std::iter::once(simulator.sim_state.clone()));
+    let mut prev: Option<Vec<Signal>> = None;
+    for (time, state) in (simulator.history.first()..).zip(states) {
+        match &prev {
+            None => {
+                writeln!(w, "#{}", time)?;
+                writeln!(w, "$dumpvars")?;
+                for var in &vars {
+                    write_value(w, var, &state[var.index])?;
+                }
+                writeln!(w, "$end")?;
+            }
+            Some(prev) => {
+                let mut changed = vars
+                    .iter()
+                    .filter(|var| prev[var.index].get_value() != state[var.index].get_value())
+                    .peekable();
+                if changed.peek().is_some() {
+                    writeln!(w, "#{}", time)?;
+                }
+                for var in changed {
+                    write_value(w, var, &state[var.index])?;
+                }
+            }
+        }
+        prev = Some(state);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::{ComponentStore, Input};
+    use crate::components::*;
+
+    #[test]
+    fn test_code() {
+        assert_eq!(code(0), "!");
+        assert_eq!(code(93), "~");
+        assert_eq!(code(94), "!!");
+        assert_eq!(code(95), "\"!");
+    }
+
+    #[test]
+    fn test_write_vcd() {
+        let cs = ComponentStore::new(vec![
+            ProbeStim::arc_new("stim", (0.0, 0.0), vec![1, 2, 2, 3]),
+            Register::arc_new("reg", (0.0, 0.0), Input::new("stim", "out")),
+        ]);
+        let mut simulator = Simulator::new(cs).unwrap();
+        for _ in 0..3 {
+            simulator.clock();
+        }
+
+        let mut vcd = vec![];
+        write_vcd(&simulator, &mut vcd).unwrap();
+        let vcd = String::from_utf8(vcd).unwrap();
+        let expected = "\
+$scope module top $end
+$scope module reg $end
+$var wire 32 ! out $end
+$upscope $end
+$scope module stim $end
+$var wire 32 \" out $end
+$upscope $end
+$upscope $end
+$enddefinitions $end
+#0
+$dumpvars
+b0 !
+b0 \"
+$end
+#1
+b1 \"
+#2
+b1 !
+b10 \"
+#3
+b10 !
+#4
+b11 \"
+";
+        assert!(vcd.ends_with(expected), "{}", vcd);
+    }
+}
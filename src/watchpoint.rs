@@ -0,0 +1,185 @@
+// Watchpoints, conditions on the simulator state checked after each clock
+//
+// A watchpoint is given as text on one of the forms:
+//
+//   add/out == 0x10     signal equals value
+//   add/out changes     signal changes value
+//   cycle >= 100        cycle reached
+//
+// Conditions stop the simulation when becoming true, not again while they hold.
+use crate::common::{Input, SignalValue, SignalWide, Simulator};
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Watchpoint {
+    /// Signal equals value (of any width)
+    Equals(Input, SignalWide),
+    /// Signal changes value
+    Changes(Input),
+    /// Cycle is at least
+    Cycle(usize),
+}
+
+impl Watchpoint {
+    /// watched signal, if any
+    pub fn signal(&self) -> Option<&Input> {
+        match self {
+            Watchpoint::Equals(input, _) | Watchpoint::Changes(input) => Some(input),
+            Watchpoint::Cycle(_) => None,
+        }
+    }
+
+    /// true if the condition holds in the current state, never for `Changes`
+    pub fn holds(&self, simulator: &Simulator) -> bool {
+        match self {
+            Watchpoint::Equals(input, value) => {
+                SignalWide::try_from(simulator.get_input_value(input)) == Ok(*value)
+            }
+            Watchpoint::Changes(_) => false,
+            Watchpoint::Cycle(cycle) => simulator.cycle >= *cycle,
+        }
+    }
+
+    /// check the watchpoint after a clock, given the value of the watched signal and
+    /// whether the condition held before the clock
+    ///
+    /// Conditions are hit when becoming true, rather than on each cycle they hold.
+    pub fn hit(&self, simulator: &Simulator, previous: Option<SignalValue>, held: bool) -> bool {
+        match self {
+            Watchpoint::Changes(input) => {
+                previous.is_some_and(|previous| previous != simulator.get_input_value(input))
+            }
+            _ => !held && self.holds(simulator),
+        }
+    }
+}
+
+impl fmt::Display for Watchpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Watchpoint::Equals(input, value) => write!(f, "{} == {:#x}", input, value),
+            Watchpoint::Changes(input) => write!(f, "{} changes", input),
+            Watchpoint::Cycle(cycle) => write!(f, "cycle >= {}", cycle),
+        }
+    }
+}
+
+impl FromStr for Watchpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens: Vec<&str> = s.split_whitespace().collect();
+        match tokens.as_slice() {
+            ["cycle", ">=", cycle] => {
+                Ok(Watchpoint::Cycle(cycle.parse().map_err(|_| {
+                    format!("Expected cycle, found {:?}", cycle)
+                })?))
+            }
+            [signal, "changes"] => Ok(Watchpoint::Changes(signal.parse()?)),
+            [signal, "==", value] => Ok(Watchpoint::Equals(signal.parse()?, parse_value(value)?)),
+            _ => Err(format!(
+                "Expected `id/field == value`, `id/field changes` or `cycle >= n`, found {:?}",
+                s
+            )),
+        }
+    }
+}
+
+// decimal, hexadecimal (0x) or binary (0b) value
+fn parse_value(s: &str) -> Result<SignalWide, String> {
+    let value = if let Some(hex) = s.strip_prefix("0x") {
+        SignalWide::from_str_radix(hex, 16)
+    } else if let Some(bin) = s.strip_prefix("0b") {
+        SignalWide::from_str_radix(bin, 2)
+    } else {
+        s.parse()
+    };
+    value.map_err(|_| format!("Expected value, found {:?}", s))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures::counter;
+
+    #[test]
+    fn test_parse() {
+        for s in ["add/out == 0x10", "add/out changes", "cycle >= 100"] {
+            let watchpoint: Watchpoint = s.parse().unwrap();
+            assert_eq!(watchpoint.to_string(), s);
+        }
+        assert_eq!(
+            "add/out == 0b101".parse(),
+            Ok(Watchpoint::Equals(Input::new("add", "out"), 5))
+        );
+        assert!("add/out".parse::<Watchpoint>().is_err());
+        assert!("add/out == x".parse::<Watchpoint>().is_err());
+    }
+
+    #[test]
+    fn test_run_until() {
+        let mut simulator = counter();
+        let cycles =
+            simulator.run_until(|s| s.get_input_value(&Input::new("r1", "out")) == 5.into());
+        assert_eq!(cycles, 5);
+        assert_eq!(simulator.cycle, 6);
+    }
+
+    #[test]
+    fn test_watchpoints() {
+        let mut simulator = counter();
+        assert!(simulator
+            .add_watchpoint("add/missing changes".parse().unwrap())
+            .is_err());
+        simulator
+            .add_watchpoint("add/out == 3".parse().unwrap())
+            .unwrap();
+        simulator
+            .add_watchpoint("cycle >= 10".parse().unwrap())
+            .unwrap();
+
+        // stops on the first watchpoint hit
+        simulator.run_until(|_| false);
+        assert_eq!(simulator.cycle, 3);
+        assert_eq!(simulator.watchpoint_hits(), &[0]);
+
+        simulator.remove_watchpoint(0);
+        simulator.run_until(|_| false);
+        assert_eq!(simulator.cycle, 10);
+        // not hit again while holding
+        simulator.clock();
+        assert!(simulator.watchpoint_hits().is_empty());
+        simulator.goto_cycle(9).unwrap();
+        simulator.clock();
+        assert_eq!(simulator.watchpoint_hits(), &[0]);
+
+        simulator.remove_watchpoint(0);
+        simulator
+            .add_watchpoint("c1/out changes".parse().unwrap())
+            .unwrap();
+        simulator
+            .add_watchpoint("r1/out changes".parse().unwrap())
+            .unwrap();
+        simulator.clock();
+        assert_eq!(simulator.watchpoint_hits(), &[1]);
+    }
+
+    #[test]
+    fn test_watchpoint_edges() {
+        let mut simulator = counter();
+        for watchpoint in ["c1/out == 1", "cycle >= 5", "add/out == 8"] {
+            simulator
+                .add_watchpoint(watchpoint.parse().unwrap())
+                .unwrap();
+        }
+        // hit when becoming true, not while holding or if holding from the start
+        let mut hits = vec![];
+        for _ in 0..12 {
+            simulator.clock();
+            if !simulator.watchpoint_hits().is_empty() {
+                hits.push((simulator.cycle, simulator.watchpoint_hits().to_vec()));
+            }
+        }
+        assert_eq!(hits, vec![(5, vec![1]), (8, vec![2])]);
+    }
+}
@@ -0,0 +1,16 @@
+// Runs the testbenches in `tests/testbench`
+use std::path::Path;
+use syncrim::testbench::Testbench;
+
+fn run(testbench: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/testbench")
+        .join(testbench);
+    let report = Testbench::load(&path).unwrap().run().unwrap();
+    assert!(report.passed(), "{}\n{}", path.display(), report);
+}
+
+#[test]
+fn test_counter() {
+    run("counter_tb.json");
+}